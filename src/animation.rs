@@ -0,0 +1,387 @@
+//! Terminal animations of the simulations, one frame per generation, to watch how the rules
+//! play out.
+
+use crate::params::Params;
+use crate::parsing::ParseError;
+use crate::{day11, day12, day17, day24};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// The colour of a cell, in the terminal's own palette.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Colour {
+    Plain,
+    Dim,
+    Red,
+    Green,
+    Yellow,
+    Cyan,
+}
+
+impl Colour {
+    fn ansi(self) -> &'static str {
+        match self {
+            Colour::Plain => "\x1b[0m",
+            Colour::Dim => "\x1b[2m",
+            Colour::Red => "\x1b[31m",
+            Colour::Green => "\x1b[32m",
+            Colour::Yellow => "\x1b[33m",
+            Colour::Cyan => "\x1b[36m",
+        }
+    }
+}
+
+pub type Cell = (char, Colour);
+
+/// A generation, as a title and rows of coloured characters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Frame {
+    pub title: String,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Frame {
+    pub fn new(title: impl Into<String>) -> Self {
+        Frame {
+            title: title.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds the cells as rows, filling the gaps of their bounding box with spaces.
+    pub fn grid(mut self, cells: impl IntoIterator<Item = ((i32, i32), Cell)>) -> Self {
+        let cells = cells.into_iter().collect::<Vec<_>>();
+        let (xs, ys) = cells
+            .iter()
+            .map(|&(position, _)| position)
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+
+        if let (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) = (
+            xs.iter().min(),
+            xs.iter().max(),
+            ys.iter().min(),
+            ys.iter().max(),
+        ) {
+            let width = (x_max - x_min + 1) as usize;
+            let mut rows = vec![vec![(' ', Colour::Plain); width]; (y_max - y_min + 1) as usize];
+
+            for ((x, y), cell) in cells {
+                rows[(y - y_min) as usize][(x - x_min) as usize] = cell;
+            }
+
+            self.rows.extend(rows);
+        }
+
+        self
+    }
+
+    /// Adds a row of plain text.
+    pub fn text(mut self, text: &str) -> Self {
+        self.rows
+            .push(text.chars().map(|c| (c, Colour::Plain)).collect());
+        self
+    }
+
+    /// The frame without colours nor trailing spaces.
+    pub fn to_plain(&self) -> String {
+        let mut plain = self.title.clone();
+
+        for row in &self.rows {
+            plain.push('\n');
+            plain.extend(row.iter().map(|&(c, _)| c));
+            plain.truncate(plain.trim_end_matches(' ').len());
+        }
+
+        plain.truncate(plain.trim_end().len());
+        plain
+    }
+
+    /// The frame with ANSI colour codes, only switching colours between cells that differ.
+    pub fn to_ansi(&self) -> String {
+        let mut ansi = format!("\x1b[1m{}\x1b[0m", self.title);
+
+        for row in &self.rows {
+            let mut colour = Colour::Plain;
+            ansi.push('\n');
+
+            for &(c, cell_colour) in row {
+                if cell_colour != colour {
+                    if colour != Colour::Plain {
+                        ansi.push_str(Colour::Plain.ansi());
+                    }
+
+                    if cell_colour != Colour::Plain {
+                        ansi.push_str(cell_colour.ansi());
+                    }
+
+                    colour = cell_colour;
+                }
+
+                ansi.push(c);
+            }
+
+            if colour != Colour::Plain {
+                ansi.push_str(Colour::Plain.ansi());
+            }
+        }
+
+        ansi
+    }
+}
+
+/// A simulation shown one generation at a time.
+pub trait Animation {
+    fn frame(&self) -> Frame;
+
+    /// Advances by one generation, returning whether anything changed.
+    fn step(&mut self) -> bool;
+}
+
+/// The parts with an animation.
+pub const PARTS: [(u8, u8); 7] = [
+    (11, 1),
+    (11, 2),
+    (12, 1),
+    (12, 2),
+    (17, 1),
+    (17, 2),
+    (24, 2),
+];
+
+/// The animation of the part's simulation on the input with `params`, or `None` if it has
+/// none.
+pub fn animation(
+    day: u8,
+    part: u8,
+    input: &str,
+    params: &Params,
+) -> Option<Result<Box<dyn Animation>, ParseError>> {
+    fn boxed<I, A: Animation + 'static>(
+        parsed: Result<I, ParseError>,
+        animation: impl FnOnce(&I) -> A,
+    ) -> Result<Box<dyn Animation>, ParseError> {
+        parsed.map(|parsed| Box::new(animation(&parsed)) as Box<dyn Animation>)
+    }
+
+    Some(match (day, part) {
+        (11, 1) => boxed(day11::parse(input), day11::part1_animation),
+        (11, 2) => boxed(day11::parse(input), day11::part2_animation),
+        (12, 1) => boxed(day12::parse(input), |instructions| {
+            day12::part1_animation(instructions)
+        }),
+        (12, 2) => boxed(day12::parse(input), |instructions| {
+            day12::part2_animation(instructions)
+        }),
+        (17, 1) => boxed(day17::parse(input), |cubes| {
+            day17::part1_animation(cubes, &params.day17)
+        }),
+        (17, 2) => boxed(day17::parse(input), |cubes| {
+            day17::part2_animation(cubes, &params.day17)
+        }),
+        (24, 2) => boxed(day24::parse(input), |directions| {
+            day24::part2_animation(directions, &params.day24)
+        }),
+        _ => return None,
+    })
+}
+
+/// The slowest and fastest speeds of a player, in generations per second.
+pub const SPEEDS: (f64, f64) = (0.25, 1_000.0);
+
+/// Plays animations on a terminal, or prints their frames one after the other.
+pub struct Player {
+    /// Generations per second.
+    pub speed: f64,
+    /// Whether to redraw the frames in place with colours rather than print them as text.
+    pub ansi: bool,
+}
+
+impl Player {
+    /// Plays the animation to its end, or until quit with `q` if `keys` are given. Space
+    /// pauses or resumes it, `n` steps one generation, and `+` and `-` double or halve the
+    /// speed.
+    pub fn play(
+        &self,
+        animation: &mut dyn Animation,
+        keys: Option<&Receiver<u8>>,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let mut speed = self.speed;
+        let (mut paused, mut finished) = (false, false);
+
+        if self.ansi {
+            write!(out, "\x1b[?25l")?;
+        }
+
+        loop {
+            self.draw(animation, speed, paused, finished, keys.is_some(), out)?;
+
+            let delay = Duration::from_secs_f64(1.0 / speed);
+            let key = match keys {
+                Some(keys) if paused || finished => match keys.recv() {
+                    Ok(key) => Some(key),
+                    Err(_) => break,
+                },
+                Some(keys) => match keys.recv_timeout(delay) {
+                    Ok(key) => Some(key),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                },
+                None if finished => break,
+                None => {
+                    if self.ansi {
+                        thread::sleep(delay);
+                    }
+
+                    None
+                }
+            };
+
+            let advance = match key {
+                None => true,
+                Some(b' ') => {
+                    paused = !paused;
+                    false
+                }
+                Some(b'n') | Some(b'.') => {
+                    paused = true;
+                    true
+                }
+                Some(b'+') | Some(b'=') => {
+                    speed = (speed * 2.0).min(SPEEDS.1);
+                    false
+                }
+                Some(b'-') => {
+                    speed = (speed / 2.0).max(SPEEDS.0);
+                    false
+                }
+                Some(b'q') | Some(3) => break,
+                Some(_) => false,
+            };
+
+            if advance && !finished {
+                finished = !animation.step();
+            }
+        }
+
+        if self.ansi {
+            write!(out, "\x1b[?25h")?;
+        }
+
+        out.flush()
+    }
+
+    fn draw(
+        &self,
+        animation: &dyn Animation,
+        speed: f64,
+        paused: bool,
+        finished: bool,
+        interactive: bool,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let frame = animation.frame();
+
+        if !self.ansi {
+            // The last step changed nothing, and so did not make a new frame.
+            return if finished {
+                Ok(())
+            } else {
+                writeln!(out, "{}\n", frame.to_plain())
+            };
+        }
+
+        let mut screen = format!("\x1b[H\x1b[2J{}\n\n", frame.to_ansi());
+        let state = match (finished, paused) {
+            (true, _) => "finished",
+            (false, true) => "paused",
+            (false, false) => "playing",
+        };
+        write!(screen, "\x1b[2m{} at {}/s", state, speed).unwrap();
+
+        if interactive {
+            screen.push_str(" - space: play/pause, n: step, +/-: speed, q: quit");
+        }
+
+        writeln!(out, "{}\x1b[0m", screen)?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    struct Countdown(usize);
+
+    impl Animation for Countdown {
+        fn frame(&self) -> Frame {
+            Frame::new(format!("{} left", self.0))
+                .grid((0..self.0 as i32).map(|x| ((x, 0), ('#', Colour::Red))))
+        }
+
+        fn step(&mut self) -> bool {
+            let changed = self.0 > 0;
+            self.0 = self.0.saturating_sub(1);
+            changed
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let frame = Frame::new("title")
+            .grid(vec![
+                ((0, 0), ('#', Colour::Red)),
+                ((2, 1), ('L', Colour::Green)),
+            ])
+            .text("z=1");
+
+        assert_eq!(frame.to_plain(), "title\n#\n  L\nz=1");
+        assert_eq!(
+            frame.to_ansi(),
+            "\x1b[1mtitle\x1b[0m\n\x1b[31m#\x1b[0m  \n  \x1b[32mL\x1b[0m\nz=1"
+        );
+    }
+
+    #[test]
+    fn play() {
+        let player = Player {
+            speed: 1_000.0,
+            ansi: false,
+        };
+        let mut out = Vec::new();
+
+        player.play(&mut Countdown(3), None, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "3 left\n###\n\n2 left\n##\n\n1 left\n#\n\n0 left\n\n"
+        );
+
+        let (sender, keys) = mpsc::channel();
+        let mut countdown = Countdown(10);
+
+        for &key in b" nnq" {
+            sender.send(key).unwrap();
+        }
+
+        player
+            .play(&mut countdown, Some(&keys), &mut Vec::new())
+            .unwrap();
+
+        assert_eq!(countdown.0, 8);
+    }
+
+    #[test]
+    fn animations() {
+        let params = Params::default();
+
+        assert!(animation(11, 1, "L.\nLL", &params).unwrap().is_ok());
+        assert!(animation(24, 2, "x", &params).unwrap().is_err());
+        assert!(animation(1, 1, "1", &params).is_none());
+    }
+}
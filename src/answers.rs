@@ -0,0 +1,189 @@
+//! A local store of known answers, keyed by the hash of the input, the day and the part, to
+//! check that the solvers still find them.
+
+use crate::differential::{self, Outcome};
+use crate::registry;
+use crate::solver::Metadata;
+use std::collections::BTreeMap;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::{fmt, fs};
+
+/// The 64 bit FNV-1a hash of `input`, which unlike the standard library's hashers is the same
+/// across Rust versions and platforms.
+pub fn input_hash(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The answers, saved one per line as `<day> <part> <input hash> <answer>`, separated by tabs.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AnswerStore(BTreeMap<(u8, u8, u64), String>);
+
+impl AnswerStore {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
+                let fields = line.splitn(4, '\t').collect::<Vec<_>>();
+
+                match fields[..] {
+                    [day, part, hash, answer] => Ok((
+                        (
+                            day.parse()
+                                .map_err(|_| format!("line {}: bad day", i + 1))?,
+                            part.parse()
+                                .map_err(|_| format!("line {}: bad part", i + 1))?,
+                            u64::from_str_radix(hash, 16)
+                                .map_err(|_| format!("line {}: bad input hash", i + 1))?,
+                        ),
+                        answer.to_string(),
+                    )),
+                    _ => Err(format!("line {}: expected 4 tab-separated fields", i + 1)),
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(AnswerStore)
+    }
+
+    /// Loads the store saved at `path`, or an empty one if there is none yet.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                Self::parse(&text).map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, day: u8, part: u8, input: &str) -> Option<&str> {
+        self.0
+            .get(&(day, part, input_hash(input)))
+            .map(String::as_str)
+    }
+
+    /// Records the answer to the part for `input`, returning the one it replaces, if any.
+    pub fn record(&mut self, day: u8, part: u8, input: &str, answer: String) -> Option<String> {
+        self.0.insert((day, part, input_hash(input)), answer)
+    }
+}
+
+impl fmt::Display for AnswerStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, part, hash), answer) in &self.0 {
+            writeln!(f, "{}\t{}\t{:016x}\t{}", day, part, hash, answer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Correct,
+    /// There is no known answer for this input.
+    Unknown,
+    Regression {
+        expected: String,
+    },
+}
+
+pub struct Check {
+    pub metadata: &'static Metadata,
+    pub outcome: Outcome,
+    pub verdict: Verdict,
+}
+
+/// Runs every variant of the part of `day` on `input`, checking their answers against the known
+/// ones.
+pub fn verify(store: &AnswerStore, day: u8, part: u8, input: &str) -> Vec<Check> {
+    registry()
+        .variants(day, part)
+        .map(|solver| {
+            let metadata = solver.metadata();
+            let outcome = differential::outcome(solver, input);
+            let verdict = match (store.get(day, metadata.part, input), &outcome) {
+                (None, _) => Verdict::Unknown,
+                (Some(expected), Ok(answer)) if expected == answer => Verdict::Correct,
+                (Some(expected), _) => Verdict::Regression {
+                    expected: expected.to_string(),
+                },
+            };
+
+            Check {
+                metadata,
+                outcome,
+                verdict,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::example;
+
+    #[test]
+    fn store() {
+        let mut store = AnswerStore::default();
+
+        assert_eq!(
+            store.record(1, 1, example(1, "example"), "514579".to_string()),
+            None
+        );
+        assert_eq!(
+            store.record(1, 2, example(1, "example"), "1".to_string()),
+            None
+        );
+        assert_eq!(
+            store.record(1, 2, example(1, "example"), "241861950".to_string()),
+            Some("1".to_string())
+        );
+        assert_eq!(store.get(1, 1, example(1, "example")), Some("514579"));
+        assert_eq!(store.get(1, 1, "1721\n299"), None);
+        assert_eq!(AnswerStore::parse(&store.to_string()), Ok(store));
+        assert!(AnswerStore::parse("1\t1\tnot a hash\t2").is_err());
+        assert_eq!(input_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(input_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn regressions() {
+        let mut store = AnswerStore::default();
+        store.record(1, 1, example(1, "example"), "514579".to_string());
+        store.record(1, 2, example(1, "example"), "0".to_string());
+
+        let checks = (1..=2)
+            .flat_map(|part| verify(&store, 1, part, example(1, "example")))
+            .collect::<Vec<_>>();
+
+        assert_eq!(checks.len(), 8);
+        assert!(checks.iter().all(|check| match check.metadata.part {
+            1 => check.verdict == Verdict::Correct,
+            _ =>
+                check.verdict
+                    == Verdict::Regression {
+                        expected: "0".to_string()
+                    },
+        }));
+        assert!(verify(&AnswerStore::default(), 1, 1, example(1, "example"))
+            .iter()
+            .all(|check| check.verdict == Verdict::Unknown));
+    }
+}
@@ -0,0 +1,410 @@
+//! A cellular automaton engine, generic over cell states, topologies and transition rules.
+
+use crate::geometry::{Vector, Vector3};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Which cells neighbour which.
+pub trait Topology {
+    type Position: Copy + Eq + Hash;
+
+    fn neighbours(&self, position: Self::Position) -> impl Iterator<Item = Self::Position> + '_;
+}
+
+/// The `N`-dimensional cubic grid, each cell touching the `3^N - 1` cells around it; the
+/// square grid with diagonals for `N = 2`.
+pub struct Cubic<const N: usize>;
+
+impl<const N: usize> Topology for Cubic<N> {
+    type Position = Vector<N>;
+
+    fn neighbours(&self, position: Self::Position) -> impl Iterator<Item = Self::Position> + '_ {
+        position.neighbours()
+    }
+}
+
+/// The hexagonal grid, in cube coordinates.
+pub struct Hex;
+
+impl Topology for Hex {
+    type Position = Vector3;
+
+    fn neighbours(&self, position: Self::Position) -> impl Iterator<Item = Self::Position> + '_ {
+        position.hex_neighbours()
+    }
+}
+
+/// An arbitrary neighbourhood, listing the neighbours of every cell.
+pub struct Graph<P>(pub HashMap<P, Vec<P>>);
+
+impl<P: Copy + Eq + Hash> Topology for Graph<P> {
+    type Position = P;
+
+    fn neighbours(&self, position: Self::Position) -> impl Iterator<Item = Self::Position> + '_ {
+        self.0.get(&position).into_iter().flatten().copied()
+    }
+}
+
+/// The next state of a cell, from its state and the states of its neighbours.
+pub trait Rule<S> {
+    fn next(&self, state: S, neighbours: &mut dyn Iterator<Item = S>) -> S;
+}
+
+impl<S, F> Rule<S> for F
+where
+    F: Fn(S, &mut dyn Iterator<Item = S>) -> S,
+{
+    fn next(&self, state: S, neighbours: &mut dyn Iterator<Item = S>) -> S {
+        self(state, neighbours)
+    }
+}
+
+/// A rule in the style of the Game of Life: a `dead` cell becomes `live` if its count of live
+/// neighbours is in `birth`, and a live one stays live only if it is in `survival`. Cells in
+/// any other state never change.
+pub struct LifeLike<S: 'static> {
+    pub live: S,
+    pub dead: S,
+    pub birth: &'static [usize],
+    pub survival: &'static [usize],
+}
+
+impl<S: Copy + Eq> Rule<S> for LifeLike<S> {
+    fn next(&self, state: S, neighbours: &mut dyn Iterator<Item = S>) -> S {
+        if state != self.live && state != self.dead {
+            return state;
+        }
+
+        let live = neighbours
+            .filter(|&neighbour| neighbour == self.live)
+            .count();
+
+        match (
+            state == self.live,
+            self.birth.contains(&live),
+            self.survival.contains(&live),
+        ) {
+            (false, true, _) | (true, _, true) => self.live,
+            _ => self.dead,
+        }
+    }
+}
+
+/// The generations at which an automaton first repeats a configuration.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+pub struct Automaton<T: Topology, S, R> {
+    cells: HashMap<T::Position, S>,
+    topology: T,
+    rule: R,
+    background: Option<S>,
+    generation: usize,
+    changes: usize,
+}
+
+impl<T, S, R> Automaton<T, S, R>
+where
+    T: Topology,
+    S: Copy + Eq,
+    R: Rule<S>,
+{
+    /// An automaton limited to the given cells, ignoring neighbours outside of them.
+    pub fn bounded(cells: HashMap<T::Position, S>, topology: T, rule: R) -> Self {
+        Automaton {
+            cells,
+            topology,
+            rule,
+            background: None,
+            generation: 0,
+            changes: 0,
+        }
+    }
+
+    /// An automaton over the whole topology, with every cell not given in the `background`
+    /// state.
+    pub fn unbounded(cells: HashMap<T::Position, S>, topology: T, rule: R, background: S) -> Self {
+        Automaton {
+            cells,
+            topology,
+            rule,
+            background: Some(background),
+            generation: 0,
+            changes: 0,
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The number of cells that changed during the last step.
+    pub fn changes(&self) -> usize {
+        self.changes
+    }
+
+    pub fn get(&self, position: T::Position) -> Option<S> {
+        self.cells.get(&position).copied().or(self.background)
+    }
+
+    /// The cells that are not in the background state.
+    pub fn cells(&self) -> impl Iterator<Item = (T::Position, S)> + '_ {
+        self.cells
+            .iter()
+            .filter(move |(_, &state)| Some(state) != self.background)
+            .map(|(&position, &state)| (position, state))
+    }
+
+    pub fn count(&self, state: S) -> usize {
+        self.cells().filter(|&(_, other)| other == state).count()
+    }
+
+    /// Updates every cell at once, returning whether any of them changed.
+    pub fn step(&mut self) -> bool {
+        if let Some(background) = self.background {
+            self.cells.retain(|_, state| *state != background);
+
+            let frontier = self
+                .cells
+                .keys()
+                .flat_map(|&position| self.topology.neighbours(position))
+                .filter(|position| !self.cells.contains_key(position))
+                .collect::<Vec<_>>();
+
+            for position in frontier {
+                self.cells.insert(position, background);
+            }
+        }
+
+        let changes = self
+            .cells
+            .iter()
+            .filter_map(|(&position, &state)| {
+                let mut neighbours = self
+                    .topology
+                    .neighbours(position)
+                    .filter_map(|neighbour| self.get(neighbour));
+                let next = self.rule.next(state, &mut neighbours);
+
+                Some((position, next)).filter(|_| next != state)
+            })
+            .collect::<Vec<_>>();
+
+        self.changes = changes.len();
+        self.cells.extend(changes);
+        self.generation += 1;
+
+        self.changes > 0
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until nothing changes, returning the generation of the fixed point, or `None` if
+    /// there is none within `max_generations` steps.
+    pub fn run_to_fixed_point(&mut self, max_generations: usize) -> Option<usize> {
+        for _ in 0..max_generations {
+            if !self.step() {
+                return Some(self.generation - 1);
+            }
+        }
+
+        None
+    }
+}
+
+impl<T, S, R> Automaton<T, S, R>
+where
+    T: Topology,
+    S: Copy + Eq + Hash,
+    R: Rule<S>,
+{
+    fn fingerprint(&self) -> u64 {
+        self.cells()
+            .map(|cell| {
+                let mut hasher = DefaultHasher::new();
+                cell.hash(&mut hasher);
+                hasher.finish()
+            })
+            .fold(0, u64::wrapping_add)
+    }
+
+    fn snapshot(&self) -> HashMap<T::Position, S> {
+        self.cells().collect()
+    }
+
+    /// Steps until a configuration repeats, or `None` if none does within `max_generations`
+    /// steps. A fixed point is a cycle with a period of 1.
+    pub fn find_cycle(&mut self, max_generations: usize) -> Option<Cycle> {
+        self.find_cycle_with(max_generations, |_| {})
+    }
+
+    /// Same as [`Automaton::find_cycle`], calling `on_step` after every step.
+    ///
+    /// A fixed point is found as soon as a step changes nothing. Otherwise only the
+    /// fingerprints of the configurations are kept, and the first one repeated is confirmed
+    /// by a snapshot of the configuration coming back, before the start of the cycle is found
+    /// among the fingerprints.
+    pub fn find_cycle_with(
+        &mut self,
+        max_generations: usize,
+        mut on_step: impl FnMut(&Self),
+    ) -> Option<Cycle> {
+        let first = self.generation;
+        let mut fingerprints = vec![self.fingerprint()];
+        let mut seen = HashMap::from([(fingerprints[0], first)]);
+        // A configuration seen before if its fingerprint is to be trusted, with the generation
+        // it should come back by.
+        let mut candidate: Option<(usize, usize, HashMap<T::Position, S>)> = None;
+
+        for _ in 0..max_generations {
+            let changed = self.step();
+            on_step(self);
+
+            if !changed {
+                return Some(Cycle {
+                    start: self.generation - 1,
+                    period: 1,
+                });
+            }
+
+            let fingerprint = self.fingerprint();
+            fingerprints.push(fingerprint);
+
+            if let Some((generation, deadline, snapshot)) = &candidate {
+                if fingerprint == fingerprints[generation - first] && self.snapshot() == *snapshot {
+                    let period = self.generation - generation;
+                    let start = (first..)
+                        .find(|&g| fingerprints[g - first] == fingerprints[g + period - first])
+                        .unwrap();
+
+                    return Some(Cycle { start, period });
+                }
+
+                if self.generation >= *deadline {
+                    candidate = None;
+                }
+            }
+
+            let earlier = *seen.entry(fingerprint).or_insert(self.generation);
+
+            if earlier < self.generation && candidate.is_none() {
+                let deadline = 2 * self.generation - earlier;
+                candidate = Some((self.generation, deadline, self.snapshot()));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONWAY: LifeLike<bool> = LifeLike {
+        live: true,
+        dead: false,
+        birth: &[3],
+        survival: &[2, 3],
+    };
+
+    fn cells(live: &[(i32, i32)]) -> HashMap<Vector<2>, bool> {
+        live.iter()
+            .map(|&position| (position.into(), true))
+            .collect()
+    }
+
+    #[test]
+    fn blinker() {
+        let mut automaton =
+            Automaton::unbounded(cells(&[(0, -1), (0, 0), (0, 1)]), Cubic, CONWAY, false);
+
+        automaton.run(1);
+
+        assert_eq!(automaton.generation(), 1);
+        assert_eq!(automaton.count(true), 3);
+        assert_eq!(automaton.get((1, 0).into()), Some(true));
+        assert_eq!(automaton.get((0, 1).into()), Some(false));
+        assert_eq!(
+            automaton.find_cycle(10),
+            Some(Cycle {
+                start: 1,
+                period: 2
+            })
+        );
+    }
+
+    #[test]
+    fn cycle_after_a_transient() {
+        // The T-tetromino turns into four blinkers, the traffic light.
+        let tetromino = cells(&[(-1, 0), (0, 0), (1, 0), (0, 1)]);
+        let mut automaton = Automaton::unbounded(tetromino.clone(), Cubic, CONWAY, false);
+        let mut history = vec![automaton.cells().collect::<HashMap<_, _>>()];
+        let expected = loop {
+            automaton.step();
+            let cells = automaton.cells().collect::<HashMap<_, _>>();
+
+            if let Some(start) = history.iter().position(|other| *other == cells) {
+                break Cycle {
+                    start,
+                    period: history.len() - start,
+                };
+            }
+
+            history.push(cells);
+        };
+
+        let mut automaton = Automaton::unbounded(tetromino, Cubic, CONWAY, false);
+        let mut steps = 0;
+
+        assert_eq!(
+            automaton.find_cycle_with(100, |_| steps += 1),
+            Some(expected)
+        );
+        assert_eq!(expected.period, 2);
+        assert_eq!(automaton.generation(), steps);
+        assert_eq!(
+            Automaton::unbounded(cells(&[(0, 0), (1, 0), (0, 1)]), Cubic, CONWAY, false)
+                .find_cycle(10),
+            Some(Cycle {
+                start: 1,
+                period: 1
+            })
+        );
+        assert_eq!(automaton.find_cycle(0), None);
+    }
+
+    #[test]
+    fn fixed_point() {
+        let block = cells(&[(0, 0), (0, 1), (1, 0), (1, 1)]);
+        let mut automaton = Automaton::unbounded(block, Cubic, CONWAY, false);
+
+        assert_eq!(automaton.run_to_fixed_point(10), Some(0));
+
+        let mut automaton = Automaton::bounded(cells(&[(0, 0), (1, 0)]), Cubic, CONWAY);
+
+        assert_eq!(automaton.run_to_fixed_point(10), Some(1));
+        assert_eq!(automaton.cells().count(), 2);
+        assert_eq!(automaton.count(true), 0);
+    }
+
+    #[test]
+    fn custom_rule() {
+        let graph = Graph([(0, vec![1]), (1, vec![0])].iter().cloned().collect());
+        let swap = |_: u8, neighbours: &mut dyn Iterator<Item = u8>| neighbours.next().unwrap();
+        let mut automaton =
+            Automaton::bounded([(0, 1), (1, 2)].iter().cloned().collect(), graph, swap);
+
+        automaton.run(3);
+
+        assert_eq!((automaton.get(0), automaton.get(1)), (Some(2), Some(1)));
+    }
+}
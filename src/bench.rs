@@ -0,0 +1,189 @@
+//! Benchmarks of the registered solvers, timing parsing and solving separately.
+
+use crate::solver::{Error, Metadata, Solver};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// Statistics over the timings of repeated runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+
+        let seconds = samples.iter().map(Duration::as_secs_f64);
+        let mean = seconds.clone().sum::<f64>() / n as f64;
+        let variance = seconds.map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Stats {
+            samples: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    /// Runs per second, based on the median.
+    pub fn throughput(&self) -> f64 {
+        1.0 / self.median.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    pub metadata: &'static Metadata,
+    pub input_len: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+impl Benchmark {
+    /// Parsed input bytes per second, based on the median.
+    pub fn parse_bytes_per_second(&self) -> f64 {
+        self.input_len as f64 * self.parse.throughput()
+    }
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Parses and solves `input` `iterations` times after a warm-up run, failing on the first error.
+pub fn bench(
+    solver: &'static dyn Solver,
+    input: &str,
+    iterations: usize,
+) -> Result<Benchmark, Error> {
+    let iterations = iterations.max(1);
+    let parsed = solver.parse(input)?;
+    solver.solve(&parsed)?;
+
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut solve_samples = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let (parsed, parse_time) = time(|| solver.parse(input));
+        let parsed = parsed?;
+        let (answer, solve_time) = time(|| solver.solve(&parsed));
+        answer?;
+
+        parse_samples.push(parse_time);
+        solve_samples.push(solve_time);
+    }
+
+    Ok(Benchmark {
+        metadata: solver.metadata(),
+        input_len: input.len(),
+        parse: Stats::new(parse_samples),
+        solve: Stats::new(solve_samples),
+    })
+}
+
+/// Formats the benchmarks as a table, comparing each variant to the fastest one of its part.
+pub fn comparison_table(benchmarks: &[Benchmark]) -> String {
+    let mut table = format!(
+        "{:<9} {:<10} {:>10} {:>10} {:>10} {:>8} {:>10} {:>10} {:>10} {:>12} {:>8}\n",
+        "part",
+        "variant",
+        "parse min",
+        "median",
+        "stddev",
+        "MB/s",
+        "solve min",
+        "median",
+        "stddev",
+        "solves/s",
+        "relative"
+    );
+
+    for benchmark in benchmarks {
+        let metadata = benchmark.metadata;
+        let fastest = benchmarks
+            .iter()
+            .filter(|other| {
+                (other.metadata.day, other.metadata.part) == (metadata.day, metadata.part)
+            })
+            .map(|other| other.solve.median)
+            .min()
+            .unwrap_or(benchmark.solve.median);
+
+        writeln!(
+            table,
+            "{:<9} {:<10} {:>10.2?} {:>10.2?} {:>10.2?} {:>8.1} {:>10.2?} {:>10.2?} {:>10.2?} {:>12.1} {:>7.2}x",
+            format!("{}/{}", metadata.day, metadata.part),
+            metadata.variant_name(),
+            benchmark.parse.min,
+            benchmark.parse.median,
+            benchmark.parse.stddev,
+            benchmark.parse_bytes_per_second() / 1e6,
+            benchmark.solve.min,
+            benchmark.solve.median,
+            benchmark.solve.stddev,
+            benchmark.solve.throughput(),
+            benchmark.solve.median.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON)
+        )
+        .unwrap();
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::example;
+    use crate::registry;
+
+    #[test]
+    fn stats() {
+        let stats = Stats::new(
+            [4, 1, 3, 2]
+                .iter()
+                .map(|&ms| Duration::from_millis(ms))
+                .collect(),
+        );
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2_500));
+        assert_eq!(stats.mean, Duration::from_micros(2_500));
+        assert_eq!(stats.stddev.as_micros(), 1_118);
+        assert_eq!(stats.throughput().round(), 400.0);
+    }
+
+    #[test]
+    fn compare_variants() {
+        let input = example(1, "example");
+        let benchmarks = registry()
+            .variants(1, 1)
+            .map(|solver| bench(solver, input, 3).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(benchmarks
+            .iter()
+            .all(|benchmark| benchmark.solve.samples == 3));
+
+        let table = comparison_table(&benchmarks);
+
+        assert_eq!(table.lines().count(), 5);
+        assert!(table.contains("Itertools"));
+        assert!(bench(registry().find(1, 1, None).unwrap(), "1\n2", 3).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Running every day at once on a pool of workers.
+
+use super::options::{Format, Options};
+use super::output::{json_line, Outcome};
+use super::{inputs, solvers};
+use advent_of_code_2020::executor::{self, Report, Task};
+use serde::Serialize;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The report of a part, as a line of JSON.
+#[derive(Serialize)]
+struct ReportJson<'a> {
+    day: u8,
+    part: u8,
+    variant: &'a str,
+    #[serde(flatten)]
+    outcome: Outcome<'a>,
+    parse_ns: u128,
+    solve_ns: u128,
+    worker: usize,
+}
+
+impl<'a> From<&'a Report> for ReportJson<'a> {
+    fn from(report: &'a Report) -> Self {
+        let metadata = report.metadata;
+
+        ReportJson {
+            day: metadata.day,
+            part: metadata.part,
+            variant: metadata.variant_name(),
+            outcome: Outcome::from(&report.answer),
+            parse_ns: report.parse_time.as_nanos(),
+            solve_ns: report.solve_time.as_nanos(),
+            worker: report.worker,
+        }
+    }
+}
+
+/// The totals of a run of every day, as a line of JSON.
+#[derive(Serialize)]
+struct Totals {
+    parts: usize,
+    failures: usize,
+    busy_ns: u128,
+    wall_ns: u128,
+    workers: usize,
+}
+
+fn report_text(report: &Report) -> String {
+    let metadata = report.metadata;
+
+    format!(
+        "{:<9} {:<10} {:<20} {:>10.2?} {:>10.2?} {:>6}",
+        format!("{}/{}", metadata.day, metadata.part),
+        metadata.variant_name(),
+        match &report.answer {
+            Ok(answer) => answer.clone(),
+            Err(error) => format!("error: {}", error),
+        },
+        report.parse_time,
+        report.solve_time,
+        report.worker
+    )
+}
+
+/// Runs the selected parts of every day on a pool of workers, printing the table of results
+/// in the order they finish, then the totals.
+pub fn all(options: &Options) -> Result<bool, String> {
+    let mut tasks = Vec::new();
+
+    for (day, part, input) in inputs(options)? {
+        let input = Arc::<str>::from(input);
+
+        for solver in solvers(day, options, false)?
+            .into_iter()
+            .filter(|solver| solver.metadata().part == part)
+        {
+            tasks.push(Task {
+                solver,
+                input: input.clone(),
+            });
+        }
+    }
+
+    let workers = options
+        .workers
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |workers| workers.get()));
+
+    if options.format == Format::Text {
+        outln!(
+            "{:<9} {:<10} {:<20} {:>10} {:>10} {:>6}",
+            "part",
+            "variant",
+            "answer",
+            "parse",
+            "solve",
+            "worker"
+        );
+    }
+
+    let start = Instant::now();
+    let reports = executor::execute(tasks, workers, |report| match options.format {
+        Format::Text => outln!("{}", report_text(report)),
+        Format::Json => outln!("{}", json_line(&ReportJson::from(report))),
+    });
+    let wall_time = start.elapsed();
+
+    let failures = reports
+        .iter()
+        .filter(|report| report.answer.is_err())
+        .count();
+    let busy_time = reports
+        .iter()
+        .map(|report| report.parse_time + report.solve_time)
+        .sum::<Duration>();
+
+    match options.format {
+        Format::Text => outln!(
+            "{} parts, {} failed, {:.2?} of work in {:.2?} on {} workers",
+            reports.len(),
+            failures,
+            busy_time,
+            wall_time,
+            workers
+        ),
+        Format::Json => outln!(
+            "{}",
+            json_line(&Totals {
+                parts: reports.len(),
+                failures,
+                busy_ns: busy_time.as_nanos(),
+                wall_ns: wall_time.as_nanos(),
+                workers,
+            })
+        ),
+    }
+
+    Ok(failures == 0)
+}
@@ -0,0 +1,95 @@
+//! Animating the simulations on the terminal.
+
+use super::options::Options;
+use super::{params, read_input};
+use advent_of_code_2020::animation::{self, Player};
+use std::fs::File;
+use std::io::{self, IsTerminal, Read};
+use std::process::{Command as Shell, Stdio};
+use std::sync::mpsc;
+use std::thread;
+
+/// The terminal in raw mode, until dropped, so that the keys are read as soon as pressed.
+struct RawTerminal {
+    settings: String,
+}
+
+impl RawTerminal {
+    fn stty(args: &[&str]) -> Option<String> {
+        let output = Shell::new("stty")
+            .args(args)
+            .stdin(File::open("/dev/tty").ok()?)
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|_| output.status.success())
+    }
+
+    /// Switches the controlling terminal to raw mode and reads its keys on a thread, or returns
+    /// `None` if there is no terminal to control.
+    fn keys() -> Option<(Self, mpsc::Receiver<u8>)> {
+        let mut tty = File::open("/dev/tty").ok()?;
+        let settings = Self::stty(&["-g"])?;
+        Self::stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+
+        let (sender, keys) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut key = [0];
+
+            while tty.read_exact(&mut key).is_ok() && sender.send(key[0]).is_ok() {}
+        });
+
+        Some((RawTerminal { settings }, keys))
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        Self::stty(&[&self.settings]);
+    }
+}
+
+pub fn animate(day: u8, options: &Options) -> Result<bool, String> {
+    let part = animation::PARTS
+        .iter()
+        .find(|&&(d, part)| d == day && options.part.is_none_or(|p| p == part))
+        .map(|&(_, part)| part)
+        .ok_or_else(|| {
+            format!(
+                "no animation for day {}{}",
+                day,
+                options
+                    .part
+                    .map_or(String::new(), |part| format!(" part {}", part))
+            )
+        })?;
+
+    let input = read_input(day, options)?;
+    let params = params(options)?.unwrap_or_default();
+    let mut animation = animation::animation(day, part, &input, &params)
+        .unwrap()
+        .map_err(|error| error.to_string())?;
+
+    let ansi = io::stdout().is_terminal();
+    let terminal = Some(()).filter(|_| ansi).and_then(|_| RawTerminal::keys());
+    let player = Player {
+        speed: options.speed as f64,
+        ansi,
+    };
+
+    player
+        .play(
+            animation.as_mut(),
+            terminal.as_ref().map(|(_, keys)| keys),
+            &mut io::stdout().lock(),
+        )
+        .or_else(|error| match error.kind() {
+            io::ErrorKind::BrokenPipe => Ok(()),
+            _ => Err(error.to_string()),
+        })?;
+
+    Ok(true)
+}
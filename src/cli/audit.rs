@@ -0,0 +1,60 @@
+//! Auditing the passwords of day 2 under their policies.
+
+use super::options::{Format, Options};
+use super::output::json_line;
+use super::read_input;
+use advent_of_code_2020::day2::{self, AuditRecord, Policies};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+fn audit_text(record: &AuditRecord) -> String {
+    let check = &record.check;
+
+    format!(
+        "line {} ({}): {}, {}, observed {}",
+        record.line,
+        record.policy,
+        if check.valid { "valid" } else { "invalid" },
+        check.rule,
+        check.observed
+    )
+}
+
+pub fn audit(options: &Options) -> Result<bool, String> {
+    let input = read_input(2, options)?;
+    let entries = day2::parse(&input).map_err(|error| error.to_string())?;
+    let policies = match &options.policies {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| Policies::parse(&text))
+            .map_err(|error| format!("{}: {}", path, error))?,
+        None => Policies::default(),
+    };
+    let records = policies.audit(&entries, &options.policy)?;
+
+    let Some(output) = options.output.as_deref() else {
+        for record in &records {
+            match options.format {
+                Format::Text => outln!("{}", audit_text(record)),
+                Format::Json => outln!("{}", json_line(record)),
+            }
+        }
+
+        return Ok(true);
+    };
+
+    let mut file =
+        BufWriter::new(File::create(output).map_err(|error| format!("{}: {}", output, error))?);
+
+    if output.ends_with(".csv") {
+        day2::write_csv(&records, &mut file)
+    } else {
+        serde_json::to_writer_pretty(&mut file, &records)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(file))
+    }
+    .and_then(|()| file.flush())
+    .map_err(|error| format!("{}: {}", output, error))?;
+
+    Ok(true)
+}
@@ -0,0 +1,89 @@
+//! Benchmarking the solvers, printing the table comparing them or a JSON line for each.
+
+use super::inputs;
+use super::options::{Format, Options};
+use super::output::json_line;
+use super::solvers;
+use advent_of_code_2020::bench::{self, Benchmark, Stats};
+use serde::Serialize;
+
+/// The statistics of the samples of a benchmark, as JSON.
+#[derive(Serialize)]
+struct StatsJson {
+    samples: usize,
+    min_ns: u128,
+    median_ns: u128,
+    mean_ns: u128,
+    stddev_ns: u128,
+}
+
+impl From<&Stats> for StatsJson {
+    fn from(stats: &Stats) -> Self {
+        StatsJson {
+            samples: stats.samples,
+            min_ns: stats.min.as_nanos(),
+            median_ns: stats.median.as_nanos(),
+            mean_ns: stats.mean.as_nanos(),
+            stddev_ns: stats.stddev.as_nanos(),
+        }
+    }
+}
+
+/// A benchmark, as a line of JSON.
+#[derive(Serialize)]
+struct BenchmarkJson<'a> {
+    day: u8,
+    part: u8,
+    variant: &'a str,
+    input_bytes: usize,
+    parse: StatsJson,
+    solve: StatsJson,
+}
+
+impl<'a> From<&'a Benchmark> for BenchmarkJson<'a> {
+    fn from(benchmark: &'a Benchmark) -> Self {
+        let metadata = benchmark.metadata;
+
+        BenchmarkJson {
+            day: metadata.day,
+            part: metadata.part,
+            variant: metadata.variant_name(),
+            input_bytes: benchmark.input_len,
+            parse: StatsJson::from(&benchmark.parse),
+            solve: StatsJson::from(&benchmark.solve),
+        }
+    }
+}
+
+pub fn bench(options: &Options) -> Result<bool, String> {
+    let inputs = inputs(options)?;
+    let mut benchmarks = Vec::new();
+    let mut solved = true;
+
+    for (day, part, input) in inputs {
+        for solver in solvers(day, options, true)?
+            .into_iter()
+            .filter(|solver| solver.metadata().part == part)
+        {
+            match bench::bench(solver, &input, options.iterations) {
+                Ok(benchmark) => {
+                    if options.format == Format::Json {
+                        outln!("{}", json_line(&BenchmarkJson::from(&benchmark)));
+                    }
+
+                    benchmarks.push(benchmark);
+                }
+                Err(error) => {
+                    eprintln!("{}: {}", solver.metadata().name, error);
+                    solved = false;
+                }
+            }
+        }
+    }
+
+    if options.format == Format::Text {
+        out!("{}", bench::comparison_table(&benchmarks));
+    }
+
+    Ok(solved)
+}
@@ -0,0 +1,65 @@
+//! Checking that the variants of a part agree, on an input or a corpus of them.
+
+use super::options::Options;
+use super::read_input;
+use advent_of_code_2020::{differential, registry};
+use std::fs;
+
+/// The inputs of `day` in the corpus directory, by file name.
+fn corpus(day: u8, dir: &str) -> Result<Vec<(String, String)>, String> {
+    let entries = fs::read_dir(dir).map_err(|error| format!("{}: {}", dir, error))?;
+    let prefix = format!("day{}", day);
+    let mut inputs = Vec::new();
+
+    for entry in entries {
+        let path = entry.map_err(|error| format!("{}: {}", dir, error))?.path();
+        let name = path
+            .file_stem()
+            .map_or(String::new(), |stem| stem.to_string_lossy().into());
+
+        if name == prefix || name.starts_with(&format!("{}-", prefix)) {
+            let input = fs::read_to_string(&path)
+                .map_err(|error| format!("{}: {}", path.display(), error))?;
+            inputs.push((name, input));
+        }
+    }
+
+    inputs.sort();
+
+    if inputs.is_empty() {
+        Err(format!("no input for day {} found in {}", day, dir))
+    } else {
+        Ok(inputs)
+    }
+}
+
+pub fn diff(day: u8, options: &Options) -> Result<bool, String> {
+    let inputs = match &options.corpus {
+        None => vec![read_input(day, options)?],
+        Some(dir) => corpus(day, dir)?
+            .into_iter()
+            .map(|(_, input)| input)
+            .collect(),
+    };
+    let mut agreed = true;
+
+    for part in (1..=2).filter(|&part| options.part.is_none_or(|p| p == part)) {
+        let variants = registry().variants(day, part).count();
+
+        match differential::check(day, part, inputs.clone()) {
+            Ok(checked) => outln!(
+                "day {} part {}: {} variants agree on {} inputs",
+                day,
+                part,
+                variants,
+                checked
+            ),
+            Err(disagreement) => {
+                out!("{}", disagreement);
+                agreed = false;
+            }
+        }
+    }
+
+    Ok(agreed)
+}
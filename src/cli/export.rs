@@ -0,0 +1,53 @@
+//! Exporting pictures of the results.
+
+use super::options::Options;
+use super::{params, read_input};
+use advent_of_code_2020::export::{self, Picture};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+pub fn export(day: u8, options: &Options) -> Result<bool, String> {
+    let part = export::PARTS
+        .iter()
+        .find(|&&(d, part)| d == day && options.part.is_none_or(|p| p == part))
+        .map(|&(_, part)| part)
+        .ok_or_else(|| {
+            format!(
+                "no picture for day {}{}",
+                day,
+                options
+                    .part
+                    .map_or(String::new(), |part| format!(" part {}", part))
+            )
+        })?;
+
+    let input = read_input(day, options)?;
+    let params = params(options)?.unwrap_or_default();
+    let mut picture = export::picture(day, part, &input, &params)
+        .unwrap()
+        .map_err(|error| error.to_string())?;
+
+    let output = options.output.as_deref().unwrap();
+    let format = export::Format::from_path(output).unwrap();
+
+    if !picture.formats().contains(&format) {
+        return Err(format!(
+            "the picture of day {} part {} cannot be written as {:?}",
+            day, part, format
+        ));
+    }
+
+    if let Picture::Raster(raster) = &mut picture {
+        *raster = raster.scaled(options.scale);
+    }
+
+    let mut file =
+        BufWriter::new(File::create(output).map_err(|error| format!("{}: {}", output, error))?);
+
+    picture
+        .write(format, &mut file)
+        .and_then(|()| file.flush())
+        .map_err(|error| format!("{}: {}", output, error))?;
+
+    Ok(true)
+}
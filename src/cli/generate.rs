@@ -0,0 +1,10 @@
+use super::options::Options;
+use advent_of_code_2020::generate;
+
+/// Prints a random input of the day, the size of the actual one unless given.
+pub fn generate(day: u8, options: &Options) -> Result<bool, String> {
+    let size = options.size.unwrap_or_else(|| generate::puzzle_size(day));
+    out!("{}", generate::input(day, options.seed, size));
+
+    Ok(true)
+}
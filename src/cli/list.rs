@@ -0,0 +1,48 @@
+//! Listing the solvers.
+
+use super::options::{Format, Options};
+use super::output::json_line;
+use advent_of_code_2020::registry;
+use serde::Serialize;
+
+/// A solver, as a line of JSON.
+#[derive(Serialize)]
+struct SolverJson {
+    day: u8,
+    part: u8,
+    variant: &'static str,
+    name: &'static str,
+    complexity: &'static str,
+    input_type: &'static str,
+}
+
+pub fn list(options: &Options) -> Result<bool, String> {
+    for solver in registry().all() {
+        let metadata = solver.metadata();
+
+        match options.format {
+            Format::Text => outln!(
+                "day {:>2} part {} {:<10} {:<24} {:<12} {}",
+                metadata.day,
+                metadata.part,
+                metadata.variant_name(),
+                metadata.name,
+                metadata.complexity,
+                metadata.input_type
+            ),
+            Format::Json => outln!(
+                "{}",
+                json_line(&SolverJson {
+                    day: metadata.day,
+                    part: metadata.part,
+                    variant: metadata.variant_name(),
+                    name: metadata.name,
+                    complexity: metadata.complexity,
+                    input_type: metadata.input_type,
+                })
+            ),
+        }
+    }
+
+    Ok(true)
+}
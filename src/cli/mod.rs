@@ -0,0 +1,203 @@
+//! The commands of the runner, one module each, and the inputs and solvers they select.
+
+#[macro_use]
+pub mod output;
+
+pub mod all;
+pub mod animate;
+pub mod audit;
+pub mod bench;
+pub mod diff;
+pub mod export;
+pub mod generate;
+pub mod list;
+pub mod options;
+pub mod parse;
+pub mod run;
+pub mod verify;
+
+use advent_of_code_2020::inputs::{Inputs, DEFAULT_PROFILE, EXAMPLE_PROFILE};
+use advent_of_code_2020::params::Params;
+use advent_of_code_2020::registry;
+use advent_of_code_2020::solver::Solver;
+use options::Options;
+use std::io::{self, Read};
+use std::{fs, slice};
+
+/// The input of the day in the profile of the input directory if one is given, else in the
+/// input file or stdin.
+fn read_input(day: u8, options: &Options) -> Result<String, String> {
+    match (&options.profile, &options.input) {
+        (Some(profile), _) => Inputs::new(&options.input_dir).read(day, profile),
+        (None, Some(path)) => fs::read_to_string(path),
+        (None, None) => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map(|_| input)
+        }
+    }
+    .map_err(|error| format!("cannot read input: {}", error))
+}
+
+/// The solvers of the selected parts of `day`, either the asked for variant or, if none was
+/// given, the main solution only or every variant.
+fn solvers(
+    day: u8,
+    options: &Options,
+    all_variants: bool,
+) -> Result<Vec<&'static dyn Solver>, String> {
+    let variant = options.variant.as_deref();
+    let solvers = (1..=2)
+        .filter(|&part| options.part.is_none_or(|p| p == part))
+        .flat_map(|part| registry().variants(day, part))
+        .filter(|solver| {
+            let metadata = solver.metadata();
+
+            match variant {
+                Some(variant) => metadata.variant_name() == variant,
+                None => all_variants || metadata.variant.is_none(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if solvers.is_empty() {
+        Err(format!(
+            "no solver for day {}{}{}",
+            day,
+            options
+                .part
+                .map_or(String::new(), |part| format!(" part {}", part)),
+            variant.map_or(String::new(), |variant| format!(" variant {}", variant))
+        ))
+    } else {
+        Ok(solvers)
+    }
+}
+
+/// The input of each selected part of the day, in every profile of the input directory that
+/// has one, in the profile given, or else in the input file or stdin.
+fn part_inputs(day: u8, options: &Options) -> Result<Vec<(Option<String>, u8, String)>, String> {
+    let profiles = match &options.profile {
+        _ if options.all_profiles => Inputs::new(&options.input_dir).profiles(),
+        Some(profile) => vec![profile.clone()],
+        None => {
+            let input = read_input(day, options)?;
+
+            return Ok(parts(options)
+                .map(|part| (None, part, input.clone()))
+                .collect());
+        }
+    };
+    let part_inputs = profile_inputs(day, &profiles, options)?;
+
+    if part_inputs.is_empty() && !options.all_profiles {
+        return Err(format!("cannot read input: no example for day {}", day));
+    }
+
+    Ok(part_inputs
+        .into_iter()
+        .map(|(profile, part, input)| (options.all_profiles.then_some(profile), part, input))
+        .collect())
+}
+
+/// The selected parts.
+fn parts(options: &Options) -> impl Iterator<Item = u8> + Clone + '_ {
+    (1..=2).filter(move |&part| options.part.is_none_or(|p| p == part))
+}
+
+/// The input of each selected part of the day in each of the profiles that has one, which
+/// for the examples are the parts they give the answer to.
+fn profile_inputs(
+    day: u8,
+    profiles: &[String],
+    options: &Options,
+) -> Result<Vec<(String, u8, String)>, String> {
+    let inputs = Inputs::new(&options.input_dir);
+    let mut profile_inputs = Vec::new();
+
+    for profile in profiles {
+        for part in parts(options) {
+            match inputs.read_part(day, part, profile) {
+                Ok(input) => profile_inputs.push((profile.clone(), part, input)),
+                Err(error)
+                    if error.kind() == io::ErrorKind::NotFound
+                        && (options.all_profiles || profile == EXAMPLE_PROFILE) => {}
+                Err(error) => return Err(format!("cannot read input: {}", error)),
+            }
+        }
+    }
+
+    Ok(profile_inputs)
+}
+
+/// The parameters of the config file overridden by those of the command line, or `None` if
+/// there are neither.
+fn params(options: &Options) -> Result<Option<Params>, String> {
+    if options.config.is_none() && options.params.is_empty() {
+        return Ok(None);
+    }
+
+    let mut params = match &options.config {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| Params::parse(&text))
+            .map_err(|error| format!("{}: {}", path, error))?,
+        None => Params::default(),
+    };
+
+    for (key, value) in &options.params {
+        params.set(key, value)?;
+    }
+
+    Ok(Some(params))
+}
+
+/// The input of each selected part of the given day, or else of every day found in the input
+/// directory.
+fn inputs(options: &Options) -> Result<Vec<(u8, u8, String)>, String> {
+    let mut inputs = Vec::new();
+
+    match options.day {
+        Some(day) => {
+            for (_, part, input) in part_inputs(day, options)? {
+                inputs.push((day, part, input));
+            }
+        }
+        None => {
+            let profile = options
+                .profile
+                .clone()
+                .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+            for day in Inputs::new(&options.input_dir).days(&profile) {
+                for (_, part, input) in profile_inputs(day, slice::from_ref(&profile), options)? {
+                    inputs.push((day, part, input));
+                }
+            }
+
+            if inputs.is_empty() {
+                return Err(format!("no input found in {}", options.input_dir));
+            }
+        }
+    }
+
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use options::args;
+
+    #[test]
+    fn variant_selection() {
+        let options = args("--day 1").unwrap();
+
+        assert_eq!(solvers(1, &options, false).unwrap().len(), 2);
+        assert_eq!(solvers(1, &options, true).unwrap().len(), 8);
+
+        let options = args("--day 1 --part 1 --variant default").unwrap();
+
+        assert_eq!(solvers(1, &options, true).unwrap().len(), 1);
+        assert!(solvers(25, &args("--day 25 --part 2").unwrap(), false).is_err());
+    }
+}
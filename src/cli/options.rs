@@ -0,0 +1,606 @@
+//! The command line: the command, and the options it takes.
+
+use advent_of_code_2020::export;
+use advent_of_code_2020::params::Params;
+use advent_of_code_2020::progress::Budget;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+usage: advent_of_code_2020 [run] --day <day> [--part <part>] [--variant <variant>]
+                           [--input <path> | --profile <name> | --all-profiles]
+                           [--input-dir <dir>] [--stream] [--format text|json]
+                           [--config <path>] [--param <key>=<value>]... [--trace <path>]
+                           [--budget <steps>|<n>s|<n>ms]... [--progress] [--cache <dir>]
+       advent_of_code_2020 bench [--day <day>] [--part <part>] [--variant <variant>]
+                                 [--input <path> | --input-dir <dir>] [--iterations <n>]
+                                 [--format text|json]
+       advent_of_code_2020 all [--part <part>] [--variant <variant>] [--input-dir <dir>]
+                               [--workers <n>] [--format text|json]
+       advent_of_code_2020 diff --day <day> [--part <part>] [--input <path> | --corpus <dir>]
+       advent_of_code_2020 verify [--day <day>] [--input <path> | --input-dir <dir>]
+                                  [--answers <path>] [--record] [--format text|json]
+       advent_of_code_2020 animate --day <day> [--part <part>] [--input <path>] [--speed <n>]
+                                   [--config <path>] [--param <key>=<value>]...
+       advent_of_code_2020 export --day <day> [--part <part>] [--input <path>] --output <path>
+                                  [--scale <n>] [--config <path>] [--param <key>=<value>]...
+       advent_of_code_2020 parse --day <day> [--input <path>] [--binary]
+       advent_of_code_2020 audit [--input <path>] [--policies <path>] [--policy <name>]
+                                 [--output <path>] [--format text|json]
+       advent_of_code_2020 generate --day <day> [--seed <seed>] [--size <size>]
+       advent_of_code_2020 list [--format text|json]
+
+The input is read from stdin if no path (or `-`) is given. Benchmarking without a
+day runs every day with an input in the input directory (`input/2020` by default).
+The input directory holds the inputs of several profiles side by side: the default one
+is its own `day<N>.txt` files, every other one a subdirectory of them, such as
+`alice/day1.txt`, and the `example` profile is the worked examples of the puzzles, for
+the parts they give the answer to. Every command reading inputs takes them from a
+profile with `--profile`, and running with `--all-profiles` solves the day for each of
+them, any budget applying to the long simulations of each.
+Running all days solves every part with an input in the input directory on a pool of
+workers, one per core by default, printing each result as soon as it is ready.
+Parameters replace the puzzle's values, such as `--param day9.preamble=5`, in the main
+solutions; a config file holds one `<key> = <value>` per line. The keys are:
+  day1.target day3.slope day7.target day9.preamble day14.word_size day15.part1_turns
+  day15.part2_turns day17.cycles day23.part1_moves day23.part2_moves day23.part2_cups
+  day24.days
+Streaming reads the input line by line, for the days that can, instead of all at once.
+Tracing writes the events of the main solutions of days 8, 11, 16, 21 and 22 as JSON
+lines to the path, or to stdout for `-`.
+The long simulations of days 15, 23 (part 2) and 25 can show their progress on stderr,
+and give up once over a budget of steps or of time, such as `--budget 10s`.
+Animating shows the simulations of days 11, 12, 17 and 24 on the terminal, at a speed of
+5 generations per second by default; space plays or pauses them, n steps one generation,
++ and - change the speed, and q quits.
+Exporting writes a picture of the result of days 3 (the toboggan's path along day3.slope),
+11, 12 (the ship's course), 20 (the sea monsters) and 24, in the format named by the
+extension of the output: .svg for day 12, .pbm or .ppm for the others, their pixels
+scaled 4 times by default.
+Parsing prints the model the day's solvers take, as JSON or in a compact binary form, and
+running with a cache directory saves the binary models there, parsing each input once.
+Auditing checks every password of day 2 under its policy, the one named by its `@name`
+or else `--policy` (count by default), among count, position and those of the policies
+file, telling the rule each one violates and what it observed, such as the number of
+times of the character. The output is a .csv or .json file, or else stdout.
+Diffing checks that every variant of a part gives the same answer, on the input or on
+every file of the corpus named after the day, such as `day1.txt` or `day1-large.txt`.
+Verifying checks the answers of every variant against the known answers, stored in
+`answers.txt` in the input directory by default, and records the unknown ones if asked.
+Generating prints a random input, the size of the actual one by default.";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Command {
+    Run,
+    Bench,
+    All,
+    Diff,
+    Verify,
+    Animate,
+    Export,
+    Parse,
+    Audit,
+    Generate,
+    List,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+    pub variant: Option<String>,
+    pub input: Option<String>,
+    pub input_dir: String,
+    pub profile: Option<String>,
+    pub all_profiles: bool,
+    pub corpus: Option<String>,
+    pub iterations: usize,
+    pub workers: Option<usize>,
+    pub seed: u64,
+    pub size: Option<usize>,
+    pub answers: Option<String>,
+    pub record: bool,
+    pub stream: bool,
+    pub config: Option<String>,
+    pub params: Vec<(String, String)>,
+    pub trace: Option<String>,
+    pub speed: u32,
+    pub output: Option<String>,
+    pub scale: usize,
+    pub binary: bool,
+    pub cache: Option<String>,
+    pub policies: Option<String>,
+    pub policy: String,
+    pub budget: Budget,
+    pub progress: bool,
+    pub format: Format,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            command: Command::Run,
+            day: None,
+            part: None,
+            variant: None,
+            input: None,
+            input_dir: "input/2020".to_string(),
+            profile: None,
+            all_profiles: false,
+            corpus: None,
+            iterations: 10,
+            workers: None,
+            seed: 0,
+            size: None,
+            answers: None,
+            record: false,
+            stream: false,
+            config: None,
+            params: Vec::new(),
+            trace: None,
+            speed: 5,
+            output: None,
+            scale: 4,
+            binary: false,
+            cache: None,
+            policies: None,
+            policy: "count".to_string(),
+            budget: Budget::default(),
+            progress: false,
+            format: Format::Text,
+        }
+    }
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut args = args.into_iter().peekable();
+    let mut options = Options::default();
+
+    if let Some(command) = args.next_if(|arg| !arg.starts_with("--")) {
+        options.command = match command.as_str() {
+            "run" => Command::Run,
+            "bench" => Command::Bench,
+            "all" => Command::All,
+            "diff" => Command::Diff,
+            "verify" => Command::Verify,
+            "animate" => Command::Animate,
+            "export" => Command::Export,
+            "parse" => Command::Parse,
+            "audit" => Command::Audit,
+            "generate" => Command::Generate,
+            "list" => Command::List,
+            _ => return Err(format!("unknown command {:?}", command)),
+        };
+    }
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => options.day = Some(parse_number(&arg, args.next(), 1..=25)?),
+            "--part" => options.part = Some(parse_number(&arg, args.next(), 1..=2)?),
+            "--variant" => options.variant = Some(value(&arg, args.next())?),
+            "--input" => options.input = Some(value(&arg, args.next())?).filter(|p| p != "-"),
+            "--input-dir" => options.input_dir = value(&arg, args.next())?,
+            "--profile" => options.profile = Some(value(&arg, args.next())?),
+            "--all-profiles" => options.all_profiles = true,
+            "--corpus" => options.corpus = Some(value(&arg, args.next())?),
+            "--iterations" => options.iterations = parse_number(&arg, args.next(), 1..=usize::MAX)?,
+            "--workers" => options.workers = Some(parse_number(&arg, args.next(), 1..=usize::MAX)?),
+            "--seed" => options.seed = parse_number(&arg, args.next(), 0..=u64::MAX)?,
+            "--size" => options.size = Some(parse_number(&arg, args.next(), 1..=usize::MAX)?),
+            "--answers" => options.answers = Some(value(&arg, args.next())?),
+            "--record" => options.record = true,
+            "--stream" => options.stream = true,
+            "--config" => options.config = Some(value(&arg, args.next())?),
+            "--param" => {
+                let param = value(&arg, args.next())?;
+                let (key, value) = param
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected <key>=<value>, found {:?}", param))?;
+
+                Params::default().set(key, value)?;
+                options.params.push((key.to_string(), value.to_string()));
+            }
+            "--trace" => options.trace = Some(value(&arg, args.next())?),
+            "--speed" => options.speed = parse_number(&arg, args.next(), 1..=1_000)?,
+            "--output" => options.output = Some(value(&arg, args.next())?),
+            "--scale" => options.scale = parse_number(&arg, args.next(), 1..=64)?,
+            "--binary" => options.binary = true,
+            "--cache" => options.cache = Some(value(&arg, args.next())?),
+            "--policies" => options.policies = Some(value(&arg, args.next())?),
+            "--policy" => options.policy = value(&arg, args.next())?,
+            "--budget" => parse_budget(&mut options.budget, args.next())?,
+            "--progress" => options.progress = true,
+            "--format" => options.format = parse_format(args.next())?,
+            _ => return Err(format!("unexpected argument {:?}", arg)),
+        }
+    }
+
+    if matches!(
+        options.command,
+        Command::Run
+            | Command::Diff
+            | Command::Animate
+            | Command::Export
+            | Command::Parse
+            | Command::Generate
+    ) && options.day.is_none()
+    {
+        return Err("missing --day".to_string());
+    }
+
+    if options.command == Command::Audit {
+        if options.day.is_some_and(|day| day != 2) {
+            return Err("audit only checks the passwords of day 2".to_string());
+        }
+
+        options.day = Some(2);
+    }
+
+    if options.command == Command::All && (options.day.is_some() || options.input.is_some()) {
+        return Err("all runs every day of the input directory".to_string());
+    }
+
+    if options.day.is_none() && options.input.is_some() {
+        return Err("--input needs a --day".to_string());
+    }
+
+    if [
+        options.input.is_some(),
+        options.profile.is_some(),
+        options.all_profiles,
+        options.corpus.is_some(),
+    ]
+    .iter()
+    .filter(|&&given| given)
+    .count()
+        > 1
+    {
+        return Err("--input, --profile, --all-profiles and --corpus are exclusive".to_string());
+    }
+
+    if options.all_profiles
+        && (options.command != Command::Run
+            || options.stream
+            || options.trace.is_some()
+            || options.progress)
+    {
+        return Err("--all-profiles only runs the main solutions".to_string());
+    }
+
+    if options.stream && (options.command != Command::Run || options.variant.is_some()) {
+        return Err("--stream only runs the streaming solvers".to_string());
+    }
+
+    if (options.config.is_some() || !options.params.is_empty())
+        && (!matches!(
+            options.command,
+            Command::Run | Command::Animate | Command::Export
+        ) || options.stream
+            || options.variant.is_some())
+    {
+        return Err("parameters only apply to the main solutions when running them".to_string());
+    }
+
+    if options.trace.is_some()
+        && (options.command != Command::Run
+            || options.stream
+            || options.variant.is_some()
+            || options.config.is_some()
+            || !options.params.is_empty())
+    {
+        return Err("--trace only runs the main solutions with the puzzle's values".to_string());
+    }
+
+    if (options.budget != Budget::default() || options.progress)
+        && (options.command != Command::Run
+            || options.stream
+            || options.variant.is_some()
+            || options.trace.is_some())
+    {
+        return Err("--budget and --progress only apply to the main solutions".to_string());
+    }
+
+    if options.cache.is_some()
+        && (options.command != Command::Run
+            || options.stream
+            || options.trace.is_some()
+            || options.budget != Budget::default()
+            || options.progress
+            || options.config.is_some()
+            || !options.params.is_empty())
+    {
+        return Err("--cache only runs the solvers with the puzzle's values".to_string());
+    }
+
+    if options.binary && options.command != Command::Parse {
+        return Err("--binary only applies to parse".to_string());
+    }
+
+    if (options.policies.is_some() || options.policy != Options::default().policy)
+        && options.command != Command::Audit
+    {
+        return Err("--policies and --policy only apply to audit".to_string());
+    }
+
+    if options.command == Command::Export && options.output.is_none() {
+        return Err("export needs an --output".to_string());
+    }
+
+    match (options.command, options.output.as_deref()) {
+        (_, None) => {}
+        (Command::Export, Some(output)) if export::Format::from_path(output).is_none() => {
+            return Err("--output must end with .pbm, .ppm or .svg".to_string());
+        }
+        (Command::Audit, Some(output))
+            if !output.ends_with(".csv") && !output.ends_with(".json") =>
+        {
+            return Err("--output must end with .csv or .json".to_string());
+        }
+        (Command::Export | Command::Audit, Some(_)) => {}
+        (_, Some(_)) => return Err("only export and audit write an --output".to_string()),
+    }
+
+    Ok(options)
+}
+
+/// Parses the arguments of a command line split on whitespace, for the tests.
+#[cfg(test)]
+pub fn args(args: &str) -> Result<Options, String> {
+    parse_args(args.split_whitespace().map(String::from))
+}
+
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("missing value for {}", flag))
+}
+
+fn parse_number<T>(
+    flag: &str,
+    value: Option<String>,
+    range: std::ops::RangeInclusive<T>,
+) -> Result<T, String>
+where
+    T: std::str::FromStr + PartialOrd + std::fmt::Display,
+{
+    let value = self::value(flag, value)?;
+
+    value
+        .parse()
+        .ok()
+        .filter(|number| range.contains(number))
+        .ok_or_else(|| {
+            format!(
+                "{} must be between {} and {}, got {:?}",
+                flag,
+                range.start(),
+                range.end(),
+                value
+            )
+        })
+}
+
+/// Sets the step budget from a number, or the time budget from a number of seconds (`s`) or
+/// milliseconds (`ms`).
+fn parse_budget(budget: &mut Budget, value: Option<String>) -> Result<(), String> {
+    let value = self::value("--budget", value)?;
+    let invalid = || {
+        format!(
+            "--budget must be a number of steps, of s or of ms, got {:?}",
+            value
+        )
+    };
+
+    if let Some(millis) = value.strip_suffix("ms") {
+        budget.time = Some(Duration::from_millis(
+            millis.parse().map_err(|_| invalid())?,
+        ));
+    } else if let Some(seconds) = value.strip_suffix('s') {
+        budget.time = Some(Duration::from_secs(seconds.parse().map_err(|_| invalid())?));
+    } else {
+        budget.steps = Some(value.parse().map_err(|_| invalid())?);
+    }
+
+    Ok(())
+}
+
+fn parse_format(value: Option<String>) -> Result<Format, String> {
+    match self::value("--format", value)?.as_str() {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        format => Err(format!("unknown format {:?}", format)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments() {
+        assert_eq!(
+            args("--day 1 --part 2 --variant Itertools --format json").unwrap(),
+            Options {
+                day: Some(1),
+                part: Some(2),
+                variant: Some("Itertools".to_string()),
+                format: Format::Json,
+                ..Options::default()
+            }
+        );
+        assert_eq!(
+            args("run --input - --day 25").unwrap(),
+            args("--day 25").unwrap()
+        );
+        assert_eq!(args("list").unwrap().command, Command::List);
+        assert_eq!(
+            args("bench --iterations 3").unwrap(),
+            Options {
+                command: Command::Bench,
+                iterations: 3,
+                ..Options::default()
+            }
+        );
+        assert!(args("--part 1").is_err());
+        assert!(args("--day 26").is_err());
+        assert!(args("--day 1 --part").is_err());
+        assert!(args("walk --day 1").is_err());
+        assert!(args("bench --iterations 0").is_err());
+        assert!(args("diff --part 1").is_err());
+        assert_eq!(
+            args("generate --day 20 --seed 3 --size 5").unwrap(),
+            Options {
+                command: Command::Generate,
+                day: Some(20),
+                seed: 3,
+                size: Some(5),
+                ..Options::default()
+            }
+        );
+        assert!(args("generate --day 20 --size 0").is_err());
+        assert!(args("diff --day 1 --input a.txt --corpus inputs").is_err());
+        assert!(args("bench --input day1.txt").is_err());
+        assert_eq!(
+            args("all --workers 4 --input-dir inputs").unwrap(),
+            Options {
+                command: Command::All,
+                workers: Some(4),
+                input_dir: "inputs".to_string(),
+                ..Options::default()
+            }
+        );
+        assert!(args("all --workers 0").is_err());
+        assert!(args("all --day 3").is_err());
+        assert_eq!(
+            args("--day 9 --param day9.preamble=5 --config what-if.txt").unwrap(),
+            Options {
+                day: Some(9),
+                config: Some("what-if.txt".to_string()),
+                params: vec![("day9.preamble".to_string(), "5".to_string())],
+                ..Options::default()
+            }
+        );
+        assert!(args("--day 9 --param day9.preamble").is_err());
+        assert!(args("--day 9 --param day9.preamble=x").is_err());
+        assert!(args("bench --param day9.preamble=5").is_err());
+        assert!(args("--day 9 --variant Fast --param day9.preamble=5").is_err());
+        assert!(args("--day 9 --stream").unwrap().stream);
+        assert!(args("bench --day 9 --stream").is_err());
+        assert!(args("--day 9 --stream --variant Itertools").is_err());
+        assert_eq!(
+            args("--day 22 --part 2 --trace -").unwrap(),
+            Options {
+                day: Some(22),
+                part: Some(2),
+                trace: Some("-".to_string()),
+                ..Options::default()
+            }
+        );
+        assert!(args("bench --day 22 --trace -").is_err());
+        assert!(args("--day 8 --trace - --variant Fast").is_err());
+        assert!(args("--day 9 --trace - --param day9.preamble=5").is_err());
+        assert_eq!(
+            args("--day 23 --budget 1000 --budget 2s --progress").unwrap(),
+            Options {
+                day: Some(23),
+                budget: Budget {
+                    steps: Some(1_000),
+                    time: Some(Duration::from_secs(2)),
+                },
+                progress: true,
+                ..Options::default()
+            }
+        );
+        assert_eq!(
+            args("--day 25 --budget 500ms").unwrap().budget.time,
+            Some(Duration::from_millis(500))
+        );
+        assert!(args("--day 25 --budget 5m").is_err());
+        assert!(args("bench --day 25 --progress").is_err());
+        assert!(args("--day 25 --stream --budget 10").is_err());
+        assert_eq!(
+            args("animate --day 11 --part 2 --speed 20").unwrap(),
+            Options {
+                command: Command::Animate,
+                day: Some(11),
+                part: Some(2),
+                speed: 20,
+                ..Options::default()
+            }
+        );
+        assert!(args("animate --speed 20").is_err());
+        assert!(args("animate --day 24 --param day24.days=10").is_ok());
+        assert!(args("animate --day 11 --speed 0").is_err());
+        assert_eq!(
+            args("export --day 3 --output path.ppm --scale 2 --param day3.slope=1,2").unwrap(),
+            Options {
+                command: Command::Export,
+                day: Some(3),
+                output: Some("path.ppm".to_string()),
+                scale: 2,
+                params: vec![("day3.slope".to_string(), "1,2".to_string())],
+                ..Options::default()
+            }
+        );
+        assert!(args("export --day 12").is_err());
+        assert!(args("export --day 12 --output ship.png").is_err());
+        assert!(args("--day 12 --output ship.svg").is_err());
+        assert_eq!(
+            args("--day 13 --all-profiles --input-dir inputs").unwrap(),
+            Options {
+                day: Some(13),
+                input_dir: "inputs".to_string(),
+                all_profiles: true,
+                ..Options::default()
+            }
+        );
+        assert!(args("bench --profile alice").is_ok());
+        assert!(args("--day 13 --profile alice --input day13.txt").is_err());
+        assert!(args("--day 13 --all-profiles --stream").is_err());
+        assert!(args("bench --all-profiles").is_err());
+        assert_eq!(
+            args("parse --day 20 --binary --profile alice").unwrap(),
+            Options {
+                command: Command::Parse,
+                day: Some(20),
+                profile: Some("alice".to_string()),
+                binary: true,
+                ..Options::default()
+            }
+        );
+        assert!(args("parse").is_err());
+        assert!(args("--day 20 --binary").is_err());
+        assert!(args("--day 19 --part 2 --cache target/models --all-profiles").is_ok());
+        assert!(args("--day 9 --cache target/models --param day9.preamble=5").is_err());
+        assert!(args("bench --cache target/models").is_err());
+        assert_eq!(
+            args("audit --policies policies.txt --policy strong --output audit.csv").unwrap(),
+            Options {
+                command: Command::Audit,
+                day: Some(2),
+                policies: Some("policies.txt".to_string()),
+                policy: "strong".to_string(),
+                output: Some("audit.csv".to_string()),
+                ..Options::default()
+            }
+        );
+        assert!(args("audit --input day2.txt --format json").is_ok());
+        assert!(args("audit --day 3").is_err());
+        assert!(args("audit --output audit.txt").is_err());
+        assert!(args("--day 2 --policy position").is_err());
+        assert_eq!(
+            args("verify --record --answers known.txt --day 3").unwrap(),
+            Options {
+                command: Command::Verify,
+                day: Some(3),
+                answers: Some("known.txt".to_string()),
+                record: true,
+                ..Options::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Writing to stdout, and the fields the JSON lines of the commands share.
+
+use serde::Serialize;
+use std::io::{self, Write};
+use std::process;
+
+/// Same as `print!`, through the locked stdout, exiting quietly rather than panicking once
+/// whatever reads it, such as `head`, is gone.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::cli::output::write_stdout(format_args!($($arg)*))
+    };
+}
+
+/// Same as `println!`, as [`out!`] does.
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        out!("{}\n", format_args!($($arg)*))
+    };
+}
+
+pub fn write_stdout(args: std::fmt::Arguments) {
+    let mut stdout = io::stdout().lock();
+
+    if let Err(error) = stdout.write_fmt(args).and_then(|()| stdout.flush()) {
+        exit_if_closed(&error);
+        eprintln!("cannot write to stdout: {}", error);
+        process::exit(1);
+    }
+}
+
+/// Exits successfully if the error is that the reader of the output is gone, as there is
+/// nobody left to tell.
+pub fn exit_if_closed(error: &io::Error) {
+    if error.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(0);
+    }
+}
+
+/// The value as a line of JSON.
+pub fn json_line(value: &impl Serialize) -> String {
+    serde_json::to_string(value).expect("the output serializes to JSON")
+}
+
+/// The answer of a part, or why there is none, as an `answer` or an `error` field.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome<'a> {
+    Answer(&'a str),
+    Error(&'a str),
+}
+
+impl<'a> From<&'a Result<String, String>> for Outcome<'a> {
+    fn from(result: &'a Result<String, String>) -> Self {
+        match result {
+            Ok(answer) => Outcome::Answer(answer),
+            Err(error) => Outcome::Error(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Line<'a> {
+        part: u8,
+        #[serde(flatten)]
+        outcome: Outcome<'a>,
+    }
+
+    #[test]
+    fn outcomes() {
+        let answer = Ok("a\"b\\c\n\t".to_string());
+        let error = Err("no solution".to_string());
+
+        assert_eq!(
+            json_line(&Line {
+                part: 1,
+                outcome: Outcome::from(&answer),
+            }),
+            "{\"part\":1,\"answer\":\"a\\\"b\\\\c\\n\\t\"}"
+        );
+        assert_eq!(
+            json_line(&Line {
+                part: 2,
+                outcome: Outcome::from(&error),
+            }),
+            "{\"part\":2,\"error\":\"no solution\"}"
+        );
+    }
+}
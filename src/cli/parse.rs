@@ -0,0 +1,36 @@
+//! Printing the models the solvers take.
+
+use super::options::Options;
+use super::output::exit_if_closed;
+use super::read_input;
+use advent_of_code_2020::model::{self, Encoding};
+use std::io::{self, Write};
+
+pub fn parse(day: u8, options: &Options) -> Result<bool, String> {
+    let input = read_input(day, options)?;
+    let codec = model::codec(day).unwrap();
+    let parsed = codec.parse(&input).map_err(|error| error.to_string())?;
+    let encoding = if options.binary {
+        Encoding::Binary
+    } else {
+        Encoding::Json
+    };
+    let mut model = codec
+        .encode(&parsed, encoding)
+        .map_err(|error| error.to_string())?;
+
+    if encoding == Encoding::Json {
+        model.push(b'\n');
+    }
+
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(&model)
+        .and_then(|()| stdout.flush())
+        .map_err(|error| {
+            exit_if_closed(&error);
+            error.to_string()
+        })?;
+
+    Ok(true)
+}
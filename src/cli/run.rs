@@ -0,0 +1,420 @@
+//! Running the solvers of a day: the main solutions or variants on the input of each profile,
+//! or the streaming, traced or monitored ones.
+
+use super::options::{Format, Options};
+use super::output::{exit_if_closed, json_line, Outcome};
+use super::{params, part_inputs, read_input, solvers};
+use advent_of_code_2020::differential::panic_message;
+use advent_of_code_2020::inputs::Inputs;
+use advent_of_code_2020::model::{self, Cache};
+use advent_of_code_2020::params::{self, Params};
+use advent_of_code_2020::progress::{self, Budget, Monitor};
+use advent_of_code_2020::registry;
+use advent_of_code_2020::solver::{self, Metadata, Solver};
+use advent_of_code_2020::stream;
+use advent_of_code_2020::trace::{self, JsonLines};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A part solved, as a line of JSON.
+#[derive(Serialize)]
+struct Solved<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<&'a str>,
+    day: u8,
+    part: u8,
+    variant: &'a str,
+    #[serde(flatten)]
+    outcome: Outcome<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_ns: Option<u128>,
+    solve_ns: u128,
+}
+
+struct Run {
+    metadata: Metadata,
+    /// The answer, or the error or panic message.
+    answer: Result<String, String>,
+    parse_time: Duration,
+    solve_time: Duration,
+}
+
+impl Run {
+    fn new(solver: &dyn Solver, input: &str) -> Self {
+        let mut parse_time = Duration::ZERO;
+        let mut solve_time = Duration::ZERO;
+
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+            let start = Instant::now();
+            let parsed = solver.parse(input);
+            parse_time = start.elapsed();
+
+            let start = Instant::now();
+            let answer = solver.solve(&parsed?);
+            solve_time = start.elapsed();
+
+            answer
+        }));
+
+        Run {
+            metadata: solver.metadata().clone(),
+            answer: Run::result(answer),
+            parse_time,
+            solve_time,
+        }
+    }
+
+    /// Runs the monitored solver of the part within the budget, parsing the input as part of
+    /// solving it.
+    fn monitored(day: u8, part: u8, input: &str, params: &Params, budget: Budget) -> Self {
+        let mut monitor = Monitor::new().with_budget(budget);
+        let start = Instant::now();
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+            progress::solve(day, part, input, params, &mut monitor).unwrap()
+        }));
+
+        Run {
+            metadata: registry().find(day, part, None).unwrap().metadata().clone(),
+            answer: Run::result(answer),
+            parse_time: Duration::ZERO,
+            solve_time: start.elapsed(),
+        }
+    }
+
+    fn result(
+        answer: std::thread::Result<Result<String, solver::Error>>,
+    ) -> Result<String, String> {
+        match answer {
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(error)) => Err(error.to_string()),
+            Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let metadata = &self.metadata;
+        let result = match &self.answer {
+            Ok(answer) => answer.clone(),
+            Err(error) => format!("error: {}", error),
+        };
+
+        format!(
+            "day {} part {} ({}): {} [parse {:?}, solve {:?}]",
+            metadata.day,
+            metadata.part,
+            metadata.variant_name(),
+            result,
+            self.parse_time,
+            self.solve_time
+        )
+    }
+
+    fn to_json(&self, profile: Option<&str>) -> String {
+        json_line(&Solved {
+            profile,
+            day: self.metadata.day,
+            part: self.metadata.part,
+            variant: self.metadata.variant_name(),
+            outcome: Outcome::from(&self.answer),
+            parse_ns: Some(self.parse_time.as_nanos()),
+            solve_ns: self.solve_time.as_nanos(),
+        })
+    }
+}
+
+pub fn run(day: u8, options: &Options) -> Result<bool, String> {
+    if options.stream {
+        return run_stream(day, options);
+    } else if let Some(path) = &options.trace {
+        return run_traced(day, path, options);
+    } else if (options.budget != Budget::default() && !options.all_profiles) || options.progress {
+        return run_monitored(day, options);
+    }
+
+    solve_day(day, options, |profile, run| {
+        match (options.format, profile) {
+            (Format::Text, None) => outln!("{}", run.to_text()),
+            (Format::Text, Some(profile)) => outln!("{}: {}", profile, run.to_text()),
+            (Format::Json, profile) => outln!("{}", run.to_json(profile)),
+        }
+    })
+}
+
+/// Runs the selected solvers of `day` on the input of each part in each profile, calling
+/// `report` with each run as it ends. A panic fails the run only, and the monitored parts run
+/// within the budget if there is one.
+fn solve_day(
+    day: u8,
+    options: &Options,
+    mut report: impl FnMut(Option<&str>, &Run),
+) -> Result<bool, String> {
+    let solvers = solvers(day, options, false)?;
+    let params = params(options)?;
+    let cache = options.cache.as_ref().map(|dir| Arc::new(Cache::new(dir)));
+    let mut solved = true;
+
+    for (profile, part, input) in part_inputs(day, options)? {
+        for &solver in solvers
+            .iter()
+            .filter(|solver| solver.metadata().part == part)
+        {
+            let run =
+                if options.budget != Budget::default() && progress::PARTS.contains(&(day, part)) {
+                    let params = params.clone().unwrap_or_default();
+                    Run::monitored(day, part, &input, &params, options.budget)
+                } else {
+                    let substitute = match (&params, &cache) {
+                        (Some(params), _) => params::solver(day, part, params),
+                        (None, Some(cache)) => Some(model::cached(solver, cache)),
+                        (None, None) => None,
+                    };
+                    Run::new(substitute.as_deref().unwrap_or(solver), &input)
+                };
+            solved &= run.answer.is_ok();
+
+            report(profile.as_deref(), &run);
+        }
+    }
+
+    Ok(solved)
+}
+
+/// Runs the streaming solvers of the selected parts of `day`, reading the input again for
+/// each part.
+fn run_stream(day: u8, options: &Options) -> Result<bool, String> {
+    let parts = stream::PARTS
+        .iter()
+        .filter(|&&(d, part)| d == day && options.part.is_none_or(|p| p == part))
+        .map(|&(_, part)| part)
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return Err(format!(
+            "no streaming solver for day {}{}",
+            day,
+            options
+                .part
+                .map_or(String::new(), |part| format!(" part {}", part))
+        ));
+    } else if parts.len() > 1 && options.input.is_none() && options.profile.is_none() {
+        return Err("streaming from stdin needs a --part".to_string());
+    }
+
+    let path = match &options.profile {
+        Some(profile) => Inputs::new(&options.input_dir).path(day, profile),
+        None => options.input.as_ref().map(PathBuf::from),
+    };
+    let mut solved = true;
+
+    for part in parts {
+        let reader: Box<dyn BufRead> =
+            match (&path, &options.profile) {
+                (Some(path), _) => Box::new(BufReader::new(File::open(path).map_err(|error| {
+                    format!("cannot read input: {}: {}", path.display(), error)
+                })?)),
+                (None, Some(_)) => Box::new(io::Cursor::new(read_input(day, options)?)),
+                (None, None) => Box::new(io::stdin().lock()),
+            };
+
+        let start = Instant::now();
+        let answer = stream::solve(day, part, reader).unwrap();
+        let time = start.elapsed();
+        solved &= answer.is_ok();
+
+        print_solved(day, part, "stream", answer, time, options.format);
+    }
+
+    Ok(solved)
+}
+
+/// Runs the traced solvers of the selected parts of `day`, writing their events as JSON lines
+/// to `path`, or to stdout for `-`.
+fn run_traced(day: u8, path: &str, options: &Options) -> Result<bool, String> {
+    let parts = trace::PARTS
+        .iter()
+        .filter(|&&(d, part)| d == day && options.part.is_none_or(|p| p == part))
+        .map(|&(_, part)| part)
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return Err(format!(
+            "no traced solver for day {}{}",
+            day,
+            options
+                .part
+                .map_or(String::new(), |part| format!(" part {}", part))
+        ));
+    }
+
+    let input = read_input(day, options)?;
+    let writer: Box<dyn Write> = match path {
+        "-" => Box::new(io::stdout().lock()),
+        path => Box::new(BufWriter::new(
+            File::create(path).map_err(|error| format!("cannot create {}: {}", path, error))?,
+        )),
+    };
+    let mut tracer = JsonLines::new(writer);
+    let mut answers = Vec::new();
+
+    for part in parts {
+        let start = Instant::now();
+        let answer = trace::solve(day, part, &input, &mut tracer).unwrap();
+        answers.push((part, answer, start.elapsed()));
+    }
+
+    tracer.finish().map_err(|error| {
+        exit_if_closed(&error);
+        format!("cannot write the trace: {}", error)
+    })?;
+
+    let mut solved = true;
+
+    for (part, answer, time) in answers {
+        solved &= answer.is_ok();
+        print_solved(day, part, "traced", answer, time, options.format);
+    }
+
+    Ok(solved)
+}
+
+/// Runs the monitored solvers of the selected parts of `day`, within the budget, showing their
+/// progress on stderr if asked.
+fn run_monitored(day: u8, options: &Options) -> Result<bool, String> {
+    let parts = progress::PARTS
+        .iter()
+        .filter(|&&(d, part)| d == day && options.part.is_none_or(|p| p == part))
+        .map(|&(_, part)| part)
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return Err(format!(
+            "no monitored solver for day {}{}",
+            day,
+            options
+                .part
+                .map_or(String::new(), |part| format!(" part {}", part))
+        ));
+    }
+
+    let input = read_input(day, options)?;
+    let params = params(options)?.unwrap_or_default();
+    let mut solved = true;
+
+    for part in parts {
+        let mut monitor = Monitor::new().with_budget(options.budget);
+
+        if options.progress {
+            monitor = monitor.with_progress(move |fraction| {
+                eprint!("\rday {} part {}: {:3.0}%", day, part, fraction * 100.0);
+            });
+        }
+
+        let start = Instant::now();
+        let answer = progress::solve(day, part, &input, &params, &mut monitor).unwrap();
+        let time = start.elapsed();
+        solved &= answer.is_ok();
+
+        if options.progress {
+            eprintln!();
+        }
+
+        print_solved(day, part, "monitored", answer, time, options.format);
+    }
+
+    Ok(solved)
+}
+
+/// Prints the answer of a part solved outside of the registry, by the `how` solver.
+fn print_solved(
+    day: u8,
+    part: u8,
+    how: &str,
+    answer: Result<String, impl std::fmt::Display>,
+    time: Duration,
+    format: Format,
+) {
+    let answer = answer.map_err(|error| error.to_string());
+
+    match format {
+        Format::Text => outln!(
+            "day {} part {} ({}): {} [{:?}]",
+            day,
+            part,
+            how,
+            answer.unwrap_or_else(|error| format!("error: {}", error)),
+            time
+        ),
+        Format::Json => outln!(
+            "{}",
+            json_line(&Solved {
+                profile: None,
+                day,
+                part,
+                variant: how,
+                outcome: Outcome::from(&answer),
+                parse_ns: None,
+                solve_ns: time.as_nanos(),
+            })
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::options::args;
+    use advent_of_code_2020::inputs::{example, part_fixture, EXAMPLE_PROFILE};
+
+    #[test]
+    fn all_profiles() {
+        // Only the examples, the simulations of days 15 and 23 cut short by the budget.
+        let slow = [(15, 2), (23, 2)];
+
+        for day in 1..=25 {
+            let options = args(&format!(
+                "--day {} --all-profiles --input-dir no-inputs --budget 100000",
+                day
+            ))
+            .unwrap();
+            let mut runs = Vec::new();
+            let solved = solve_day(day, &options, |profile, run| {
+                runs.push((
+                    profile.map(str::to_string),
+                    run.metadata.part,
+                    run.answer.clone(),
+                ))
+            });
+
+            assert_eq!(solved, Ok(day != 15 && day != 23), "day {}", day);
+
+            for (profile, part, answer) in runs {
+                let expected = part_fixture(day, part).unwrap().answers[usize::from(part) - 1];
+
+                assert_eq!(profile.as_deref(), Some(EXAMPLE_PROFILE));
+
+                if slow.contains(&(day, part)) {
+                    assert!(answer.unwrap_err().starts_with("budget exceeded"));
+                } else {
+                    assert_eq!(answer.as_deref(), Ok(expected.unwrap()), "day {}", day);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn json_output() {
+        let run = Run::new(registry().find(1, 1, None).unwrap(), example(1, "example"));
+
+        assert!(run
+            .to_json(None)
+            .starts_with("{\"day\":1,\"part\":1,\"variant\":\"default\",\"answer\":\"514579\","));
+        assert!(run
+            .to_json(Some("alice"))
+            .starts_with("{\"profile\":\"alice\",\"day\":1,"));
+    }
+}
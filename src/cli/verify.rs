@@ -0,0 +1,90 @@
+//! Checking the answers of every variant against the known ones.
+
+use super::inputs;
+use super::options::{Format, Options};
+use super::output::{json_line, Outcome};
+use advent_of_code_2020::answers::{self, AnswerStore, Verdict};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// The check of a variant, as a line of JSON.
+#[derive(Serialize)]
+struct CheckJson<'a> {
+    day: u8,
+    part: u8,
+    variant: &'a str,
+    #[serde(flatten)]
+    outcome: Outcome<'a>,
+    verdict: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected: Option<&'a str>,
+}
+
+pub fn verify(options: &Options) -> Result<bool, String> {
+    let path = options.answers.as_ref().map_or_else(
+        || Path::new(&options.input_dir).join("answers.txt"),
+        PathBuf::from,
+    );
+    let mut store =
+        AnswerStore::load(&path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    let mut recorded = 0;
+    let mut passed = true;
+
+    for (day, part, input) in inputs(options)? {
+        for check in answers::verify(&store, day, part, &input) {
+            let metadata = check.metadata;
+            passed &= check.outcome.is_ok() && !matches!(check.verdict, Verdict::Regression { .. });
+
+            let verdict = match (&check.verdict, &check.outcome) {
+                (Verdict::Unknown, Ok(answer)) if options.record && metadata.variant.is_none() => {
+                    store.record(day, metadata.part, &input, answer.clone());
+                    recorded += 1;
+                    "recorded"
+                }
+                (Verdict::Unknown, _) => "unknown",
+                (Verdict::Correct, _) => "correct",
+                (Verdict::Regression { .. }, _) => "regression",
+            };
+
+            match options.format {
+                Format::Text => outln!(
+                    "day {} part {} ({}): {} [{}{}]",
+                    day,
+                    metadata.part,
+                    metadata.variant_name(),
+                    match &check.outcome {
+                        Ok(answer) => answer.clone(),
+                        Err(error) => format!("error: {}", error),
+                    },
+                    verdict,
+                    match &check.verdict {
+                        Verdict::Regression { expected } => format!(", expected {}", expected),
+                        _ => String::new(),
+                    }
+                ),
+                Format::Json => outln!(
+                    "{}",
+                    json_line(&CheckJson {
+                        day,
+                        part: metadata.part,
+                        variant: metadata.variant_name(),
+                        outcome: Outcome::from(&check.outcome),
+                        verdict,
+                        expected: match &check.verdict {
+                            Verdict::Regression { expected } => Some(expected),
+                            _ => None,
+                        },
+                    })
+                ),
+            }
+        }
+    }
+
+    if recorded > 0 {
+        store
+            .save(&path)
+            .map_err(|error| format!("{}: {}", path.display(), error))?;
+    }
+
+    Ok(passed)
+}
@@ -1,107 +1,785 @@
+//! Day 1: Report Repair.
+
+use crate::parsing::{parse_lines, ParseError, Source, StreamError};
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
-use std::collections::HashSet;
-use std::num::ParseIntError;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::io::BufRead;
+use std::iter;
+use std::ops::ControlFlow;
+
+/// A single line of the expense report.
+pub type Entry = i32;
 
-type Entry = i32;
+/// The puzzle parameters, 2020 by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    /// The sum the entries must add up to.
+    pub target: Entry,
+}
 
+impl Default for Params {
+    fn default() -> Self {
+        Params { target: 2020 }
+    }
+}
+
+/// Parses the expense report, one entry per line.
 #[aoc_generator(day1)]
-fn parse_input(input: &str) -> Result<Vec<Entry>, ParseIntError> {
-    input.lines().map(|l| l.parse()).collect()
+pub fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+    let source = Source::new(1, input);
+    input.lines().map(|l| source.parse(l)).collect()
 }
 
+/// Returns the product of the two entries that sum to 2020.
 #[aoc(day1, part1)]
-fn part1(entries: &[Entry]) -> Option<Entry> {
-    let dictionary: HashSet<_> = entries.iter().collect();
+pub fn part1(entries: &[Entry]) -> Option<Entry> {
+    part1_with(entries, &Params::default())
+}
+
+/// Same as [`part1`], for the sum given by `params`.
+pub fn part1_with(entries: &[Entry], params: &Params) -> Option<Entry> {
+    product(entries, Variant::Default.pairs(), params.target)
+}
+
+/// Same as [`part1`], using a lookup array instead of a hash map.
+#[aoc(day1, part1, BoolArray)]
+pub fn part1_bool_array(entries: &[Entry]) -> Option<Entry> {
+    product(entries, Variant::BoolArray.pairs(), 2020)
+}
+
+/// Same as [`part1`], checking every pair of entries.
+#[aoc(day1, part1, Itertools)]
+pub fn part1_itertools(entries: &[Entry]) -> Option<Entry> {
+    product(entries, Variant::Itertools.pairs(), 2020)
+}
+
+/// Same as [`part1`], reading the expense report line by line up to the second entry of the
+/// pair.
+pub fn part1_stream(reader: impl BufRead) -> Result<Option<Entry>, StreamError> {
+    let mut dictionary = HashSet::new();
+
+    for second in parse_lines(1, reader, |source, line| source.parse(line)) {
+        let second: Entry = second?;
+        let first = 2020 - second;
 
-    for first in entries {
-        if let Some(&second) = dictionary.get(&(2020 - first)) {
-            return Some(first * second);
+        if dictionary.contains(&first) {
+            return Ok(Some(first * second));
         }
+
+        dictionary.insert(second);
     }
 
-    None
+    Ok(None)
 }
 
-#[aoc(day1, part1, BoolArray)]
-fn part1_bool_array(entries: &[Entry]) -> Option<Entry> {
-    let dictionary: [bool; 2021] = entries.iter().fold([false; 2021], |mut acc, entry| {
-        acc[*entry as usize] = true;
-        acc
+/// Returns the product of the three entries that sum to 2020.
+#[aoc(day1, part2)]
+pub fn part2(entries: &[Entry]) -> Option<Entry> {
+    part2_with(entries, &Params::default())
+}
+
+/// Same as [`part2`], for the sum given by `params`.
+pub fn part2_with(entries: &[Entry], params: &Params) -> Option<Entry> {
+    product(entries, Variant::Default.triples(), params.target)
+}
+
+/// Same as [`part2`], using a lookup array instead of a hash map.
+#[aoc(day1, part2, BoolArray)]
+pub fn part2_bool_array(entries: &[Entry]) -> Option<Entry> {
+    product(entries, Variant::BoolArray.triples(), 2020)
+}
+
+/// Same as [`part2`], reading the expense report line by line up to the last entry of the
+/// triple and only keeping the number of times each entry was seen.
+pub fn part2_stream(reader: impl BufRead) -> Result<Option<Entry>, StreamError> {
+    let mut counts = HashMap::<Entry, usize>::new();
+
+    for third in parse_lines(1, reader, |source, line| source.parse(line)) {
+        let third: Entry = third?;
+
+        for &first in counts.keys() {
+            let second = 2020 - (first + third);
+            let needed = if first == second { 2 } else { 1 };
+
+            if counts.get(&second).is_some_and(|&count| count >= needed) {
+                return Ok(Some(first * second * third));
+            }
+        }
+
+        *counts.entry(third).or_default() += 1;
+    }
+
+    Ok(None)
+}
+
+/// Same as [`part2`], checking every triple of entries.
+#[aoc(day1, part2, Itertools)]
+pub fn part2_itertools(entries: &[Entry]) -> Option<Entry> {
+    product(entries, Variant::Itertools.triples(), 2020)
+}
+
+/// Visits the indices of the tuples of entries adding up to a target, in increasing order,
+/// until told to stop.
+type Tuples = fn(&[Entry], Entry, &mut dyn FnMut(&[usize]) -> ControlFlow<()>);
+
+fn product(entries: &[Entry], search: Tuples, target: Entry) -> Option<Entry> {
+    let mut product = None;
+
+    search(entries, target, &mut |indices| {
+        product = Some(indices.iter().map(|&index| entries[index]).product());
+        ControlFlow::Break(())
     });
 
-    for first in entries {
-        let second = 2020 - first;
+    product
+}
+
+/// The index of the first of each entry seen so far, in a hash map or a lookup array.
+trait Dictionary {
+    fn slot(&self, entry: Entry) -> Option<&usize>;
+
+    /// The index of the entry, if the dictionary can hold it.
+    fn slot_mut(&mut self, entry: Entry) -> Option<&mut usize>;
+
+    fn first(&self, entry: Entry) -> Option<usize> {
+        self.slot(entry).copied().filter(|&index| index != UNSEEN)
+    }
 
-        if second >= 0 && dictionary[second as usize] {
-            return Some(first * (2020 - first));
+    fn insert(&mut self, entry: Entry, index: usize) {
+        if let Some(first) = self.slot_mut(entry).filter(|first| **first == UNSEEN) {
+            *first = index;
         }
     }
 
-    None
+    /// Forgets the entries, and only them.
+    fn remove(&mut self, entries: &[Entry]) {
+        for &entry in entries {
+            if let Some(first) = self.slot_mut(entry) {
+                *first = UNSEEN;
+            }
+        }
+    }
 }
 
-#[aoc(day1, part1, Itertools)]
-fn part1_itertools(entries: &[Entry]) -> Option<Entry> {
-    entries
-        .iter()
-        .tuple_combinations()
-        .find(|(first, second)| *first + *second == 2020)
-        .map(|(first, second)| first * second)
+/// The index of the entries not seen.
+const UNSEEN: usize = usize::MAX;
+
+impl Dictionary for HashMap<Entry, usize> {
+    fn slot(&self, entry: Entry) -> Option<&usize> {
+        self.get(&entry)
+    }
+
+    fn slot_mut(&mut self, entry: Entry) -> Option<&mut usize> {
+        Some(self.entry(entry).or_insert(UNSEEN))
+    }
 }
 
-#[aoc(day1, part2)]
-fn part2(entries: &[Entry]) -> Option<Entry> {
-    let dictionary: HashSet<_> = entries.iter().collect();
+/// A lookup array, holding the entries from `lowest` on.
+struct LookupArray {
+    lowest: i64,
+    firsts: Vec<usize>,
+}
+
+impl LookupArray {
+    /// The lookup array of the entries adding up to the target with `others` more entries:
+    /// those between the target less the others at their largest and at their smallest.
+    fn new(entries: &[Entry], others: i64, target: Entry) -> Self {
+        let (min, max) = match (entries.iter().min(), entries.iter().max()) {
+            (Some(&min), Some(&max)) => (i64::from(min), i64::from(max)),
+            _ => (0, -1),
+        };
+        let lowest = min.max(i64::from(target) - others * max);
+        let highest = max.min(i64::from(target) - others * min);
+
+        LookupArray {
+            lowest,
+            firsts: vec![UNSEEN; usize::try_from(highest - lowest + 1).unwrap_or(0)],
+        }
+    }
 
-    for i in 0..entries.len() - 1 {
-        for j in i + 1..entries.len() {
-            let first = entries[i];
-            let second = entries[j];
+    fn index(&self, entry: Entry) -> Option<usize> {
+        usize::try_from(i64::from(entry) - self.lowest).ok()
+    }
+}
 
-            if let Some(&third) = dictionary.get(&(2020 - (first + second))) {
-                return Some(first * second * third);
+impl Dictionary for LookupArray {
+    fn slot(&self, entry: Entry) -> Option<&usize> {
+        self.firsts.get(self.index(entry)?)
+    }
+
+    fn slot_mut(&mut self, entry: Entry) -> Option<&mut usize> {
+        let index = self.index(entry)?;
+        self.firsts.get_mut(index)
+    }
+}
+
+/// After each index, the index of the next entry equal to it, or the number of entries, so
+/// that the indices of an entry follow from the first one in the dictionary. The dictionary
+/// is left without the entries.
+fn next_equal(dictionary: &mut impl Dictionary, entries: &[Entry]) -> Vec<usize> {
+    let mut next = vec![entries.len(); entries.len()];
+
+    for (index, &entry) in entries.iter().enumerate().rev() {
+        if let Some(first) = dictionary.slot_mut(entry) {
+            if *first != UNSEEN {
+                next[index] = *first;
             }
+
+            *first = index;
         }
     }
 
-    None
+    dictionary.remove(entries);
+    next
 }
 
-#[aoc(day1, part2, BoolArray)]
-fn part2_bool_array(entries: &[Entry]) -> Option<Entry> {
-    let dictionary: [bool; 2021] = entries.iter().fold([false; 2021], |mut acc, entry| {
-        acc[*entry as usize] = true;
-        acc
-    });
+/// The indices of `entries[..end]` equal to `entry`, from the first one the dictionary has.
+fn indices<'a>(
+    dictionary: &impl Dictionary,
+    next: &'a [usize],
+    end: usize,
+    entry: Entry,
+) -> impl Iterator<Item = usize> + 'a {
+    iter::successors(dictionary.first(entry), move |&index| {
+        next.get(index).copied()
+    })
+    .take_while(move |&index| index < end)
+}
 
-    for i in 0..entries.len() - 1 {
-        for j in i + 1..entries.len() {
-            let first = entries[i];
-            let second = entries[j];
-            let third = 2020 - (first + second);
+fn pairs_in(
+    dictionary: &mut impl Dictionary,
+    entries: &[Entry],
+    target: Entry,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) {
+    let next = next_equal(dictionary, entries);
 
-            if third >= 0 && dictionary[third as usize] {
-                return Some(first * second * third);
+    for (second, &entry) in entries.iter().enumerate() {
+        for first in indices(dictionary, &next, second, target - entry) {
+            if visit(&[first, second]).is_break() {
+                return;
             }
         }
+
+        dictionary.insert(entry, second);
     }
+}
+
+fn triples_in(
+    dictionary: &mut impl Dictionary,
+    entries: &[Entry],
+    target: Entry,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) {
+    let next = next_equal(dictionary, entries);
+
+    for (first, &first_entry) in entries.iter().enumerate() {
+        for (third, &entry) in entries.iter().enumerate().skip(first + 1) {
+            let second_entry = target - (first_entry + entry);
+
+            for second in indices(dictionary, &next, third, second_entry) {
+                if visit(&[first, second, third]).is_break() {
+                    return;
+                }
+            }
+
+            dictionary.insert(entry, third);
+        }
 
-    None
+        dictionary.remove(&entries[first + 1..]);
+    }
 }
 
-#[aoc(day1, part2, Itertools)]
-fn part2_itertools(entries: &[Entry]) -> Option<Entry> {
-    entries
-        .iter()
+fn pairs(entries: &[Entry], target: Entry, visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>) {
+    pairs_in(&mut HashMap::new(), entries, target, visit);
+}
+
+fn triples(entries: &[Entry], target: Entry, visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>) {
+    triples_in(&mut HashMap::new(), entries, target, visit);
+}
+
+fn pairs_bool_array(
+    entries: &[Entry],
+    target: Entry,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) {
+    pairs_in(
+        &mut LookupArray::new(entries, 1, target),
+        entries,
+        target,
+        visit,
+    );
+}
+
+fn triples_bool_array(
+    entries: &[Entry],
+    target: Entry,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) {
+    triples_in(
+        &mut LookupArray::new(entries, 2, target),
+        entries,
+        target,
+        visit,
+    );
+}
+
+fn pairs_itertools(
+    entries: &[Entry],
+    target: Entry,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) {
+    let _ = (0..entries.len())
+        .tuple_combinations()
+        .filter(|&(first, second)| entries[first] + entries[second] == target)
+        .try_for_each(|(first, second)| visit(&[first, second]));
+}
+
+fn triples_itertools(
+    entries: &[Entry],
+    target: Entry,
+    visit: &mut dyn FnMut(&[usize]) -> ControlFlow<()>,
+) {
+    let _ = (0..entries.len())
         .tuple_combinations()
-        .find(|(first, second, third)| *first + *second + *third == 2020)
-        .map(|(first, second, third)| first * second * third)
+        .filter(|&(first, second, third)| {
+            entries[first] + entries[second] + entries[third] == target
+        })
+        .try_for_each(|(first, second, third)| visit(&[first, second, third]));
+}
+
+/// The solvers of both parts, for their reports.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Variant {
+    Default,
+    BoolArray,
+    Itertools,
+}
+
+impl Variant {
+    fn pairs(self) -> Tuples {
+        match self {
+            Variant::Default => pairs,
+            Variant::BoolArray => pairs_bool_array,
+            Variant::Itertools => pairs_itertools,
+        }
+    }
+
+    fn triples(self) -> Tuples {
+        match self {
+            Variant::Default => triples,
+            Variant::BoolArray => triples_bool_array,
+            Variant::Itertools => triples_itertools,
+        }
+    }
+}
+
+/// The entries a solver found adding up to the target, for audits.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Report {
+    /// The indices of the entries in the report, in increasing order, which are also their
+    /// lines from 0: distinct, so that no entry is used twice, even when it is repeated.
+    pub indices: Vec<usize>,
+    pub entries: Vec<Entry>,
+    pub product: i128,
+    /// Whether other lines also add up to the target, be they a repeated entry.
+    pub ambiguous: bool,
+}
+
+fn report(entries: &[Entry], search: Tuples, target: Entry) -> Option<Report> {
+    let mut found = Vec::new();
+
+    search(entries, target, &mut |indices| {
+        found.push(indices.to_vec());
+
+        if found.len() < 2 {
+            ControlFlow::Continue(())
+        } else {
+            ControlFlow::Break(())
+        }
+    });
+
+    let mut found = found.into_iter();
+    let indices = found.next()?;
+    let entries = indices
+        .iter()
+        .map(|&index| entries[index])
+        .collect::<Vec<_>>();
+
+    Some(Report {
+        // Three entries of 32 bits multiply to fewer than 128 bits.
+        product: entries.iter().map(|&entry| i128::from(entry)).product(),
+        indices,
+        entries,
+        ambiguous: found.next().is_some(),
+    })
+}
+
+/// The report of the two entries the variant finds for part 1, for the sum given by `params`.
+pub fn part1_report(entries: &[Entry], variant: Variant, params: &Params) -> Option<Report> {
+    report(entries, variant.pairs(), params.target)
+}
+
+/// The report of the three entries the variant finds for part 2, for the sum given by
+/// `params`.
+pub fn part2_report(entries: &[Entry], variant: Variant, params: &Params) -> Option<Report> {
+    report(entries, variant.triples(), params.target)
+}
+
+/// How [`KSum`] finds the last two entries of a tuple, once the others are chosen.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Strategy {
+    /// A bitset of the entries seen so far, offset by the smallest entry, taking memory in
+    /// proportion to the range of the entries, in front of the positions of those seen.
+    Bitset,
+    /// A hash map of the entries seen so far.
+    Hash,
+    /// Two pointers closing in from both ends of the entries, sorted once for all the tuples.
+    TwoPointers,
+}
+
+/// The widest range of entries [`KSum::new`] picks [`Strategy::Bitset`] for, whatever their
+/// number.
+const BITSET_SPAN: i64 = 1 << 16;
+
+/// The tuples of `k` entries, at distinct indices, adding up to a target.
+#[derive(Clone, Debug)]
+pub struct KSum<'a> {
+    entries: &'a [Entry],
+    k: usize,
+    target: i64,
+    strategy: Strategy,
+}
+
+impl<'a> KSum<'a> {
+    /// The `k`-sum of `target`, with a bitset if the range of the entries is narrow enough and
+    /// else, with two pointers for the many 2-sums of `k` from 3 or a hash map for one.
+    pub fn new(entries: &'a [Entry], k: usize, target: i64) -> Self {
+        let span = match (entries.iter().min(), entries.iter().max()) {
+            (Some(&min), Some(&max)) => max as i64 - min as i64 + 1,
+            _ => 0,
+        };
+        let strategy = if span <= BITSET_SPAN.max(64 * entries.len() as i64) {
+            Strategy::Bitset
+        } else if k >= 3 {
+            Strategy::TwoPointers
+        } else {
+            Strategy::Hash
+        };
+
+        KSum {
+            entries,
+            k,
+            target,
+            strategy,
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// The indices of the first tuple found, in increasing order. Which tuple is found first
+    /// depends on the strategy.
+    pub fn first(&self) -> Option<Vec<usize>> {
+        let mut first = None;
+
+        self.search(|tuple| {
+            first = Some(tuple.to_vec());
+            ControlFlow::Break(())
+        });
+
+        first
+    }
+
+    /// The indices of every tuple, each in increasing order, sorted.
+    pub fn all(&self) -> Vec<Vec<usize>> {
+        let mut all = Vec::new();
+
+        self.search(|tuple| {
+            all.push(tuple.to_vec());
+            ControlFlow::Continue(())
+        });
+
+        all.sort_unstable();
+        all
+    }
+
+    pub fn count(&self) -> usize {
+        let mut count = 0;
+
+        self.search(|_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+
+        count
+    }
+
+    /// The product of the entries of a tuple, as long as it fits.
+    pub fn product(&self, tuple: &[usize]) -> Option<i64> {
+        tuple.iter().try_fold(1_i64, |product, &index| {
+            product.checked_mul(self.entries[index] as i64)
+        })
+    }
+
+    /// Visits the tuples, returning the number of positions the search went through.
+    fn search(&self, mut visit: impl FnMut(&[usize]) -> ControlFlow<()>) -> usize {
+        let mut indices = (0..self.entries.len()).collect::<Vec<_>>();
+
+        if self.strategy == Strategy::TwoPointers {
+            indices.sort_by_key(|&index| self.entries[index]);
+        }
+
+        let values = indices
+            .iter()
+            .map(|&index| self.entries[index] as i64)
+            .collect::<Vec<_>>();
+        let min = values.iter().min().copied().unwrap_or(0);
+        let bits = match self.strategy {
+            Strategy::Bitset => {
+                let max = values.iter().max().copied().unwrap_or(0);
+                vec![0; ((max - min) / 64 + 1) as usize]
+            }
+            _ => Vec::new(),
+        };
+
+        let mut search = Search {
+            values: &values,
+            k: self.k,
+            strategy: self.strategy,
+            min,
+            bits,
+            last: HashMap::new(),
+            previous: vec![None; values.len()],
+            steps: 0,
+        };
+        let mut tuple = Vec::with_capacity(self.k);
+
+        let _ = search.tuples(
+            0,
+            self.target,
+            &mut Vec::with_capacity(self.k),
+            &mut |positions| {
+                tuple.clear();
+                tuple.extend(positions.iter().map(|&position| indices[position]));
+                tuple.sort_unstable();
+                visit(&tuple)
+            },
+        );
+
+        search.steps
+    }
+}
+
+/// A depth-first search of the tuples, by their positions in `values`.
+struct Search<'a> {
+    values: &'a [i64],
+    k: usize,
+    strategy: Strategy,
+    min: i64,
+    bits: Vec<u64>,
+    /// The last position of each value seen, for the values the bitset has.
+    last: HashMap<i64, usize>,
+    /// The position of the value seen before each one, if the same.
+    previous: Vec<Option<usize>>,
+    /// The number of positions gone through so far.
+    steps: usize,
+}
+
+type Visit<'a> = dyn FnMut(&[usize]) -> ControlFlow<()> + 'a;
+
+impl Search<'_> {
+    /// Completes the tuple of the `chosen` positions with positions from `start` adding up to
+    /// `remaining`.
+    fn tuples(
+        &mut self,
+        start: usize,
+        remaining: i64,
+        chosen: &mut Vec<usize>,
+        visit: &mut Visit,
+    ) -> ControlFlow<()> {
+        match self.k - chosen.len() {
+            0 if remaining == 0 => visit(chosen),
+            0 => ControlFlow::Continue(()),
+            1 => {
+                for last in start..self.values.len() {
+                    self.steps += 1;
+
+                    if self.values[last] == remaining {
+                        chosen.push(last);
+                        let flow = visit(chosen);
+                        chosen.pop();
+                        flow?;
+                    }
+                }
+
+                ControlFlow::Continue(())
+            }
+            2 => self.pairs(start, remaining, &mut |first, second| {
+                chosen.extend([first, second]);
+                let flow = visit(chosen);
+                chosen.truncate(chosen.len() - 2);
+                flow
+            }),
+            _ => {
+                for next in start..self.values.len() {
+                    self.steps += 1;
+                    chosen.push(next);
+                    let flow = self.tuples(next + 1, remaining - self.values[next], chosen, visit);
+                    chosen.pop();
+                    flow?;
+                }
+
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    /// Visits the pairs of positions from `start` adding up to `remaining`.
+    fn pairs(
+        &mut self,
+        start: usize,
+        remaining: i64,
+        visit: &mut dyn FnMut(usize, usize) -> ControlFlow<()>,
+    ) -> ControlFlow<()> {
+        let values = self.values;
+
+        match self.strategy {
+            Strategy::Bitset => {
+                let flow = (start..values.len()).try_for_each(|second| {
+                    self.steps += 1;
+                    let wanted = remaining - values[second];
+
+                    // Only the values seen are looked up, and only their positions visited.
+                    if self.bit(wanted) {
+                        let last = self.last.get(&wanted).copied();
+                        let previous = &self.previous;
+
+                        for first in iter::successors(last, |&first| previous[first]) {
+                            self.steps += 1;
+                            visit(first, second)?;
+                        }
+                    }
+
+                    self.set_bit(values[second], true);
+                    self.previous[second] = self.last.insert(values[second], second);
+                    ControlFlow::Continue(())
+                });
+
+                for &value in &values[start..] {
+                    self.set_bit(value, false);
+                }
+
+                self.last.clear();
+                flow
+            }
+            Strategy::Hash => {
+                let mut seen = HashMap::<i64, Vec<usize>>::new();
+
+                for (second, &value) in values.iter().enumerate().skip(start) {
+                    self.steps += 1;
+
+                    for &first in seen.get(&(remaining - value)).into_iter().flatten() {
+                        self.steps += 1;
+                        visit(first, second)?;
+                    }
+
+                    seen.entry(value).or_default().push(second);
+                }
+
+                ControlFlow::Continue(())
+            }
+            Strategy::TwoPointers => {
+                let (mut low, mut high) = (start, values.len().saturating_sub(1));
+
+                while low < high {
+                    self.steps += 1;
+                    let sum = values[low] + values[high];
+
+                    if sum < remaining {
+                        low += 1;
+                    } else if sum > remaining {
+                        high -= 1;
+                    } else if values[low] == values[high] {
+                        // Every pair of the run of equal values in between matches.
+                        for first in low..high {
+                            for second in first + 1..=high {
+                                visit(first, second)?;
+                            }
+                        }
+
+                        break;
+                    } else {
+                        let low_end = (low..=high).find(|&i| values[i] != values[low]).unwrap();
+                        let high_start =
+                            (low..=high).rfind(|&i| values[i] != values[high]).unwrap() + 1;
+
+                        for first in low..low_end {
+                            for second in high_start..=high {
+                                visit(first, second)?;
+                            }
+                        }
+
+                        low = low_end;
+                        high = high_start - 1;
+                    }
+                }
+
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    fn bit(&self, value: i64) -> bool {
+        let offset = value - self.min;
+
+        offset >= 0
+            && self
+                .bits
+                .get((offset / 64) as usize)
+                .is_some_and(|word| word >> (offset % 64) & 1 == 1)
+    }
+
+    fn set_bit(&mut self, value: i64, set: bool) {
+        let offset = value - self.min;
+        let word = &mut self.bits[(offset / 64) as usize];
+
+        if set {
+            *word |= 1 << (offset % 64);
+        } else {
+            *word &= !(1 << (offset % 64));
+        }
+    }
+}
+
+/// Same as [`part1`], as the 2-sum of [`KSum`].
+#[aoc(day1, part1, KSum)]
+pub fn part1_k_sum(entries: &[Entry]) -> Option<Entry> {
+    KSum::new(entries, 2, 2020)
+        .first()
+        .map(|tuple| tuple.iter().map(|&index| entries[index]).product())
+}
+
+/// Same as [`part2`], as the 3-sum of [`KSum`].
+#[aoc(day1, part2, KSum)]
+pub fn part2_k_sum(entries: &[Entry]) -> Option<Entry> {
+    KSum::new(entries, 3, 2020)
+        .first()
+        .map(|tuple| tuple.iter().map(|&index| entries[index]).product())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
+    use crate::random::Rng;
 
     #[test]
     fn part1_example() {
@@ -131,4 +809,212 @@ mod tests {
             Some(241_861_950)
         );
     }
+
+    #[test]
+    fn other_target() {
+        let params = Params { target: 1_020 };
+
+        assert_eq!(part1_with(&[1_721, 979, 366, 41], &params), Some(979 * 41));
+        assert_eq!(
+            part2_with(&[1_721, 979, 366, 299, 675, 1_456], &params),
+            None
+        );
+        assert_eq!(
+            part2_with(&[300, 979, 20, 700], &params),
+            Some(300 * 20 * 700)
+        );
+    }
+
+    #[test]
+    fn reports() {
+        let entries = [1_721, 979, 366, 299, 675, 1_456];
+        let params = Params::default();
+
+        for variant in [Variant::Default, Variant::BoolArray, Variant::Itertools] {
+            assert_eq!(
+                part1_report(&entries, variant, &params),
+                Some(Report {
+                    indices: vec![0, 3],
+                    entries: vec![1_721, 299],
+                    product: 514_579,
+                    ambiguous: false,
+                })
+            );
+            assert_eq!(
+                part2_report(&entries, variant, &params).unwrap().indices,
+                vec![1, 2, 4]
+            );
+
+            // A repeated entry is only used once per line it is on.
+            let report = part1_report(&[1_010, 7, 1_010], variant, &params).unwrap();
+
+            assert_eq!(report.indices, vec![0, 2]);
+            assert!(!report.ambiguous);
+            assert_eq!(
+                part1_report(&[5, 5, 2_015, 5], variant, &params)
+                    .unwrap()
+                    .indices,
+                vec![0, 2]
+            );
+            assert_eq!(part1_report(&[1_010, 7], variant, &params), None);
+            assert!(
+                part2_report(&[1_000, 10, 1_010, 10], variant, &params)
+                    .unwrap()
+                    .ambiguous
+            );
+            assert!(
+                part1_report(&[2_000, 20, 1_010, 1_010, 0], variant, &params)
+                    .unwrap()
+                    .ambiguous
+            );
+        }
+
+        let params = Params { target: 4 };
+
+        assert_eq!(
+            part1_report(&[2, -3, 7], Variant::Default, &params)
+                .unwrap()
+                .entries,
+            vec![-3, 7]
+        );
+        for variant in [Variant::BoolArray, Variant::Itertools] {
+            assert_eq!(
+                part1_report(&[2, -3, 7], variant, &params),
+                part1_report(&[2, -3, 7], Variant::Default, &params)
+            );
+            assert_eq!(
+                part2_report(&[-5, 2, 9, -1, 0], variant, &params)
+                    .unwrap()
+                    .entries,
+                vec![-5, 9, 0]
+            );
+        }
+        assert_eq!(
+            part1_report(&[-1], Variant::BoolArray, &Params { target: -2 }),
+            None
+        );
+        // The lookup array only goes up to the largest entry, however large the target.
+        assert_eq!(
+            part2_report(
+                &[1, 2, 3],
+                Variant::BoolArray,
+                &Params { target: Entry::MAX }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn k_sums() {
+        let entries = [1_721, 979, 366, 299, 675, 1_456];
+        let strategies = [Strategy::Bitset, Strategy::Hash, Strategy::TwoPointers];
+
+        assert_eq!(part1_k_sum(&entries), Some(514_579));
+        assert_eq!(part2_k_sum(&entries), Some(241_861_950));
+        assert_eq!(KSum::new(&entries, 3, 2020).strategy(), Strategy::Bitset);
+
+        for strategy in strategies {
+            let k_sum = KSum::new(&entries, 3, 2020).with_strategy(strategy);
+
+            assert_eq!(k_sum.first(), Some(vec![1, 2, 4]));
+            assert_eq!(k_sum.product(&[1, 2, 4]), Some(241_861_950));
+            assert_eq!(k_sum.all(), vec![vec![1, 2, 4]]);
+            assert_eq!(
+                KSum::new(&entries, 2, 2020).with_strategy(strategy).count(),
+                1
+            );
+        }
+
+        // Negative and large entries, far too wide a range for a bitset.
+        let entries = [-5, 1_000_000_000, 7, -999_999_995, 3, 2, 0];
+        let k_sum = KSum::new(&entries, 2, 5);
+
+        assert_eq!(k_sum.strategy(), Strategy::Hash);
+        assert_eq!(KSum::new(&entries, 3, 5).strategy(), Strategy::TwoPointers);
+
+        for strategy in strategies {
+            assert_eq!(
+                k_sum.clone().with_strategy(strategy).all(),
+                vec![vec![1, 3], vec![4, 5]]
+            );
+            assert_eq!(
+                KSum::new(&entries, 3, 5).with_strategy(strategy).all(),
+                vec![vec![0, 2, 4], vec![1, 3, 6], vec![4, 5, 6]]
+            );
+        }
+
+        // Repeated entries make as many tuples as there are ways to pick them.
+        for strategy in strategies {
+            let ones = [1; 5];
+
+            assert_eq!(KSum::new(&ones, 2, 2).with_strategy(strategy).count(), 10);
+            assert_eq!(KSum::new(&ones, 3, 3).with_strategy(strategy).count(), 10);
+            assert_eq!(KSum::new(&ones, 6, 6).with_strategy(strategy).count(), 0);
+            assert_eq!(KSum::new(&ones, 1, 1).with_strategy(strategy).count(), 5);
+            assert_eq!(KSum::new(&[], 2, 0).with_strategy(strategy).first(), None);
+        }
+    }
+
+    #[test]
+    fn k_sums_with_many_tuples() {
+        // A permutation of 1..=n has n / 2 pairs adding up to n + 1, which a quadratic search
+        // would go through n² / 2 positions to find.
+        let n = 200_000;
+        let mut entries = (1..=n).collect::<Vec<_>>();
+        Rng::new(3).shuffle(&mut entries);
+
+        let k_sum = KSum::new(&entries, 2, i64::from(n) + 1);
+
+        assert_eq!(k_sum.strategy(), Strategy::Bitset);
+        assert_eq!(k_sum.count(), n as usize / 2);
+        assert!(k_sum.search(|_| ControlFlow::Continue(())) <= 2 * n as usize);
+    }
+
+    #[test]
+    fn k_sums_agree_with_brute_force() {
+        let mut rng = Rng::new(1);
+
+        for _ in 0..50 {
+            let entries = (0..rng.between(0, 12))
+                .map(|_| rng.between(-20, 20) as Entry)
+                .collect::<Vec<_>>();
+            let target = rng.between(-30, 30);
+
+            for k in 1..=4 {
+                let expected = (0..entries.len())
+                    .combinations(k)
+                    .filter(|tuple| {
+                        tuple
+                            .iter()
+                            .map(|&index| entries[index] as i64)
+                            .sum::<i64>()
+                            == target
+                    })
+                    .collect::<Vec<_>>();
+
+                for strategy in [Strategy::Bitset, Strategy::Hash, Strategy::TwoPointers] {
+                    let k_sum = KSum::new(&entries, k, target).with_strategy(strategy);
+
+                    assert_eq!(k_sum.all(), expected, "{:?} {} {:?}", entries, k, strategy);
+                    assert_eq!(k_sum.count(), expected.len());
+                    assert_eq!(k_sum.first().is_some(), !expected.is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn streamed() {
+        let input = example(1, "example");
+
+        assert_eq!(part1_stream(input.as_bytes()).unwrap(), Some(514_579));
+        assert_eq!(part2_stream(input.as_bytes()).unwrap(), Some(241_861_950));
+        assert_eq!(part1_stream("1010\n".as_bytes()).unwrap(), None);
+        assert_eq!(part2_stream("5\n2010".as_bytes()).unwrap(), None);
+        assert_eq!(
+            part2_stream("5\n5\n2010".as_bytes()).unwrap(),
+            Some(5 * 5 * 2010)
+        );
+        assert!(part1_stream("1721\nx\n299".as_bytes()).is_err());
+    }
 }
@@ -1,14 +1,24 @@
+//! Day 10: Adapter Array.
+
+use crate::overflow::Overflow;
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 
-type Joltage = u64;
+pub type Joltage = u64;
 
+/// Parses the adapter joltages and returns them sorted, together with the charging outlet (0)
+/// and the built-in adapter (3 higher than the highest one).
 #[aoc_generator(day10)]
-fn parse_input(input: &str) -> Vec<Joltage> {
-    let mut entries: Vec<Joltage> = input.lines().map(|l| l.parse().unwrap()).collect();
+pub fn parse(input: &str) -> Result<Vec<Joltage>, ParseError> {
+    let source = Source::new(10, input);
+    let mut entries = input
+        .lines()
+        .map(|l| source.parse(l))
+        .collect::<Result<Vec<Joltage>, _>>()?;
     entries.push(0);
     entries.sort_unstable();
     entries.push(entries.last().unwrap() + 3);
-    entries
+    Ok(entries)
 }
 
 fn count_ones_and_threes(entries: &[Joltage]) -> (usize, usize) {
@@ -25,13 +35,16 @@ fn count_ones_and_threes(entries: &[Joltage]) -> (usize, usize) {
         })
 }
 
+/// Multiplies the number of 1-jolt differences by the number of 3-jolt differences.
 #[aoc(day10, part1)]
-fn part1(entries: &[Joltage]) -> usize {
+pub fn part1(entries: &[Joltage]) -> usize {
     let (ones, threes) = count_ones_and_threes(entries);
     ones * threes
 }
 
-fn count_arrangements(cluster: &[u64]) -> usize {
+/// Counts the arrangements of a cluster one by one, and so could not count to an overflow of
+/// 128 bits anyway.
+fn count_arrangements(cluster: &[u64]) -> u128 {
     if cluster.len() <= 1 {
         return 1;
     }
@@ -56,8 +69,10 @@ fn count_arrangements(cluster: &[u64]) -> usize {
     count
 }
 
+/// Counts the distinct adapter arrangements connecting the outlet to the device, the product
+/// of those of the clusters between 3-jolt gaps, which grows exponentially with the adapters.
 #[aoc(day10, part2)]
-fn part2(entries: &[Joltage]) -> usize {
+pub fn part2(entries: &[Joltage]) -> Result<u128, Overflow> {
     let mut start = 0;
     let mut end = 1;
     let mut count = 1;
@@ -67,14 +82,16 @@ fn part2(entries: &[Joltage]) -> usize {
             end += 1;
         }
 
-        count *= count_arrangements(&entries[start..end]);
+        count = count_arrangements(&entries[start..end])
+            .checked_mul(count)
+            .ok_or(Overflow("the number of arrangements"))?;
         start = end;
 
         while entries[start] - entries[start - 1] >= 3 {
             start += 1;
 
             if start >= entries.len() {
-                return count;
+                return Ok(count);
             }
         }
 
@@ -86,65 +103,42 @@ fn part2(entries: &[Joltage]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static SMALL_TEST_INPUT: &str = r"16
-10
-15
-5
-1
-11
-7
-19
-6
-12
-4";
-    static LARGER_TEST_INPUT: &str = r"28
-33
-18
-42
-31
-14
-46
-20
-48
-47
-24
-23
-49
-45
-19
-38
-39
-11
-1
-32
-25
-35
-8
-17
-7
-9
-4
-2
-34
-10
-3";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            count_ones_and_threes(&parse_input(SMALL_TEST_INPUT)),
+            count_ones_and_threes(&parse(example(10, "example")).unwrap()),
             (7, 5)
         );
         assert_eq!(
-            count_ones_and_threes(&parse_input(LARGER_TEST_INPUT)),
+            count_ones_and_threes(&parse(example(10, "larger")).unwrap()),
             (22, 10)
         );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(SMALL_TEST_INPUT)), 8);
-        assert_eq!(part2(&parse_input(LARGER_TEST_INPUT)), 19_208);
+        assert_eq!(part2(&parse(example(10, "example")).unwrap()), Ok(8));
+        assert_eq!(part2(&parse(example(10, "larger")).unwrap()), Ok(19_208));
+    }
+
+    #[test]
+    fn many_arrangements() {
+        // Clusters of 5 adapters 1 jolt apart, 3 jolts apart from each other, each with 7
+        // arrangements.
+        let clusters = |count: u64| {
+            (0..count)
+                .flat_map(|cluster| (7 * cluster..=7 * cluster + 4).filter(|&j| j > 0))
+                .map(|joltage| joltage.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        assert_eq!(part2(&parse(&clusters(30)).unwrap()), Ok(7u128.pow(30)));
+        assert_eq!(
+            part2(&parse(&clusters(50)).unwrap()),
+            Err(Overflow("the number of arrangements"))
+        );
     }
 }
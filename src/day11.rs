@@ -1,261 +1,263 @@
+//! Day 11: Seating System.
+
+use crate::animation::{Animation, Colour, Frame};
+use crate::automaton::{Automaton, Cubic, Graph, LifeLike, Topology};
+use crate::geometry::Vector2;
+use crate::parsing::{ParseError, Source};
+use crate::trace::{Event, NoTrace, Tracer};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Eq, PartialEq, Clone)]
-enum TileKind {
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum TileKind {
     Floor,
     EmptySeat,
     OccupiedSeat,
 }
 
-impl TileKind {
-    fn flip(&mut self) {
-        *self = match self {
-            TileKind::Floor => TileKind::Floor,
-            TileKind::EmptySeat => TileKind::OccupiedSeat,
-            TileKind::OccupiedSeat => TileKind::EmptySeat,
-        }
-    }
-}
-
-impl From<char> for TileKind {
-    fn from(c: char) -> Self {
-        match c {
-            '.' => Self::Floor,
-            'L' => Self::EmptySeat,
-            '#' => Self::OccupiedSeat,
-            _ => unreachable!(),
-        }
-    }
-}
-
-#[derive(Eq, PartialEq, Hash, Clone, Debug)]
-struct Position {
-    column: i32,
-    row: i32,
-}
-
-impl Position {
-    fn adjacent_position(&self, direction: &Direction) -> Self {
-        let (column_delta, row_delta) = direction.into();
-
-        Position {
-            column: self.column + column_delta,
-            row: self.row + row_delta,
-        }
-    }
-
-    fn adjacent_positions(&self) -> Vec<Position> {
-        use Direction::*;
-
-        let mut result = Vec::with_capacity(8);
-
-        for direction in &[
-            Northwest, North, Northeast, West, East, Southwest, South, Southeast,
-        ] {
-            result.push(self.adjacent_position(direction));
-        }
-
-        result
+fn parse_tile_kind(source: &Source, c_str: &str) -> Result<TileKind, ParseError> {
+    match c_str {
+        "." => Ok(TileKind::Floor),
+        "L" => Ok(TileKind::EmptySeat),
+        "#" => Ok(TileKind::OccupiedSeat),
+        _ => Err(source.error(c_str, "expected '.', 'L' or '#'")),
     }
 }
 
-#[derive(Clone)]
-struct Layout(HashMap<Position, TileKind>);
-
-#[derive(Debug)]
-enum Direction {
-    Northwest,
-    North,
-    Northeast,
-    West,
-    East,
-    Southwest,
-    South,
-    Southeast,
-}
+/// A tile of the layout, by its column (`x`) and row (`y`).
+pub type Position = Vector2;
 
-impl From<&Direction> for (i32, i32) {
-    fn from(direction: &Direction) -> Self {
-        match direction {
-            Direction::Northwest => (-1, -1),
-            Direction::North => (0, -1),
-            Direction::Northeast => (1, -1),
-            Direction::West => (-1, 0),
-            Direction::East => (1, 0),
-            Direction::Southwest => (-1, 1),
-            Direction::South => (0, 1),
-            Direction::Southeast => (1, 1),
-        }
-    }
-}
+/// The seat layout of the waiting area.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Layout(#[serde(with = "crate::model::pairs")] pub HashMap<Position, TileKind>);
 
+/// Parses the seat layout of floor (`.`), empty (`L`) and occupied (`#`) seats.
 #[aoc_generator(day11)]
-fn parse_input(input: &str) -> Layout {
+pub fn parse(input: &str) -> Result<Layout, ParseError> {
+    let source = Source::new(11, input);
     let mut tiles = HashMap::new();
 
     for (row, line) in input.lines().enumerate() {
-        for (column, c) in line.chars().enumerate() {
+        for (column, c) in line.char_indices() {
             tiles.insert(
-                Position {
-                    column: column as i32,
-                    row: row as i32,
-                },
-                c.into(),
+                (column as i32, row as i32).into(),
+                parse_tile_kind(&source, &line[column..column + c.len_utf8()])?,
             );
         }
     }
 
-    Layout(tiles)
+    Ok(Layout(tiles))
 }
 
-#[derive(Eq, PartialEq)]
-enum RoundResult {
-    Changed,
-    Stabilized,
-}
+/// The first seat seen from every seat in each of the eight directions.
+fn lines_of_sight(layout: &Layout) -> Graph<Position> {
+    let mut graph = HashMap::new();
 
-fn count_adjacent_occupied(layout: &Layout, position: &Position) -> usize {
-    position
-        .adjacent_positions()
-        .iter()
-        .filter_map(|adjacent_position| layout.0.get(adjacent_position))
-        .filter(|&adjacent_tile_kind| *adjacent_tile_kind == TileKind::OccupiedSeat)
-        .count()
-}
+    for (&position, tile_kind) in layout.0.iter() {
+        if *tile_kind == TileKind::Floor {
+            continue;
+        }
 
-fn count_visible_occupied(layout: &Layout, position: &Position) -> usize {
-    use Direction::*;
+        let visible = Position::moore_directions()
+            .filter_map(|direction| {
+                let mut next_position = position + direction;
 
-    let mut count = 0;
+                while let Some(tile_kind) = layout.0.get(&next_position) {
+                    match tile_kind {
+                        TileKind::Floor => next_position += direction,
+                        _ => return Some(next_position),
+                    }
+                }
 
-    for direction in &[
-        Northwest, North, Northeast, West, East, Southwest, South, Southeast,
-    ] {
-        let mut next_position = position.adjacent_position(direction);
+                None
+            })
+            .collect();
 
-        while let Some(tile_kind) = layout.0.get(&next_position) {
-            match tile_kind {
-                TileKind::OccupiedSeat => {
-                    count += 1;
-                    break;
-                }
-                TileKind::EmptySeat => break,
-                TileKind::Floor => next_position = next_position.adjacent_position(direction),
-            }
-        }
+        graph.insert(position, visible);
     }
 
-    count
+    Graph(graph)
 }
 
-fn empty_seat_flip_rule(
-    layout: &Layout,
-    position: &Position,
-    count_function: fn(&Layout, &Position) -> usize,
-) -> bool {
-    count_function(layout, position) == 0
+/// Seats empty out from `threshold` occupied neighbours, and fill up with none.
+fn seating_rule(threshold: usize) -> LifeLike<TileKind> {
+    const NEIGHBOUR_COUNTS: [usize; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+    LifeLike {
+        live: TileKind::OccupiedSeat,
+        dead: TileKind::EmptySeat,
+        birth: &NEIGHBOUR_COUNTS[..1],
+        survival: &NEIGHBOUR_COUNTS[..threshold],
+    }
 }
 
-fn occupied_seat_flip_rule(
+/// Counts the occupied seats once nothing changes any more, or `None` if some seats keep
+/// flipping instead, tracing the seats flipped every round.
+fn count_occupied_when_stable<T>(
     layout: &Layout,
-    position: &Position,
-    count_function: fn(&Layout, &Position) -> usize,
+    topology: T,
     threshold: usize,
-) -> bool {
-    count_function(layout, position) >= threshold
+    tracer: &mut impl Tracer,
+) -> Option<usize>
+where
+    T: Topology<Position = Position>,
+{
+    let mut automaton = Automaton::bounded(layout.0.clone(), topology, seating_rule(threshold));
+    let cycle = automaton.find_cycle_with(usize::MAX, |automaton| {
+        tracer.trace(|| {
+            Event::new(11, "round")
+                .with("round", automaton.generation())
+                .with("flips", automaton.changes())
+                .with("occupied", automaton.count(TileKind::OccupiedSeat))
+        })
+    })?;
+
+    Some(automaton.count(TileKind::OccupiedSeat)).filter(|_| cycle.period == 1)
 }
 
-fn round(
-    layout: &mut Layout,
-    count_function: fn(&Layout, &Position) -> usize,
-    occupied_seats_threshold: usize,
-) -> RoundResult {
-    let mut to_flip = Vec::new();
-
-    for (position, tile_kind) in layout.0.iter() {
-        if (*tile_kind == TileKind::EmptySeat
-            && empty_seat_flip_rule(layout, position, count_function))
-            || (*tile_kind == TileKind::OccupiedSeat
-                && occupied_seat_flip_rule(
-                    layout,
-                    position,
-                    count_function,
-                    occupied_seats_threshold,
-                ))
-        {
-            to_flip.push(position.clone())
-        }
-    }
+/// Counts the occupied seats once the layout stabilizes, looking at adjacent seats only.
+#[aoc(day11, part1)]
+pub fn part1(layout: &Layout) -> Option<usize> {
+    part1_traced(layout, &mut NoTrace)
+}
 
-    for position in to_flip.iter() {
-        if let Some(tile_kind) = layout.0.get_mut(position) {
-            (*tile_kind).flip()
-        }
-    }
+/// Same as [`part1`], tracing every round.
+pub fn part1_traced(layout: &Layout, tracer: &mut impl Tracer) -> Option<usize> {
+    count_occupied_when_stable(layout, Cubic, 4, tracer)
+}
 
-    use RoundResult::*;
+/// Counts the occupied seats once the layout stabilizes, looking at the first visible seats.
+#[aoc(day11, part2)]
+pub fn part2(layout: &Layout) -> Option<usize> {
+    part2_traced(layout, &mut NoTrace)
+}
 
-    if !to_flip.is_empty() {
-        Changed
-    } else {
-        Stabilized
-    }
+/// Same as [`part2`], tracing every round.
+pub fn part2_traced(layout: &Layout, tracer: &mut impl Tracer) -> Option<usize> {
+    count_occupied_when_stable(layout, lines_of_sight(layout), 5, tracer)
 }
 
-fn stabilize(
-    layout: &mut Layout,
-    count_function: fn(&Layout, &Position) -> usize,
-    occupied_seats_threshold: usize,
-) -> usize {
-    use RoundResult::*;
-    let mut round_result = Changed;
+/// The seats of the layout filling up and emptying round after round.
+pub struct Seating<T: Topology<Position = Position>> {
+    automaton: Automaton<T, TileKind, LifeLike<TileKind>>,
+    previous: HashMap<Position, TileKind>,
+}
 
-    while round_result == Changed {
-        round_result = round(layout, count_function, occupied_seats_threshold);
+impl<T: Topology<Position = Position>> Seating<T> {
+    /// The layout of the current round.
+    pub fn layout(&self) -> Layout {
+        Layout(self.automaton.cells().collect())
+    }
+}
+
+impl<T: Topology<Position = Position>> Animation for Seating<T> {
+    fn frame(&self) -> Frame {
+        Frame::new(format!(
+            "day 11, round {}: {} occupied seats",
+            self.automaton.generation(),
+            self.automaton.count(TileKind::OccupiedSeat)
+        ))
+        .grid(self.automaton.cells().map(|(position, tile_kind)| {
+            let cell = match tile_kind {
+                TileKind::Floor => ('.', Colour::Dim),
+                TileKind::EmptySeat => ('L', Colour::Green),
+                TileKind::OccupiedSeat => ('#', Colour::Red),
+            };
+
+            ((position.x(), position.y()), cell)
+        }))
     }
 
-    layout
-        .0
-        .values()
-        .filter(|&tile_kind| *tile_kind == TileKind::OccupiedSeat)
-        .count()
+    /// Stops once the seats stabilize, or flip back to the previous layout.
+    fn step(&mut self) -> bool {
+        let current = self.automaton.cells().collect::<HashMap<_, _>>();
+        let changed = self.automaton.step()
+            && self
+                .automaton
+                .cells()
+                .any(|(position, tile_kind)| self.previous.get(&position) != Some(&tile_kind));
+
+        self.previous = current;
+        changed
+    }
 }
 
-#[aoc(day11, part1)]
-fn part1(layout: &Layout) -> usize {
-    let mut layout = layout.clone();
-    stabilize(&mut layout, count_adjacent_occupied, 4)
+/// The rounds of [`part1`].
+pub fn part1_animation(layout: &Layout) -> Seating<Cubic<2>> {
+    Seating {
+        automaton: Automaton::bounded(layout.0.clone(), Cubic, seating_rule(4)),
+        previous: HashMap::new(),
+    }
 }
 
-#[aoc(day11, part2)]
-fn part2(layout: &Layout) -> usize {
-    let mut layout = layout.clone();
-    stabilize(&mut layout, count_visible_occupied, 5)
+/// The rounds of [`part2`].
+pub fn part2_animation(layout: &Layout) -> Seating<Graph<Position>> {
+    Seating {
+        automaton: Automaton::bounded(layout.0.clone(), lines_of_sight(layout), seating_rule(5)),
+        previous: HashMap::new(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_STARTING_LAYOUT: &str = r"L.LL.LL.LL
-LLLLLLL.LL
-L.L.L..L..
-LLLL.LL.LL
-L.LL.LL.LL
-L.LLLLL.LL
-..L.L.....
-LLLLLLLLLL
-L.LLLLLL.L
-L.LLLLL.LL";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_STARTING_LAYOUT,)), 37);
+        assert_eq!(part1(&parse(example(11, "example")).unwrap()), Some(37));
+        assert_eq!(part1(&parse(".LL.\nLLLL\nLLLL\n.LL.").unwrap()), None);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_STARTING_LAYOUT,)), 26);
+        assert_eq!(part2(&parse(example(11, "example")).unwrap()), Some(26));
+    }
+
+    #[test]
+    fn animation() {
+        let mut seating = part1_animation(&parse(example(11, "example")).unwrap());
+
+        assert!(seating.step());
+        assert!(seating.step());
+        assert_eq!(
+            seating.frame().to_plain(),
+            "day 11, round 2: 20 occupied seats
+#.LL.L#.##
+#LLLLLL.L#
+L.L.L..L..
+#LLL.LL.L#
+#.LL.LL.LL
+#.LLLL#.##
+..L.L.....
+#LLLLLLLL#
+#.LLLLLL.L
+#.#LLLL.##"
+        );
+
+        while seating.step() {}
+
+        assert_eq!(seating.automaton.generation(), 6);
+
+        let mut seating = part1_animation(&parse(".LL.\nLLLL\nLLLL\n.LL.").unwrap());
+
+        while seating.step() {}
+
+        assert_eq!(seating.automaton.generation(), 2);
+    }
+
+    #[test]
+    fn traced() {
+        let mut events = Vec::new();
+
+        assert_eq!(
+            part1_traced(&parse(example(11, "example")).unwrap(), &mut events),
+            Some(37)
+        );
+        assert_eq!(events[0].get("flips"), Some(&71.into()));
+        assert_eq!(events[0].get("occupied"), Some(&71.into()));
+        assert_eq!(events[1].get("occupied"), Some(&20.into()));
+        assert_eq!(events.last().unwrap().get("flips"), Some(&0.into()));
     }
 }
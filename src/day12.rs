@@ -1,64 +1,16 @@
-use aoc_runner_derive::{aoc, aoc_generator};
-use std::ops::{AddAssign, Mul};
-
-#[derive(Clone)]
-struct Vector2 {
-    x: i32,
-    y: i32,
-}
-
-impl From<(i32, i32)> for Vector2 {
-    fn from((x, y): (i32, i32)) -> Self {
-        Vector2 { x, y }
-    }
-}
-
-type Position = Vector2;
-type Direction = Vector2;
-
-impl AddAssign for Vector2 {
-    fn add_assign(&mut self, rhs: Self) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-    }
-}
-
-impl Mul<i32> for &Vector2 {
-    type Output = Vector2;
-
-    fn mul(self, rhs: i32) -> Self::Output {
-        Vector2 {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
-}
-
-impl Vector2 {
-    fn rotate(&mut self, direction: &TurnDirection, degrees: i32) {
-        use TurnDirection::*;
+//! Day 12: Rain Risk.
 
-        let (cos, sin) = match degrees / 90 % 4 {
-            0 => (1, 0),
-            1 => (0, 1),
-            2 => (-1, 0),
-            3 => (0, -1),
-            _ => unreachable!(),
-        };
-
-        let sign = match direction {
-            Right => -1,
-            Left => 1,
-        };
+use crate::animation::{Animation, Colour, Frame};
+use crate::geometry::Vector2;
+use crate::parsing::{ParseError, Source};
+use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 
-        *self = Vector2 {
-            x: self.x * cos - self.y * sin * sign,
-            y: self.x * sin * sign + self.y * cos,
-        }
-    }
-}
+pub type Position = Vector2;
+pub type Direction = Vector2;
 
-enum MoveDirection {
+#[derive(Clone, Serialize, Deserialize)]
+pub enum MoveDirection {
     North,
     South,
     East,
@@ -78,39 +30,41 @@ impl From<&MoveDirection> for Vector2 {
     }
 }
 
-enum TurnDirection {
+#[derive(Clone, Serialize, Deserialize)]
+pub enum TurnDirection {
     Left,
     Right,
 }
 
-enum Action {
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Action {
     MoveInDirection(MoveDirection),
     Turn(TurnDirection),
     MoveForward,
 }
 
-impl From<char> for Action {
-    fn from(c: char) -> Self {
-        use Action::*;
-        use MoveDirection::*;
-        use TurnDirection::*;
-
-        match c {
-            'N' => MoveInDirection(North),
-            'S' => MoveInDirection(South),
-            'E' => MoveInDirection(East),
-            'W' => MoveInDirection(West),
-            'L' => Turn(Left),
-            'R' => Turn(Right),
-            'F' => MoveForward,
-            _ => unreachable!(),
-        }
+fn parse_action(source: &Source, action_str: &str) -> Result<Action, ParseError> {
+    use Action::*;
+    use MoveDirection::*;
+    use TurnDirection::*;
+
+    match action_str {
+        "N" => Ok(MoveInDirection(North)),
+        "S" => Ok(MoveInDirection(South)),
+        "E" => Ok(MoveInDirection(East)),
+        "W" => Ok(MoveInDirection(West)),
+        "L" => Ok(Turn(Left)),
+        "R" => Ok(Turn(Right)),
+        "F" => Ok(MoveForward),
+        _ => Err(source.error(action_str, "unknown action")),
     }
 }
 
-struct Instruction {
-    action: Action,
-    value: i32,
+/// A single navigation instruction.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Instruction {
+    pub action: Action,
+    pub value: i32,
 }
 
 #[derive(Clone)]
@@ -119,95 +73,226 @@ struct Coordinates {
     waypoint: Direction,
 }
 
+/// Parses the navigation instructions, one `<action><value>` per line.
 #[aoc_generator(day12)]
-fn parse_input(input: &str) -> Vec<Instruction> {
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let source = Source::new(12, input);
     input
         .lines()
-        .map(|line| Instruction {
-            action: line.chars().next().unwrap().into(),
-            value: (&line[1..]).parse::<i32>().unwrap(),
+        .map(|line| {
+            let split = line.char_indices().nth(1).map_or(line.len(), |(i, _)| i);
+
+            Ok(Instruction {
+                action: parse_action(&source, &line[..split])?,
+                value: source.parse(&line[split..])?,
+            })
         })
         .collect()
 }
 
+fn follow(coordinates: &mut Coordinates, instruction: &Instruction, use_waypoint: bool) {
+    use Action::*;
+
+    match &instruction.action {
+        MoveInDirection(direction) => {
+            let direction = Vector2::from(direction) * instruction.value;
+
+            if use_waypoint {
+                coordinates.waypoint += direction;
+            } else {
+                coordinates.position += direction;
+            }
+        }
+        Turn(direction) => {
+            let quarter_turns = match direction {
+                TurnDirection::Left => instruction.value / 90,
+                TurnDirection::Right => -instruction.value / 90,
+            };
+
+            coordinates.waypoint = coordinates.waypoint.rotate(0, 1, quarter_turns);
+        }
+        Action::MoveForward => {
+            coordinates.position += coordinates.waypoint * instruction.value;
+        }
+    }
+}
+
 fn navigate(
     starting_coordinates: &Coordinates,
     instructions: &[Instruction],
     use_waypoint: bool,
 ) -> Coordinates {
-    use Action::*;
-
     let mut destination = starting_coordinates.clone();
 
     for instruction in instructions {
-        match &instruction.action {
-            MoveInDirection(direction) => {
-                let mut direction: Vector2 = direction.into();
-                direction = &direction * instruction.value;
-
-                if use_waypoint {
-                    destination.waypoint += direction;
-                } else {
-                    destination.position += direction;
-                }
-            }
-            Turn(direction) => {
-                destination.waypoint.rotate(direction, instruction.value);
-            }
-            Action::MoveForward => {
-                destination.position += &destination.waypoint * instruction.value;
+        follow(&mut destination, instruction, use_waypoint);
+    }
+
+    destination
+}
+
+/// The ship following one instruction at a time, its course scaled down to fit the terminal.
+pub struct Voyage {
+    instructions: Vec<Instruction>,
+    coordinates: Coordinates,
+    use_waypoint: bool,
+    course: Vec<Position>,
+}
+
+impl Voyage {
+    /// The size of the map the course is scaled down to.
+    const MAP: (i32, i32) = (72, 24);
+
+    fn new(instructions: &[Instruction], waypoint: Direction, use_waypoint: bool) -> Self {
+        Voyage {
+            instructions: instructions.to_vec(),
+            coordinates: Coordinates {
+                position: Vector2::ZERO,
+                waypoint,
+            },
+            use_waypoint,
+            course: vec![Vector2::ZERO],
+        }
+    }
+}
+
+impl Voyage {
+    /// The positions of the ship so far, from the start.
+    pub fn course(&self) -> &[Position] {
+        &self.course
+    }
+}
+
+impl Animation for Voyage {
+    fn frame(&self) -> Frame {
+        let position = self.coordinates.position;
+        let (min, max) =
+            self.course
+                .iter()
+                .fold((Vector2::ZERO, Vector2::ZERO), |(min, max), position| {
+                    (
+                        (min.x().min(position.x()), min.y().min(position.y())).into(),
+                        (max.x().max(position.x()), max.y().max(position.y())).into(),
+                    )
+                });
+        let scale = ((max.x() - min.x()) / Self::MAP.0).max((max.y() - min.y()) / Self::MAP.1) + 1;
+        // North is up, so rows go down as y goes up.
+        let cell = |position: Position| {
+            (
+                position.x().div_euclid(scale),
+                (-position.y()).div_euclid(scale),
+            )
+        };
+
+        Frame::new(format!(
+            "day 12, instruction {}/{}: ship at ({}, {}), {} from the start, 1:{}",
+            self.course.len() - 1,
+            self.instructions.len(),
+            position.x(),
+            position.y(),
+            position.manhattan_length(),
+            scale
+        ))
+        .grid(
+            self.course
+                .iter()
+                .map(|&position| (cell(position), ('.', Colour::Dim)))
+                .chain(Some((cell(Vector2::ZERO), ('o', Colour::Cyan))))
+                .chain(Some((cell(position), ('@', Colour::Yellow)))),
+        )
+    }
+
+    fn step(&mut self) -> bool {
+        match self.instructions.get(self.course.len() - 1) {
+            Some(instruction) => {
+                follow(&mut self.coordinates, instruction, self.use_waypoint);
+                self.course.push(self.coordinates.position);
+                true
             }
+            None => false,
         }
     }
+}
 
-    destination
+/// The course of [`part1`].
+pub fn part1_animation(instructions: &[Instruction]) -> Voyage {
+    Voyage::new(instructions, (1, 0).into(), false)
 }
 
+/// The course of [`part2`].
+pub fn part2_animation(instructions: &[Instruction]) -> Voyage {
+    Voyage::new(instructions, (10, 1).into(), true)
+}
+
+/// Returns the Manhattan distance travelled by the ship, moving it directly.
 #[aoc(day12, part1)]
-fn part1(instructions: &[Instruction]) -> i32 {
+pub fn part1(instructions: &[Instruction]) -> i32 {
     let destination = navigate(
         &Coordinates {
-            position: Vector2 { x: 0, y: 0 },
-            waypoint: Vector2 { x: 1, y: 0 },
+            position: Vector2::ZERO,
+            waypoint: (1, 0).into(),
         },
         instructions,
         false,
     );
 
-    destination.position.x.abs() + destination.position.y.abs()
+    destination.position.manhattan_length()
 }
 
+/// Returns the Manhattan distance travelled by the ship, moving its waypoint.
 #[aoc(day12, part2)]
-fn part2(instructions: &[Instruction]) -> i32 {
+pub fn part2(instructions: &[Instruction]) -> i32 {
     let destination = navigate(
         &Coordinates {
-            position: Vector2 { x: 0, y: 0 },
-            waypoint: Vector2 { x: 10, y: 1 },
+            position: Vector2::ZERO,
+            waypoint: (10, 1).into(),
         },
         instructions,
         true,
     );
 
-    destination.position.x.abs() + destination.position.y.abs()
+    destination.position.manhattan_length()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"F10
-N3
-F7
-R90
-F11";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_INPUT,)), 25);
+        assert_eq!(part1(&parse(example(12, "example")).unwrap()), 25);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_INPUT,)), 286);
+        assert_eq!(part2(&parse(example(12, "example")).unwrap()), 286);
+    }
+
+    #[test]
+    fn animation() {
+        let mut voyage = part1_animation(&parse("F10\nN3\nL90\nF2").unwrap());
+
+        while voyage.step() {}
+
+        assert_eq!(
+            voyage.frame().to_plain(),
+            "day 12, instruction 4/4: ship at (10, 5), 15 from the start, 1:1
+          @
+
+          .
+
+
+o         ."
+        );
+
+        let mut voyage = part2_animation(&parse(example(12, "example")).unwrap());
+
+        while voyage.step() {}
+
+        assert_eq!(
+            voyage.frame().title,
+            "day 12, instruction 5/5: ship at (214, -72), 286 from the start, 1:5"
+        );
     }
 }
@@ -1,48 +1,61 @@
+//! Day 13: Shuttle Search.
+
+use crate::overflow::Overflow;
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 use modinverse::*;
+use serde::{Deserialize, Serialize};
 
-type Timestamp = i64;
-type BusId = i64;
+pub type Timestamp = i64;
+pub type BusId = i64;
 
-struct BusSchedule {
-    shift: Timestamp,
-    id: BusId,
+/// A bus in service, and how many minutes after the first bus it has to depart.
+#[derive(Serialize, Deserialize)]
+pub struct BusSchedule {
+    pub shift: Timestamp,
+    pub id: BusId,
 }
 
-struct Notes {
-    earliest_timestamp: Timestamp,
-    bus_schedules: Vec<BusSchedule>,
+/// The earliest departure timestamp and the buses in service.
+#[derive(Serialize, Deserialize)]
+pub struct Notes {
+    pub earliest_timestamp: Timestamp,
+    pub bus_schedules: Vec<BusSchedule>,
 }
 
-fn parse_bus_schedules(input: &str) -> Vec<BusSchedule> {
+fn parse_bus_schedules(source: &Source, input: &str) -> Result<Vec<BusSchedule>, ParseError> {
     input
         .split(',')
         .enumerate()
-        .filter_map(|(index, record)| {
-            if let Ok(id) = record.parse::<i64>() {
-                Some(BusSchedule {
-                    shift: index as i64,
-                    id,
-                })
-            } else {
-                None
-            }
+        .filter(|(_, record)| *record != "x")
+        .map(|(index, record)| {
+            Ok(BusSchedule {
+                shift: index as i64,
+                id: source.parse(record)?,
+            })
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
+/// Parses the notes: the earliest timestamp, then the comma-separated bus IDs.
 #[aoc_generator(day13)]
-fn parse_input(input: &str) -> Notes {
+pub fn parse(input: &str) -> Result<Notes, ParseError> {
+    let source = Source::new(13, input);
     let mut lines = input.lines();
 
-    Notes {
-        earliest_timestamp: lines.next().unwrap().parse().unwrap(),
-        bus_schedules: parse_bus_schedules(lines.next().unwrap()),
-    }
+    Ok(Notes {
+        earliest_timestamp: source.parse(source.next(&mut lines, input, "a timestamp")?)?,
+        bus_schedules: parse_bus_schedules(
+            &source,
+            source.next(&mut lines, input, "a bus schedule")?,
+        )?,
+    })
 }
 
+/// Multiplies the ID of the earliest bus to take by the minutes to wait for it, which fits in
+/// 128 bits as both are 64-bit.
 #[aoc(day13, part1)]
-fn part1(notes: &Notes) -> i64 {
+pub fn part1(notes: &Notes) -> i128 {
     let (id, time) = notes
         .bus_schedules
         .iter()
@@ -55,65 +68,102 @@ fn part1(notes: &Notes) -> i64 {
         .min_by(|x, y| x.1.cmp(&y.1))
         .unwrap();
 
-    id * time
+    id as i128 * time as i128
 }
 
-fn find_earliest_timestamp(bus_schedules: &[BusSchedule]) -> Timestamp {
+/// Finds the earliest timestamp at which every bus departs at its shift, via the Chinese
+/// remainder theorem, in 128 bits as the product of the IDs outgrows 64 bits with a few more
+/// buses. Every term of the sum is reduced below that product, so only the product itself can
+/// overflow.
+pub fn find_earliest_timestamp(bus_schedules: &[BusSchedule]) -> Result<i128, Overflow> {
     let product = bus_schedules
         .iter()
-        .map(|schedule| schedule.id)
-        .product::<i64>();
+        .try_fold(1i128, |product, schedule| {
+            product.checked_mul(schedule.id as i128)
+        })
+        .ok_or(Overflow("the product of the bus IDs"))?;
 
-    bus_schedules
+    Ok(bus_schedules
         .iter()
         .map(|schedule| {
-            let product_without_id = product / schedule.id;
-            let modular_inverse = modinverse(product_without_id, schedule.id).unwrap();
-            (schedule.id - schedule.shift % schedule.id) * product_without_id * modular_inverse
+            let id = schedule.id as i128;
+            let product_without_id = product / id;
+            let modular_inverse =
+                modinverse((product_without_id % id) as i64, schedule.id).unwrap() as i128;
+            let remainder = (id - schedule.shift as i128 % id) % id;
+            remainder * modular_inverse % id * product_without_id
         })
-        .sum::<i64>()
-        % product
+        .fold(0, |sum, term| {
+            if sum >= product - term {
+                sum - (product - term)
+            } else {
+                sum + term
+            }
+        }))
 }
 
+/// Returns the earliest timestamp at which the buses depart at their offsets.
 #[aoc(day13, part2)]
-fn part2(notes: &Notes) -> Timestamp {
+pub fn part2(notes: &Notes) -> Result<i128, Overflow> {
     find_earliest_timestamp(&notes.bus_schedules)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
-    static TEST_NOTES: &str = r"939
-7,13,x,x,59,x,31,19";
+    fn bus_schedules(input: &str) -> Vec<BusSchedule> {
+        parse_bus_schedules(&Source::new(13, input), input).unwrap()
+    }
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_NOTES)), 295);
+        assert_eq!(part1(&parse(example(13, "example")).unwrap()), 295);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_NOTES)), 1_068_781);
         assert_eq!(
-            find_earliest_timestamp(&parse_bus_schedules("17,x,13,19")),
-            3417
+            part2(&parse(example(13, "example")).unwrap()),
+            Ok(1_068_781)
         );
         assert_eq!(
-            find_earliest_timestamp(&parse_bus_schedules("67,7,59,61")),
-            754_018
+            find_earliest_timestamp(&bus_schedules("17,x,13,19")),
+            Ok(3417)
         );
         assert_eq!(
-            find_earliest_timestamp(&parse_bus_schedules("67,x,7,59,61")),
-            779_210
+            find_earliest_timestamp(&bus_schedules("67,7,59,61")),
+            Ok(754_018)
         );
         assert_eq!(
-            find_earliest_timestamp(&parse_bus_schedules("67,7,x,59,61")),
-            1_261_476
+            find_earliest_timestamp(&bus_schedules("67,x,7,59,61")),
+            Ok(779_210)
         );
         assert_eq!(
-            find_earliest_timestamp(&parse_bus_schedules("1789,37,47,1889")),
-            1_202_161_486
+            find_earliest_timestamp(&bus_schedules("67,7,x,59,61")),
+            Ok(1_261_476)
+        );
+        assert_eq!(
+            find_earliest_timestamp(&bus_schedules("1789,37,47,1889")),
+            Ok(1_202_161_486)
+        );
+    }
+
+    #[test]
+    fn large_ids() {
+        let schedules = bus_schedules("1000000007,x,998244353,1000000009");
+        let timestamp = find_earliest_timestamp(&schedules).unwrap();
+
+        assert!(timestamp > i64::MAX as i128);
+        assert!(schedules
+            .iter()
+            .all(|schedule| (timestamp + schedule.shift as i128) % schedule.id as i128 == 0));
+        assert_eq!(
+            find_earliest_timestamp(&bus_schedules(
+                "4611686018427387847,4611686018427387817,4611686018427387787"
+            )),
+            Err(Overflow("the product of the bus IDs"))
         );
     }
 }
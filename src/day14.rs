@@ -1,58 +1,117 @@
+//! Day 14: Docking Data.
+
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 const WORD_SIZE: usize = 36;
 
-#[derive(Copy, Clone)]
-struct BitMask {
-    and_mask: u64,
-    or_mask: u64,
+/// The puzzle parameters, 36 bit words by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    /// The number of bits of the values, addresses and masks, up to 63.
+    pub word_size: usize,
 }
 
-impl Default for BitMask {
+impl Default for Params {
     fn default() -> Self {
+        Params {
+            word_size: WORD_SIZE,
+        }
+    }
+}
+
+/// A mask, stored as the bits to keep (`X` and `1`) and the bits to set (`1`).
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct BitMask {
+    pub and_mask: u64,
+    pub or_mask: u64,
+}
+
+impl BitMask {
+    /// The mask keeping every bit of a word.
+    pub fn unmasked(word_size: usize) -> Self {
         Self {
-            and_mask: 2_u64.pow(WORD_SIZE as u32) - 1,
+            and_mask: (1 << word_size) - 1,
             or_mask: 0,
         }
     }
 }
 
-enum Instruction {
+impl Default for BitMask {
+    fn default() -> Self {
+        Self::unmasked(WORD_SIZE)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Instruction {
     Mask(BitMask),
     Mem(u64, u64),
 }
 
-fn parse_mask(mask_str: &str) -> BitMask {
-    BitMask {
-        and_mask: u64::from_str_radix(&mask_str.replace("X", "1"), 2).unwrap(),
-        or_mask: u64::from_str_radix(&mask_str.replace("X", "0"), 2).unwrap(),
+fn parse_mask(source: &Source, mask_str: &str, word_size: usize) -> Result<BitMask, ParseError> {
+    if mask_str.len() != word_size {
+        return Err(source.error(mask_str, format!("expected a {} bit mask", word_size)));
+    }
+
+    if let Some(i) = mask_str.find(|c| !matches!(c, 'X' | '0' | '1')) {
+        return Err(source.error(&mask_str[i..=i], "expected 'X', '0' or '1'"));
     }
+
+    Ok(BitMask {
+        and_mask: u64::from_str_radix(&mask_str.replace('X', "1"), 2).unwrap(),
+        or_mask: u64::from_str_radix(&mask_str.replace('X', "0"), 2).unwrap(),
+    })
 }
 
+fn parse_instruction(
+    source: &Source,
+    line: &str,
+    word_size: usize,
+) -> Result<Instruction, ParseError> {
+    let mut split = line.split(" = ");
+    let first = source.next(&mut split, line, "an instruction")?;
+    let second = source.next(&mut split, line, "\" = \"")?;
+
+    if first == "mask" {
+        return Ok(Instruction::Mask(parse_mask(source, second, word_size)?));
+    }
+
+    match first.strip_prefix("mem[").and_then(|s| s.strip_suffix(']')) {
+        Some(address_str) => Ok(Instruction::Mem(
+            source.parse(address_str)?,
+            source.parse(second)?,
+        )),
+        None => Err(source.error(first, "expected \"mask\" or \"mem[<address>]\"")),
+    }
+}
+
+/// Parses the initialization program, one `mask = ...` or `mem[...] = ...` per line.
 #[aoc_generator(day14)]
-fn parse_input(input: &str) -> Vec<Instruction> {
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_with(input, &Params::default())
+}
+
+/// Same as [`parse`], with masks of the word size given by `params`.
+pub fn parse_with(input: &str, params: &Params) -> Result<Vec<Instruction>, ParseError> {
+    let source = Source::new(14, input);
     input
         .lines()
-        .map(|line| {
-            let mut split = line.split(" = ");
-            let first = split.next().unwrap();
-
-            match first {
-                "mask" => Instruction::Mask(parse_mask(split.next().unwrap())),
-                _ => {
-                    let address = first[4..first.len() - 1].parse::<u64>().unwrap();
-                    let value = split.next().unwrap().parse::<u64>().unwrap();
-                    Instruction::Mem(address, value)
-                }
-            }
-        })
+        .map(|line| parse_instruction(&source, line, params.word_size))
         .collect()
 }
 
+/// Sums the memory after applying the masks to the written values.
 #[aoc(day14, part1)]
-fn part1(instructions: &[Instruction]) -> u64 {
-    let mut current_mask = BitMask::default();
+pub fn part1(instructions: &[Instruction]) -> u64 {
+    part1_with(instructions, &Params::default())
+}
+
+/// Same as [`part1`], for a program parsed with the same `params`.
+pub fn part1_with(instructions: &[Instruction], params: &Params) -> u64 {
+    let mut current_mask = BitMask::unmasked(params.word_size);
     let mut memory = HashMap::new();
 
     for instruction in instructions {
@@ -70,9 +129,15 @@ fn part1(instructions: &[Instruction]) -> u64 {
     memory.values().sum()
 }
 
+/// Sums the memory after applying the masks, with floating bits, to the addresses.
 #[aoc(day14, part2)]
-fn part2(instructions: &[Instruction]) -> u64 {
-    let mut current_mask = BitMask::default();
+pub fn part2(instructions: &[Instruction]) -> u64 {
+    part2_with(instructions, &Params::default())
+}
+
+/// Same as [`part2`], for a program parsed with the same `params`.
+pub fn part2_with(instructions: &[Instruction], params: &Params) -> u64 {
+    let mut current_mask = BitMask::unmasked(params.word_size);
     let mut x_mask = 0;
     let mut memory = HashMap::new();
 
@@ -88,7 +153,7 @@ fn part2(instructions: &[Instruction]) -> u64 {
 
                 let mut addresses: Vec<u64> = Vec::new();
 
-                for i in 0..WORD_SIZE {
+                for i in 0..params.word_size {
                     let a = address_base % 2;
                     let x = x_mask % 2;
 
@@ -98,8 +163,7 @@ fn part2(instructions: &[Instruction]) -> u64 {
                         } else {
                             addresses
                                 .iter()
-                                .map(|address| vec![address + 2_u64.pow(i as u32), *address])
-                                .flatten()
+                                .flat_map(|address| vec![address + 2_u64.pow(i as u32), *address])
                                 .collect()
                         }
                     } else if addresses.is_empty() {
@@ -128,24 +192,32 @@ fn part2(instructions: &[Instruction]) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_PROGRAM_1: &str = r"mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
-mem[8] = 11
-mem[7] = 101
-mem[8] = 0";
-
-    static TEST_PROGRAM_2: &str = r"mask = 000000000000000000000000000000X1001X
-mem[42] = 100
-mask = 00000000000000000000000000000000X0XX
-mem[26] = 1";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_PROGRAM_1)), 165);
+        assert_eq!(part1(&parse(example(14, "example")).unwrap()), 165);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_PROGRAM_2)), 208);
+        assert_eq!(part2(&parse(example(14, "part2")).unwrap()), 208);
+    }
+
+    #[test]
+    fn other_word_size() {
+        let params = Params { word_size: 6 };
+        let program = "mask = X1001X\nmem[42] = 100\nmask = X0XX\nmem[26] = 1";
+
+        assert!(parse_with(program, &params).is_err());
+
+        let program = "mask = X1001X\nmem[42] = 100\nmask = 00X0XX\nmem[26] = 1";
+        let instructions = parse_with(program, &params).unwrap();
+
+        assert_eq!(part2_with(&instructions, &params), 208);
+        assert_eq!(
+            part1_with(&parse_with("mem[3] = 100", &params).unwrap(), &params),
+            100 & 0b11_1111
+        );
     }
 }
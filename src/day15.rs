@@ -1,13 +1,55 @@
+//! Day 15: Rambunctious Recitation.
+
+use crate::parsing::{ParseError, Source};
+use crate::progress::{Interrupted, Monitor};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::num::ParseIntError;
 
+/// The puzzle parameters, the 2020th and 30000000th turns by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    pub part1_turns: usize,
+    pub part2_turns: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_turns: 2_020,
+            part2_turns: 30_000_000,
+        }
+    }
+}
+
+/// Parses the comma-separated starting numbers.
 #[aoc_generator(day15)]
-fn parse_input(input: &str) -> Result<Vec<usize>, ParseIntError> {
-    input.split(',').map(|l| l.parse()).collect()
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    let source = Source::new(15, input);
+    input
+        .trim_end()
+        .split(',')
+        .map(|l| source.parse(l))
+        .collect()
+}
+
+/// Returns the `n`th number spoken in the memory game.
+pub fn van_ecks_nth(n: usize, starting_numbers: &[usize]) -> usize {
+    van_ecks_nth_monitored(n, starting_numbers, &mut Monitor::new())
+        .expect("an unmonitored game is never interrupted")
 }
 
-fn van_ecks_nth(n: usize, starting_numbers: &[usize]) -> usize {
-    let mut last_spoken_turns = vec![0; n];
+/// Same as [`van_ecks_nth`], one step per turn.
+pub fn van_ecks_nth_monitored(
+    n: usize,
+    starting_numbers: &[usize],
+    monitor: &mut Monitor,
+) -> Result<usize, Interrupted> {
+    if n <= starting_numbers.len() {
+        return Ok(starting_numbers[n - 1]);
+    }
+
+    // No number spoken after the starting ones can be greater than the number of turns.
+    let max_starting_number = starting_numbers.iter().max().unwrap();
+    let mut last_spoken_turns = vec![0; n.max(max_starting_number + 1)];
 
     starting_numbers
         .iter()
@@ -17,6 +59,11 @@ fn van_ecks_nth(n: usize, starting_numbers: &[usize]) -> usize {
     let mut last_number_spoken = starting_numbers[starting_numbers.len() - 1];
 
     for turn in starting_numbers.len()..n {
+        monitor.tick(
+            (turn - starting_numbers.len()) as u64,
+            (n - starting_numbers.len()) as u64,
+        )?;
+
         let last_spoken_on = last_spoken_turns[last_number_spoken];
         last_spoken_turns[last_number_spoken] = turn;
 
@@ -27,17 +74,48 @@ fn van_ecks_nth(n: usize, starting_numbers: &[usize]) -> usize {
         }
     }
 
-    last_number_spoken
+    monitor.finish();
+    Ok(last_number_spoken)
 }
 
+/// Returns the 2020th number spoken.
 #[aoc(day15, part1)]
-fn part1(input: &[usize]) -> usize {
-    van_ecks_nth(2_020, input)
+pub fn part1(input: &[usize]) -> usize {
+    part1_with(input, &Params::default())
+}
+
+/// Same as [`part1`], for the turn given by `params`.
+pub fn part1_with(input: &[usize], params: &Params) -> usize {
+    van_ecks_nth(params.part1_turns, input)
+}
+
+/// Same as [`part1_with`], watched over by `monitor`.
+pub fn part1_monitored(
+    input: &[usize],
+    params: &Params,
+    monitor: &mut Monitor,
+) -> Result<usize, Interrupted> {
+    van_ecks_nth_monitored(params.part1_turns, input, monitor)
 }
 
+/// Returns the 30000000th number spoken.
 #[aoc(day15, part2)]
-fn part2(input: &[usize]) -> usize {
-    van_ecks_nth(30_000_000, input)
+pub fn part2(input: &[usize]) -> usize {
+    part2_with(input, &Params::default())
+}
+
+/// Same as [`part2`], for the turn given by `params`.
+pub fn part2_with(input: &[usize], params: &Params) -> usize {
+    van_ecks_nth(params.part2_turns, input)
+}
+
+/// Same as [`part2_with`], watched over by `monitor`.
+pub fn part2_monitored(
+    input: &[usize],
+    params: &Params,
+    monitor: &mut Monitor,
+) -> Result<usize, Interrupted> {
+    van_ecks_nth_monitored(params.part2_turns, input, monitor)
 }
 
 #[cfg(test)]
@@ -46,23 +124,56 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&(parse_input("0,3,6")).unwrap()), 436);
-        assert_eq!(part1(&(parse_input("1,3,2")).unwrap()), 1);
-        assert_eq!(part1(&(parse_input("2,1,3")).unwrap()), 10);
-        assert_eq!(part1(&(parse_input("1,2,3")).unwrap()), 27);
-        assert_eq!(part1(&(parse_input("2,3,1")).unwrap()), 78);
-        assert_eq!(part1(&(parse_input("3,2,1")).unwrap()), 438);
-        assert_eq!(part1(&(parse_input("3,1,2")).unwrap()), 1_836);
+        assert_eq!(part1(&(parse("0,3,6")).unwrap()), 436);
+        assert_eq!(part1(&(parse("1,3,2")).unwrap()), 1);
+        assert_eq!(part1(&(parse("2,1,3")).unwrap()), 10);
+        assert_eq!(part1(&(parse("1,2,3")).unwrap()), 27);
+        assert_eq!(part1(&(parse("2,3,1")).unwrap()), 78);
+        assert_eq!(part1(&(parse("3,2,1")).unwrap()), 438);
+        assert_eq!(part1(&(parse("3,1,2")).unwrap()), 1_836);
+    }
+
+    #[test]
+    fn other_turns() {
+        let starting_numbers = parse("0,3,6").unwrap();
+        let spoken = [0, 3, 6, 0, 3, 3, 1, 0, 4, 0];
+
+        for (turn, &number) in spoken.iter().enumerate() {
+            let params = Params {
+                part1_turns: turn + 1,
+                ..Params::default()
+            };
+
+            assert_eq!(part1_with(&starting_numbers, &params), number);
+        }
+
+        assert_eq!(van_ecks_nth(2, &[20, 0, 7]), 0);
+        assert_eq!(van_ecks_nth(5, &[20, 0, 7]), 2);
+    }
+
+    #[test]
+    fn monitored() {
+        let mut fractions = Vec::new();
+        let mut monitor = Monitor::new().with_progress(|fraction| fractions.push(fraction));
+
+        assert_eq!(
+            part2_monitored(&[0, 3, 6], &Params::default(), &mut monitor),
+            Ok(175_594)
+        );
+        drop(monitor);
+
+        assert_eq!(fractions.len(), 459);
+        assert!(fractions.windows(2).all(|pair| pair[0] < pair[1]));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&(parse_input("0,3,6")).unwrap()), 175_594);
-        assert_eq!(part2(&(parse_input("1,3,2")).unwrap()), 2_578);
-        assert_eq!(part2(&(parse_input("2,1,3")).unwrap()), 3_544_142);
-        assert_eq!(part2(&(parse_input("1,2,3")).unwrap()), 261_214);
-        assert_eq!(part2(&(parse_input("2,3,1")).unwrap()), 6_895_259);
-        assert_eq!(part2(&(parse_input("3,2,1")).unwrap()), 18);
-        assert_eq!(part2(&(parse_input("3,1,2")).unwrap()), 362);
+        assert_eq!(part2(&(parse("0,3,6")).unwrap()), 175_594);
+        assert_eq!(part2(&(parse("1,3,2")).unwrap()), 2_578);
+        assert_eq!(part2(&(parse("2,1,3")).unwrap()), 3_544_142);
+        assert_eq!(part2(&(parse("1,2,3")).unwrap()), 261_214);
+        assert_eq!(part2(&(parse("2,3,1")).unwrap()), 6_895_259);
+        assert_eq!(part2(&(parse("3,2,1")).unwrap()), 18);
+        assert_eq!(part2(&(parse("3,1,2")).unwrap()), 362);
     }
 }
@@ -1,78 +1,95 @@
+//! Day 16: Ticket Translation.
+
+use crate::parsing::{ParseError, Source};
+use crate::trace::{Event, NoTrace, Tracer};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::ops::RangeInclusive;
 
-type Rule = (RangeInclusive<usize>, RangeInclusive<usize>);
-type FieldName = String;
+/// The two ranges a field value must fall into one of.
+pub type Rule = (RangeInclusive<usize>, RangeInclusive<usize>);
+pub type FieldName = String;
 type RuleEntry = (FieldName, Rule);
-type Rules = HashMap<FieldName, Rule>;
-type RawTicket = Vec<usize>;
-
-struct Notes {
-    rules: Rules,
-    tickets: Vec<RawTicket>,
+pub type Rules = HashMap<FieldName, Rule>;
+pub type RawTicket = Vec<usize>;
+
+/// The ticket rules, followed by your ticket and then all the nearby ones.
+#[derive(Serialize, Deserialize)]
+pub struct Notes {
+    pub rules: Rules,
+    pub tickets: Vec<RawTicket>,
 }
 
-fn parse_rule(rule_str: &str) -> RuleEntry {
-    let mut key_value_split = rule_str.split(": ");
-    let field_name = key_value_split.next().unwrap().to_string();
-    let mut range_split = key_value_split.next().unwrap().split(" or ");
-
-    let range_str = range_split.next().unwrap();
+fn parse_range(source: &Source, range_str: &str) -> Result<RangeInclusive<usize>, ParseError> {
     let mut boundaries_split = range_str.split('-');
-    let lower_bounds = RangeInclusive::new(
-        boundaries_split.next().unwrap().parse::<usize>().unwrap(),
-        boundaries_split.next().unwrap().parse::<usize>().unwrap(),
-    );
 
-    let range_str = range_split.next().unwrap();
-    let mut boundaries_split = range_str.split('-');
-    let upper_bounds = RangeInclusive::new(
-        boundaries_split.next().unwrap().parse::<usize>().unwrap(),
-        boundaries_split.next().unwrap().parse::<usize>().unwrap(),
-    );
+    Ok(RangeInclusive::new(
+        source.parse(source.next(&mut boundaries_split, range_str, "a lower bound")?)?,
+        source.parse(source.next(&mut boundaries_split, range_str, "an upper bound")?)?,
+    ))
+}
 
-    (field_name, (lower_bounds, upper_bounds))
+fn parse_rule(source: &Source, rule_str: &str) -> Result<RuleEntry, ParseError> {
+    let mut key_value_split = rule_str.split(": ");
+    let field_name = source
+        .next(&mut key_value_split, rule_str, "a field name")?
+        .to_string();
+    let ranges_str = source.next(&mut key_value_split, rule_str, "\": \"")?;
+    let mut range_split = ranges_str.split(" or ");
+
+    let lower_bounds = parse_range(
+        source,
+        source.next(&mut range_split, ranges_str, "a range")?,
+    )?;
+    let upper_bounds = parse_range(
+        source,
+        source.next(&mut range_split, ranges_str, "\" or \"")?,
+    )?;
+
+    Ok((field_name, (lower_bounds, upper_bounds)))
 }
 
-fn parse_rules(rules_str: &str) -> Rules {
+fn parse_rules(source: &Source, rules_str: &str) -> Result<Rules, ParseError> {
     rules_str
         .lines()
-        .map(|rule_str| parse_rule(rule_str))
+        .map(|rule_str| parse_rule(source, rule_str))
         .collect()
 }
 
-fn parse_raw_ticket(raw_ticket_str: &str) -> RawTicket {
-    raw_ticket_str
-        .split(',')
-        .map(|c| c.parse::<usize>().unwrap())
-        .collect()
+fn parse_raw_ticket(source: &Source, raw_ticket_str: &str) -> Result<RawTicket, ParseError> {
+    raw_ticket_str.split(',').map(|c| source.parse(c)).collect()
 }
 
+/// Parses the notes: the field rules, your ticket and the nearby tickets.
 #[aoc_generator(day16)]
-fn parse_input(input: &str) -> Notes {
+pub fn parse(input: &str) -> Result<Notes, ParseError> {
+    let source = Source::new(16, input);
     let mut notes_split = input.split("\n\n");
 
-    let rules = parse_rules(notes_split.next().unwrap());
+    let rules = parse_rules(&source, source.next(&mut notes_split, input, "rules")?)?;
 
+    let your_ticket_str = source.next(&mut notes_split, input, "your ticket")?;
+    let mut your_ticket_lines = your_ticket_str.lines().skip(1);
     let mut tickets = vec![parse_raw_ticket(
-        notes_split.next().unwrap().lines().nth(1).unwrap(),
-    )];
-
-    tickets.extend(
-        notes_split
-            .next()
-            .unwrap()
-            .lines()
-            .skip(1)
-            .map(|raw_ticket_str| parse_raw_ticket(raw_ticket_str)),
-    );
-
-    Notes { rules, tickets }
+        &source,
+        source.next(&mut your_ticket_lines, your_ticket_str, "a ticket")?,
+    )?];
+
+    for raw_ticket_str in source
+        .next(&mut notes_split, input, "nearby tickets")?
+        .lines()
+        .skip(1)
+    {
+        tickets.push(parse_raw_ticket(&source, raw_ticket_str)?);
+    }
+
+    Ok(Notes { rules, tickets })
 }
 
+/// Sums the nearby ticket values that are not valid for any field.
 #[aoc(day16, part1)]
-fn part1(notes: &Notes) -> usize {
+pub fn part1(notes: &Notes) -> usize {
     let mut united_rule = vec![false; 1000];
 
     for (i, is_valid) in united_rule.iter_mut().enumerate() {
@@ -142,10 +159,29 @@ fn get_possible_positions_by_field(notes: &Notes) -> HashMap<&FieldName, HashSet
         .collect::<HashMap<_, _>>()
 }
 
-fn get_actual_positions_by_field(notes: &Notes) -> HashMap<&FieldName, usize> {
+fn get_actual_positions_by_field<'a>(
+    notes: &'a Notes,
+    tracer: &mut impl Tracer,
+) -> HashMap<&'a FieldName, usize> {
     let mut all_possible_positions = get_possible_positions_by_field(notes);
     let mut actual_positions = HashMap::with_capacity(notes.rules.len());
 
+    if tracer.enabled() {
+        let mut fields = all_possible_positions.iter().collect::<Vec<_>>();
+        fields.sort_unstable_by_key(|&(field_name, _)| *field_name);
+
+        for (field_name, possible_field_positions) in fields {
+            let mut positions = possible_field_positions.iter().copied().collect::<Vec<_>>();
+            positions.sort_unstable();
+
+            tracer.record(
+                Event::new(16, "candidates")
+                    .with("field", field_name.as_str())
+                    .with("positions", positions),
+            );
+        }
+    }
+
     while !all_possible_positions.is_empty() {
         let mut position = 0;
 
@@ -154,6 +190,11 @@ fn get_actual_positions_by_field(notes: &Notes) -> HashMap<&FieldName, usize> {
                 position = possible_field_positions.drain().next().unwrap();
                 actual_positions.insert(field_name, position);
                 all_possible_positions.remove(field_name);
+                tracer.trace(|| {
+                    Event::new(16, "assign")
+                        .with("field", field_name.as_str())
+                        .with("position", position)
+                });
                 break;
             }
         }
@@ -166,9 +207,16 @@ fn get_actual_positions_by_field(notes: &Notes) -> HashMap<&FieldName, usize> {
     actual_positions
 }
 
+/// Multiplies the values of the six "departure" fields on your ticket.
 #[aoc(day16, part2)]
-fn part2(notes: &Notes) -> usize {
-    get_actual_positions_by_field(notes)
+pub fn part2(notes: &Notes) -> usize {
+    part2_traced(notes, &mut NoTrace)
+}
+
+/// Same as [`part2`], tracing the candidate positions of every field and the order in which
+/// they are assigned.
+pub fn part2_traced(notes: &Notes, tracer: &mut impl Tracer) -> usize {
+    get_actual_positions_by_field(notes, tracer)
         .into_iter()
         .filter(|(field_name, _)| field_name.starts_with("departure"))
         .map(|(_, position)| notes.tickets[0][position])
@@ -178,47 +226,19 @@ fn part2(notes: &Notes) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(
-            part1(
-                &(parse_input(
-                    r"class: 1-3 or 5-7
-row: 6-11 or 33-44
-seat: 13-40 or 45-50
-
-your ticket:
-7,1,14
-
-nearby tickets:
-7,3,47
-40,4,50
-55,2,20
-38,6,12"
-                ))
-            ),
-            71
-        );
+        assert_eq!(part1(&parse(example(16, "example")).unwrap()), 71);
     }
 
     #[test]
     fn part2_example() {
-        let notes = parse_input(
-            r"class: 0-1 or 4-19
-row: 0-5 or 8-19
-seat: 0-13 or 16-19
-
-your ticket:
-11,12,13
-
-nearby tickets:
-3,9,18
-15,1,5
-5,14,9",
-        );
+        let notes = parse(example(16, "part2")).unwrap();
 
-        let positions_by_field = get_actual_positions_by_field(&(notes));
+        let mut events = Vec::new();
+        let positions_by_field = get_actual_positions_by_field(&(notes), &mut events);
 
         assert_eq!(
             notes.tickets[0][*positions_by_field.get(&"class".to_string()).unwrap()],
@@ -232,5 +252,13 @@ nearby tickets:
             notes.tickets[0][*positions_by_field.get(&"seat".to_string()).unwrap()],
             13
         );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.name == "assign")
+                .map(|event| event.get("field").unwrap().clone())
+                .collect::<Vec<_>>(),
+            vec!["seat".into(), "class".into(), "row".into()]
+        );
     }
 }
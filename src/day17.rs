@@ -1,366 +1,238 @@
+//! Day 17: Conway Cubes.
+
+use crate::animation::{Animation, Colour, Frame};
+use crate::automaton::{Automaton, Cubic, LifeLike};
+use crate::geometry::{Vector, Vector2};
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::hash::Hash;
-use std::ops::Not;
 
-#[derive(Eq, PartialEq, Copy, Clone)]
-enum CubeState {
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum CubeState {
     Active,
     Inactive,
 }
 
-impl Not for CubeState {
-    type Output = Self;
-
-    fn not(self) -> Self::Output {
-        use CubeState::*;
+fn parse_cube_state(source: &Source, c_str: &str) -> Result<CubeState, ParseError> {
+    use CubeState::*;
 
-        match self {
-            Active => Inactive,
-            Inactive => Active,
-        }
+    match c_str {
+        "." => Ok(Inactive),
+        "#" => Ok(Active),
+        _ => Err(source.error(c_str, "expected '.' or '#'")),
     }
 }
 
-impl From<char> for CubeState {
-    fn from(c: char) -> Self {
-        use CubeState::*;
+/// A cube of the initial 2D slice, with its position in it.
+pub type Cube = (Vector2, CubeState);
 
-        match c {
-            '.' => Inactive,
-            '#' => Active,
-            _ => unreachable!(),
+/// Parses the initial slice of inactive (`.`) and active (`#`) cubes.
+#[aoc_generator(day17)]
+pub fn parse(input: &str) -> Result<Vec<Cube>, ParseError> {
+    let source = Source::new(17, input);
+    let mut cubes = Vec::new();
+
+    for (row, line) in input.lines().enumerate() {
+        for (column, c) in line.char_indices() {
+            cubes.push((
+                (column as i32, row as i32).into(),
+                parse_cube_state(&source, &line[column..column + c.len_utf8()])?,
+            ));
         }
     }
-}
-
-trait Position: Sized + Eq + PartialEq + Hash + Clone {
-    type Direction;
 
-    fn adjacent_position(&self, direction: &Self::Direction) -> Self;
-    fn adjacent_positions(&self) -> Vec<Self>;
+    Ok(cubes)
 }
 
-trait Grid {
-    type CubePosition: Position;
-
-    fn check_cube_state(&self, position: &Self::CubePosition) -> CubeState;
-
-    fn count_adjacent_active(&self, position: &Self::CubePosition) -> usize {
-        position
-            .adjacent_positions()
-            .iter()
-            .filter(|adjacent_position| {
-                self.check_cube_state(adjacent_position) == CubeState::Active
-            })
-            .count()
-    }
+/// The puzzle parameters, six cycles by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    pub cycles: usize,
 }
 
-type HashMapGrid<T> = HashMap<T, CubeState>;
-
-impl<T> Grid for HashMapGrid<T>
-where
-    T: Position,
-{
-    type CubePosition = T;
-
-    fn check_cube_state(&self, position: &Self::CubePosition) -> CubeState {
-        if let Some(&state) = (*self).get(position) {
-            state
-        } else {
-            CubeState::Inactive
-        }
+impl Default for Params {
+    fn default() -> Self {
+        Params { cycles: 6 }
     }
 }
 
-trait Infinite {
-    fn expand(&mut self);
-}
-
-#[derive(Eq, PartialEq, Hash, Clone, Debug)]
-struct Vector3 {
-    x: i32,
-    y: i32,
-    z: i32,
-}
+const CONWAY_CUBES: LifeLike<CubeState> = LifeLike {
+    live: CubeState::Active,
+    dead: CubeState::Inactive,
+    birth: &[3],
+    survival: &[2, 3],
+};
 
-impl From<&Vector3> for (i32, i32, i32) {
-    fn from(vector3: &Vector3) -> Self {
-        (vector3.x, vector3.y, vector3.z)
-    }
+fn count_active_after<const N: usize>(
+    cubes: HashMap<Vector<N>, CubeState>,
+    cycles: usize,
+) -> usize {
+    let mut automaton = Automaton::unbounded(cubes, Cubic, CONWAY_CUBES, CubeState::Inactive);
+    automaton.run(cycles);
+    automaton.count(CubeState::Active)
 }
 
-impl From<(i32, i32, i32)> for Vector3 {
-    fn from((x, y, z): (i32, i32, i32)) -> Self {
-        Vector3 { x, y, z }
-    }
+/// The pocket dimension cycle after cycle, shown as the 2D slices holding active cubes.
+pub struct Pocket<const N: usize> {
+    automaton: Automaton<Cubic<N>, CubeState, LifeLike<CubeState>>,
+    cycles: usize,
 }
 
-type Position3 = Vector3;
-
-impl Position for Position3 {
-    type Direction = Vector3;
-
-    fn adjacent_position(&self, direction: &Self::Direction) -> Self {
-        let (x_delta, y_delta, z_delta) = direction.into();
+impl<const N: usize> Pocket<N> {
+    fn new(cubes: &[Cube], cycles: usize) -> Self {
+        let cubes = cubes
+            .iter()
+            .map(|&(position, state)| {
+                let mut coordinates = [0; N];
+                coordinates[..2].copy_from_slice(&position.0);
 
-        (self.x + x_delta, self.y + y_delta, self.z + z_delta).into()
-    }
+                (Vector(coordinates), state)
+            })
+            .collect();
 
-    fn adjacent_positions(&self) -> Vec<Position3> {
-        let mut result = Vec::with_capacity(26);
-
-        for x in -1..=1 {
-            for y in -1..=1 {
-                for z in -1..=1 {
-                    if (x, y, z) != (0, 0, 0) {
-                        result.push(self.adjacent_position(&(x, y, z).into()));
-                    }
-                }
-            }
+        Pocket {
+            automaton: Automaton::unbounded(cubes, Cubic, CONWAY_CUBES, CubeState::Inactive),
+            cycles,
         }
-
-        result
     }
 }
 
-type Grid3 = HashMapGrid<Position3>;
-
-impl Infinite for Grid3 {
-    fn expand(&mut self) {
-        let min_x = self.keys().map(|position| position.x).min().unwrap() - 1;
-        let max_x = self.keys().map(|position| position.x).max().unwrap() + 1;
-        let min_y = self.keys().map(|position| position.y).min().unwrap() - 1;
-        let max_y = self.keys().map(|position| position.y).max().unwrap() + 1;
-        let min_z = self.keys().map(|position| position.z).min().unwrap() - 1;
-        let max_z = self.keys().map(|position| position.z).max().unwrap() + 1;
-
-        for x in min_x..=max_x {
-            for y in min_y..=max_y {
-                for z in min_z..=max_z {
-                    if x == min_x
-                        || x == max_x
-                        || y == min_y
-                        || y == max_y
-                        || z == min_z
-                        || z == max_z
-                    {
-                        self.entry((x, y, z).into()).or_insert(CubeState::Inactive);
-                    }
-                }
-            }
-        }
-    }
-}
+impl<const N: usize> Animation for Pocket<N> {
+    fn frame(&self) -> Frame {
+        const AXES: [&str; 2] = ["z", "w"];
 
-#[aoc_generator(day17, part1)]
-fn parse_input1(input: &str) -> Grid3 {
-    let mut grid = HashMap::new();
+        let mut active = self
+            .automaton
+            .cells()
+            .filter(|&(_, state)| state == CubeState::Active)
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        active
+            .sort_unstable_by_key(|position| (position.0[2..].to_vec(), position[1], position[0]));
 
-    for (row, line) in input.lines().enumerate() {
-        for (column, c) in line.chars().enumerate() {
-            grid.insert(
-                Position3 {
-                    x: column as i32,
-                    y: row as i32,
-                    z: 0,
-                },
-                c.into(),
+        let (x_min, y_min) = active
+            .iter()
+            .fold((i32::MAX, i32::MAX), |(x, y), position| {
+                (x.min(position[0]), y.min(position[1]))
+            });
+        let mut frame = Frame::new(format!(
+            "day 17, cycle {}: {} active cubes",
+            self.automaton.generation(),
+            active.len()
+        ));
+
+        for slice in active.chunk_by(|a, b| a.0[2..] == b.0[2..]) {
+            let label = (2..N)
+                .map(|axis| format!("{}={}", AXES[axis - 2], slice[0][axis]))
+                .collect::<Vec<_>>()
+                .join(", ");
+            // Anchors every slice to the same corner, so that they line up.
+            let corner = ((x_min, y_min), (' ', Colour::Plain));
+
+            frame = frame.text("").text(&label).grid(
+                Some(corner).into_iter().chain(
+                    slice
+                        .iter()
+                        .map(|position| ((position[0], position[1]), ('#', Colour::Cyan))),
+                ),
             );
         }
-    }
-
-    grid
-}
-
-#[derive(Eq, PartialEq, Hash, Clone, Debug)]
-struct Vector4 {
-    x: i32,
-    y: i32,
-    z: i32,
-    w: i32,
-}
-
-impl From<&Vector4> for (i32, i32, i32, i32) {
-    fn from(vector4: &Vector4) -> Self {
-        (vector4.x, vector4.y, vector4.z, vector4.w)
-    }
-}
-
-impl From<(i32, i32, i32, i32)> for Vector4 {
-    fn from((x, y, z, w): (i32, i32, i32, i32)) -> Self {
-        Vector4 { x, y, z, w }
-    }
-}
-
-type Position4 = Vector4;
-
-impl Position for Position4 {
-    type Direction = Vector4;
-
-    fn adjacent_position(&self, direction: &Self::Direction) -> Self {
-        let (x_delta, y_delta, z_delta, w_delta) = direction.into();
-
-        (
-            self.x + x_delta,
-            self.y + y_delta,
-            self.z + z_delta,
-            self.w + w_delta,
-        )
-            .into()
-    }
-
-    fn adjacent_positions(&self) -> Vec<Position4> {
-        let mut result = Vec::with_capacity(80);
-
-        for x in -1..=1 {
-            for y in -1..=1 {
-                for z in -1..=1 {
-                    for w in -1..=1 {
-                        if (x, y, z, w) != (0, 0, 0, 0) {
-                            result.push(self.adjacent_position(&(x, y, z, w).into()));
-                        }
-                    }
-                }
-            }
-        }
 
-        result
+        frame
     }
-}
 
-type Grid4 = HashMapGrid<Position4>;
-
-impl Infinite for Grid4 {
-    fn expand(&mut self) {
-        let min_x = self.keys().map(|position| position.x).min().unwrap() - 1;
-        let max_x = self.keys().map(|position| position.x).max().unwrap() + 1;
-        let min_y = self.keys().map(|position| position.y).min().unwrap() - 1;
-        let max_y = self.keys().map(|position| position.y).max().unwrap() + 1;
-        let min_z = self.keys().map(|position| position.z).min().unwrap() - 1;
-        let max_z = self.keys().map(|position| position.z).max().unwrap() + 1;
-        let min_w = self.keys().map(|position| position.w).min().unwrap() - 1;
-        let max_w = self.keys().map(|position| position.w).max().unwrap() + 1;
-
-        for x in min_x..=max_x {
-            for y in min_y..=max_y {
-                for z in min_z..=max_z {
-                    for w in min_w..=max_w {
-                        if x == min_x
-                            || x == max_x
-                            || y == min_y
-                            || y == max_y
-                            || z == min_z
-                            || z == max_z
-                            || w == min_w
-                            || w == max_w
-                        {
-                            self.entry((x, y, z, w).into())
-                                .or_insert(CubeState::Inactive);
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
-#[aoc_generator(day17, part2)]
-fn parse_input2(input: &str) -> Grid4 {
-    let mut grid = HashMap::new();
-
-    for (row, line) in input.lines().enumerate() {
-        for (column, c) in line.chars().enumerate() {
-            grid.insert(
-                Position4 {
-                    x: column as i32,
-                    y: row as i32,
-                    z: 0,
-                    w: 0,
-                },
-                c.into(),
-            );
-        }
+    fn step(&mut self) -> bool {
+        self.automaton.generation() < self.cycles && self.automaton.step()
     }
-
-    grid
 }
 
-fn active_flip_rule<G: Grid>(grid: &G, position: &G::CubePosition) -> bool {
-    let adjacent_active = grid.count_adjacent_active(position);
-    adjacent_active != 2 && adjacent_active != 3
+/// The cycles of [`part1_with`].
+pub fn part1_animation(cubes: &[Cube], params: &Params) -> Pocket<3> {
+    Pocket::new(cubes, params.cycles)
 }
 
-fn inactive_flip_rule<G: Grid>(grid: &G, position: &G::CubePosition) -> bool {
-    grid.count_adjacent_active(position) == 3
+/// The cycles of [`part2_with`].
+pub fn part2_animation(cubes: &[Cube], params: &Params) -> Pocket<4> {
+    Pocket::new(cubes, params.cycles)
 }
 
-fn cycle<P>(grid: &mut HashMapGrid<P>)
-where
-    HashMapGrid<P>: Infinite,
-    P: Position,
-{
-    let mut to_flip = Vec::new();
-
-    grid.expand();
-
-    for (position, cube_state) in grid.iter() {
-        if (*cube_state == CubeState::Inactive && inactive_flip_rule(&(*grid), position))
-            || (*cube_state == CubeState::Active && active_flip_rule(&(*grid), position))
-        {
-            to_flip.push(position.clone())
-        }
-    }
-
-    for position in to_flip.iter() {
-        if let Some(cube_state) = grid.get_mut(position) {
-            *cube_state = !(*cube_state);
-        }
-    }
+/// Counts the active cubes after six cycles in three dimensions.
+#[aoc(day17, part1)]
+pub fn part1(cubes: &[Cube]) -> usize {
+    part1_with(cubes, &Params::default())
 }
 
-fn count_active_after<P>(grid: &mut HashMapGrid<P>, cycles: usize) -> usize
-where
-    HashMapGrid<P>: Infinite,
-    P: Position,
-{
-    for _ in 1..=cycles {
-        cycle(grid);
-    }
-
-    grid.values()
-        .filter(|&state| *state == CubeState::Active)
-        .count()
+/// Same as [`part1`], after the number of cycles given by `params`.
+pub fn part1_with(cubes: &[Cube], params: &Params) -> usize {
+    let cubes = cubes
+        .iter()
+        .map(|&(position, state)| (Vector([position.x(), position.y(), 0]), state))
+        .collect();
+    count_active_after(cubes, params.cycles)
 }
 
-#[aoc(day17, part1)]
-fn part1(grid: &Grid3) -> usize {
-    let mut grid = grid.clone();
-    count_active_after(&mut grid, 6)
+/// Counts the active cubes after six cycles in four dimensions.
+#[aoc(day17, part2)]
+pub fn part2(cubes: &[Cube]) -> usize {
+    part2_with(cubes, &Params::default())
 }
 
-#[aoc(day17, part2)]
-fn part2(grid: &Grid4) -> usize {
-    let mut grid = grid.clone();
-    count_active_after(&mut grid, 6)
+/// Same as [`part2`], after the number of cycles given by `params`.
+pub fn part2_with(cubes: &[Cube], params: &Params) -> usize {
+    let cubes = cubes
+        .iter()
+        .map(|&(position, state)| (Vector([position.x(), position.y(), 0, 0]), state))
+        .collect();
+    count_active_after(cubes, params.cycles)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_STARTING_GRID: &str = r".#.
-..#
-###";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input1(TEST_STARTING_GRID,)), 112);
+        assert_eq!(part1(&parse(example(17, "example")).unwrap()), 112);
+        assert_eq!(
+            part1_with(
+                &parse(example(17, "example")).unwrap(),
+                &Params { cycles: 1 }
+            ),
+            11
+        );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input2(TEST_STARTING_GRID,)), 848);
+        assert_eq!(part2(&parse(example(17, "example")).unwrap()), 848);
+    }
+
+    #[test]
+    fn animation() {
+        let params = Params { cycles: 2 };
+        let mut pocket = part1_animation(&parse(example(17, "example")).unwrap(), &params);
+
+        assert!(pocket.step());
+        assert_eq!(
+            pocket.frame().to_plain(),
+            "day 17, cycle 1: 11 active cubes
+
+z=-1
+#
+  #
+ #
+
+z=0
+# #
+ ##
+ #
+
+z=1
+#
+  #
+ #"
+        );
     }
 }
@@ -1,185 +1,276 @@
+//! Day 18: Operation Order.
+
+use crate::overflow::Overflow;
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 
-#[derive(Eq, PartialEq, Hash)]
-enum Operation {
+#[derive(Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Operation {
     Add,
     Mul,
 }
 
-#[derive(Eq, PartialEq)]
-enum Token {
+#[derive(Eq, PartialEq, Serialize, Deserialize)]
+pub enum Token {
     Number(i64),
     Operator(Operation),
     LeftParenthesis,
     RightParenthesis,
 }
 
-struct Expression {
-    tokens: Vec<Token>,
+/// A well-formed expression, as the sequence of its tokens.
+#[derive(Serialize, Deserialize)]
+pub struct Expression {
+    pub tokens: Vec<Token>,
 }
 
 impl Expression {
-    fn evaluate(&self) -> i64 {
+    fn to_postfix(&self, check_precedence: bool) -> Vec<&Token> {
+        use Operation::*;
+        use Token::*;
+
+        let mut operator_stack: Vec<&Token> = Vec::new();
+        let mut output = Vec::new();
+
+        for token in &self.tokens {
+            match token {
+                Number(_) => output.push(token),
+                Operator(_) => {
+                    while let Some(operator_token) = operator_stack.pop() {
+                        if *operator_token == LeftParenthesis
+                            || (check_precedence
+                                && *operator_token == Operator(Mul)
+                                && *token == Operator(Add))
+                        {
+                            operator_stack.push(operator_token);
+                            break;
+                        } else {
+                            output.push(operator_token);
+                        }
+                    }
+
+                    operator_stack.push(token);
+                }
+                LeftParenthesis => {
+                    operator_stack.push(token);
+                }
+                RightParenthesis => {
+                    while let Some(operator_token) = operator_stack.pop() {
+                        if *operator_token == LeftParenthesis {
+                            break;
+                        } else {
+                            output.push(operator_token);
+                        }
+                    }
+                }
+            }
+        }
+
+        while let Some(operator_token) = operator_stack.pop() {
+            if *operator_token != LeftParenthesis {
+                output.push(operator_token);
+            }
+        }
+
+        output
+    }
+
+    /// Evaluates the expression left to right, giving addition precedence over
+    /// multiplication if `check_precedence` is set, in 128 bits as a few products of 64-bit
+    /// numbers are enough to overflow 64 bits.
+    pub fn evaluate(&self, check_precedence: bool) -> Result<i128, Overflow> {
         let mut stack = Vec::new();
 
         use Operation::*;
         use Token::*;
 
-        for token in &self.tokens {
+        for token in self.to_postfix(check_precedence) {
             match token {
                 Number(number) => {
-                    stack.push(*number);
+                    stack.push(*number as i128);
                 }
                 Operator(operation) if *operation == Add => {
-                    let lhs = stack.pop().unwrap();
+                    let lhs: i128 = stack.pop().unwrap();
                     let rhs = stack.pop().unwrap();
-                    stack.push(lhs + rhs);
+                    stack.push(lhs.checked_add(rhs).ok_or(Overflow("a sum"))?);
                 }
                 Operator(operation) if *operation == Mul => {
-                    let lhs = stack.pop().unwrap();
+                    let lhs: i128 = stack.pop().unwrap();
                     let rhs = stack.pop().unwrap();
-                    stack.push(lhs * rhs);
+                    stack.push(lhs.checked_mul(rhs).ok_or(Overflow("a product"))?);
                 }
                 _ => {}
             }
         }
 
-        stack.pop().unwrap()
+        Ok(stack.pop().unwrap())
     }
 }
 
-fn parse_expression(expression_str: &str, check_precedence: bool) -> Expression {
-    let expression_str = expression_str.replace("(", " ( ").replace(")", " ) ");
-
+fn tokenize<'a>(
+    source: &Source,
+    expression_str: &'a str,
+) -> Result<Vec<(&'a str, Token)>, ParseError> {
     use Operation::*;
     use Token::*;
 
-    let mut tokens: Vec<_> = expression_str
-        .split_whitespace()
-        .map(|token_str| match token_str {
-            "*" => Operator(Mul),
-            "+" => Operator(Add),
-            "(" => LeftParenthesis,
-            ")" => RightParenthesis,
-            _ => Number(token_str.parse::<i64>().unwrap()),
-        })
-        .collect();
-
-    let mut operator_stack = Vec::new();
-    let mut output = Vec::new();
-
-    for token in tokens.drain(0..) {
-        match token {
-            Number(_) => output.push(token),
-            Operator(_) => {
-                while let Some(operator_token) = operator_stack.pop() {
-                    if operator_token == LeftParenthesis
-                        || (check_precedence
-                            && operator_token == Operator(Mul)
-                            && token == Operator(Add))
-                    {
-                        operator_stack.push(operator_token);
-                        break;
-                    } else {
-                        output.push(operator_token);
-                    }
+    let mut tokens = Vec::new();
+    let mut chars = expression_str.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            '*' => Operator(Mul),
+            '+' => Operator(Add),
+            '(' => LeftParenthesis,
+            ')' => RightParenthesis,
+            c if c.is_whitespace() => continue,
+            c if c.is_ascii_digit() => {
+                let mut end = start + 1;
+
+                while let Some((i, _)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    end = i + 1;
                 }
 
-                operator_stack.push(token);
+                Number(source.parse(&expression_str[start..end])?)
             }
-            LeftParenthesis => {
-                operator_stack.push(token);
-            }
-            RightParenthesis => {
-                while let Some(operator_token) = operator_stack.pop() {
-                    if operator_token == LeftParenthesis {
-                        break;
-                    } else {
-                        output.push(operator_token);
-                    }
-                }
+            _ => {
+                return Err(source.error(
+                    &expression_str[start..start + c.len_utf8()],
+                    "unexpected character",
+                ))
             }
-        }
+        };
+
+        let end = chars.peek().map_or(expression_str.len(), |(i, _)| *i);
+        tokens.push((expression_str[start..end].trim_end(), token));
     }
 
-    while let Some(operator_token) = operator_stack.pop() {
-        if operator_token != LeftParenthesis {
-            output.push(operator_token);
+    Ok(tokens)
+}
+
+fn parse_expression(source: &Source, expression_str: &str) -> Result<Expression, ParseError> {
+    use Token::*;
+
+    let mut tokens = Vec::new();
+    let mut expect_operand = true;
+    let mut depth = 0;
+
+    for (token_str, token) in tokenize(source, expression_str)? {
+        match (&token, expect_operand) {
+            (Number(_), true) => expect_operand = false,
+            (Operator(_), false) => expect_operand = true,
+            (LeftParenthesis, true) => depth += 1,
+            (RightParenthesis, false) if depth > 0 => depth -= 1,
+            (RightParenthesis, false) => return Err(source.error(token_str, "unmatched ')'")),
+            (_, true) => return Err(source.error(token_str, "expected a number or '('")),
+            (_, false) => return Err(source.error(token_str, "expected an operator or ')'")),
         }
+
+        tokens.push(token);
     }
 
-    Expression { tokens: output }
+    if expect_operand {
+        return Err(source.error(
+            &expression_str[expression_str.len()..],
+            "expected a number or '('",
+        ));
+    }
+
+    Ok(Expression { tokens })
 }
 
-#[aoc_generator(day18, part1)]
-fn parse_input1(input: &str) -> Vec<Expression> {
+/// Parses the homework, one expression per line.
+#[aoc_generator(day18)]
+pub fn parse(input: &str) -> Result<Vec<Expression>, ParseError> {
+    let source = Source::new(18, input);
     input
         .lines()
-        .map(|line| parse_expression(line, false))
+        .map(|line| parse_expression(&source, line))
         .collect()
 }
 
-#[aoc_generator(day18, part2)]
-fn parse_input2(input: &str) -> Vec<Expression> {
-    input
-        .lines()
-        .map(|line| parse_expression(line, true))
-        .collect()
+fn sum(expressions: &[Expression], check_precedence: bool) -> Result<i128, Overflow> {
+    expressions.iter().try_fold(0i128, |sum, expression| {
+        sum.checked_add(expression.evaluate(check_precedence)?)
+            .ok_or(Overflow("the sum of the expressions"))
+    })
 }
 
+/// Sums the expressions evaluated strictly left to right.
 #[aoc(day18, part1)]
-fn part1(expressions: &[Expression]) -> i64 {
-    expressions
-        .iter()
-        .map(|expression| expression.evaluate())
-        .sum()
+pub fn part1(expressions: &[Expression]) -> Result<i128, Overflow> {
+    sum(expressions, false)
 }
 
+/// Sums the expressions evaluated with addition before multiplication.
 #[aoc(day18, part2)]
-fn part2(expressions: &[Expression]) -> i64 {
-    expressions
-        .iter()
-        .map(|expression| expression.evaluate())
-        .sum()
+pub fn part2(expressions: &[Expression]) -> Result<i128, Overflow> {
+    sum(expressions, true)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input1("1 + 2 * 3 + 4 * 5 + 6")), 71);
-        assert_eq!(part1(&parse_input1("1 + (2 * 3) + (4 * (5 + 6))")), 51);
-        assert_eq!(part1(&parse_input1("2 * 3 + (4 * 5)")), 26);
-        assert_eq!(part1(&parse_input1("5 + (8 * 3 + 9 + 3 * 4 * 3)")), 437);
+        assert_eq!(part1(&parse("1 + 2 * 3 + 4 * 5 + 6").unwrap()), Ok(71));
+        assert_eq!(
+            part1(&parse("1 + (2 * 3) + (4 * (5 + 6))").unwrap()),
+            Ok(51)
+        );
+        assert_eq!(part1(&parse("2 * 3 + (4 * 5)").unwrap()), Ok(26));
         assert_eq!(
-            part1(&parse_input1("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))")),
-            12_240
+            part1(&parse("5 + (8 * 3 + 9 + 3 * 4 * 3)").unwrap()),
+            Ok(437)
         );
         assert_eq!(
-            part1(&parse_input1(
-                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"
-            )),
-            13_632
+            part1(&parse("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))").unwrap()),
+            Ok(12_240)
         );
+        assert_eq!(
+            part1(&parse("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2").unwrap()),
+            Ok(13_632)
+        );
+        assert_eq!(part1(&parse(example(18, "example")).unwrap()), Ok(26_457));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input2("1 + 2 * 3 + 4 * 5 + 6")), 231);
-        assert_eq!(part2(&parse_input2("1 + (2 * 3) + (4 * (5 + 6)")), 51);
-        assert_eq!(part2(&parse_input2("2 * 3 + (4 * 5)")), 46);
-        assert_eq!(part2(&parse_input2("5 + (8 * 3 + 9 + 3 * 4 * 3")), 1_445);
+        assert_eq!(part2(&parse("1 + 2 * 3 + 4 * 5 + 6").unwrap()), Ok(231));
+        assert_eq!(part2(&parse("1 + (2 * 3) + (4 * (5 + 6)").unwrap()), Ok(51));
+        assert_eq!(part2(&parse("2 * 3 + (4 * 5)").unwrap()), Ok(46));
+        assert_eq!(
+            part2(&parse("5 + (8 * 3 + 9 + 3 * 4 * 3").unwrap()),
+            Ok(1_445)
+        );
+        assert_eq!(
+            part2(&parse("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))").unwrap()),
+            Ok(669_060)
+        );
+        assert_eq!(
+            part2(&parse("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2").unwrap()),
+            Ok(23_340)
+        );
+    }
+
+    #[test]
+    fn large_numbers() {
+        let large = "4000000000 * 4000000000 * 4000000000 + 1";
+
+        assert_eq!(
+            part1(&parse(large).unwrap()),
+            Ok(64_000_000_000_000_000_000_000_000_001)
+        );
         assert_eq!(
-            part2(&parse_input2("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))")),
-            669_060
+            part2(&parse(large).unwrap()),
+            Ok(64_000_000_016_000_000_000_000_000_000)
         );
         assert_eq!(
-            part2(&parse_input2(
-                "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"
-            )),
-            23_340
+            part1(&parse(&[large; 5].join(" * ")).unwrap()),
+            Err(Overflow("a product"))
         );
     }
 }
@@ -1,7 +1,13 @@
+//! Day 19: Monster Messages.
+
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
-enum Rule {
+/// A rule matching a single character, a sequence of rules or one of several sequences.
+#[derive(Serialize, Deserialize)]
+pub enum Rule {
     Character(char),
     Product(Vec<usize>),
     Sum(Vec<Vec<usize>>),
@@ -41,65 +47,101 @@ fn validate<'a>(message: &'a str, rules: &Rules, rule: &Rule) -> Result<&'a str,
     }
 }
 
-type Rules = HashMap<usize, Rule>;
+pub type Rules = HashMap<usize, Rule>;
+
+fn parse_product(
+    source: &Source,
+    product_str: &str,
+    rules_numbers: &HashSet<usize>,
+) -> Result<Vec<usize>, ParseError> {
+    product_str
+        .split_whitespace()
+        .map(|num| {
+            let number = source.parse(num)?;
+
+            if rules_numbers.contains(&number) {
+                Ok(number)
+            } else {
+                Err(source.error(num, "reference to an undefined rule"))
+            }
+        })
+        .collect()
+}
 
-fn parse_rule(rule_str: &str) -> Rule {
+fn parse_rule(
+    source: &Source,
+    rule_str: &str,
+    rules_numbers: &HashSet<usize>,
+) -> Result<Rule, ParseError> {
     use Rule::*;
 
     if rule_str.starts_with('"') {
-        Character(rule_str.chars().nth(1).unwrap())
+        let mut chars = rule_str.chars();
+
+        match (chars.next(), chars.next(), chars.next(), chars.next()) {
+            (Some('"'), Some(c), Some('"'), None) => Ok(Character(c)),
+            _ => Err(source.error(rule_str, "expected a single quoted character")),
+        }
     } else if rule_str.contains('|') {
-        Sum(rule_str
+        Ok(Sum(rule_str
             .split('|')
-            .map(|product_str| {
-                product_str
-                    .split_whitespace()
-                    .map(|num| num.parse::<usize>().unwrap())
-                    .collect()
-            })
-            .collect())
+            .map(|product_str| parse_product(source, product_str, rules_numbers))
+            .collect::<Result<_, _>>()?))
     } else {
-        Product(
-            rule_str
-                .split_whitespace()
-                .map(|num| num.parse::<usize>().unwrap())
-                .collect(),
-        )
+        Ok(Product(parse_product(source, rule_str, rules_numbers)?))
     }
 }
 
-fn parse_rules(rules_str: &str) -> Rules {
-    rules_str
-        .lines()
-        .map(|line| {
-            let mut split = line.split(": ");
-            (
-                split.next().unwrap().parse::<usize>().unwrap(),
-                parse_rule(split.next().unwrap()),
-            )
-        })
+fn parse_rules(source: &Source, rules_str: &str) -> Result<Rules, ParseError> {
+    let mut numbered_rules_strs = Vec::new();
+
+    for line in rules_str.lines() {
+        let mut split = line.split(": ");
+        let number = source.parse(source.next(&mut split, line, "a rule number")?)?;
+        let rule_str = source.next(&mut split, line, "\": \"")?;
+
+        numbered_rules_strs.push((number, rule_str));
+    }
+
+    let rules_numbers = numbered_rules_strs
+        .iter()
+        .map(|(number, _)| *number)
+        .collect();
+
+    numbered_rules_strs
+        .into_iter()
+        .map(|(number, rule_str)| Ok((number, parse_rule(source, rule_str, &rules_numbers)?)))
         .collect()
 }
 
-type Message = String;
-type Messages = Vec<Message>;
+pub type Message = String;
+pub type Messages = Vec<Message>;
 
 fn parse_messages(messages_str: &str) -> Messages {
     messages_str.lines().map(|line| line.to_string()).collect()
 }
 
+/// Parses the numbered rules, a blank line and then the received messages.
 #[aoc_generator(day19)]
-fn parse_input(input: &str) -> (Rules, Messages) {
+pub fn parse(input: &str) -> Result<(Rules, Messages), ParseError> {
+    let source = Source::new(19, input);
     let mut split = input.split("\n\n");
 
-    (
-        parse_rules(split.next().unwrap()),
-        parse_messages(split.next().unwrap()),
-    )
+    let rules = parse_rules(&source, source.next(&mut split, input, "rules")?)?;
+
+    if !rules.contains_key(&0) {
+        return Err(source.error(&input[..0], "expected a definition of rule 0"));
+    }
+
+    Ok((
+        rules,
+        parse_messages(source.next(&mut split, input, "messages")?),
+    ))
 }
 
+/// Counts the messages completely matching rule 0.
 #[aoc(day19, part1)]
-fn part1((rules, messages): &(Rules, Messages)) -> usize {
+pub fn part1((rules, messages): &(Rules, Messages)) -> usize {
     messages
         .iter()
         .filter(|&message| {
@@ -112,8 +154,9 @@ fn part1((rules, messages): &(Rules, Messages)) -> usize {
         .count()
 }
 
+/// Counts the messages completely matching rule 0, with the looping rules 8 and 11.
 #[aoc(day19, part2)]
-fn part2((rules, messages): &(Rules, Messages)) -> usize {
+pub fn part2((rules, messages): &(Rules, Messages)) -> usize {
     messages
         .iter()
         .filter(|&message| {
@@ -148,91 +191,31 @@ fn part2((rules, messages): &(Rules, Messages)) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse_input(
-                r#"0: 1 2
+            part1(
+                &parse(
+                    r#"0: 1 2
 1: "a"
 2: 1 3 | 3 1
 3: "b"
 
 aab
 aba"#
-            )),
-            2
-        );
-        assert_eq!(
-            part1(&parse_input(
-                r#"0: 4 1 5
-1: 2 3 | 3 2
-2: 4 4 | 5 5
-3: 4 5 | 5 4
-4: "a"
-5: "b"
-
-ababbb
-bababa
-abbbab
-aaabbb
-aaaabbb"#
-            )),
+                )
+                .unwrap()
+            ),
             2
         );
+        assert_eq!(part1(&parse(example(19, "example")).unwrap()), 2);
     }
 
     #[test]
     fn part2_example() {
-        static TEST_INPUT: &str = r#"42: 9 14 | 10 1
-9: 14 27 | 1 26
-10: 23 14 | 28 1
-1: "a"
-11: 42 31
-5: 1 14 | 15 1
-19: 14 1 | 14 14
-12: 24 14 | 19 1
-16: 15 1 | 14 14
-31: 14 17 | 1 13
-6: 14 14 | 1 14
-2: 1 24 | 14 4
-0: 8 11
-13: 14 3 | 1 12
-15: 1 | 14
-17: 14 2 | 1 7
-23: 25 1 | 22 14
-28: 16 1
-4: 1 1
-20: 14 14 | 1 15
-3: 5 14 | 16 1
-27: 1 6 | 14 18
-14: "b"
-21: 14 1 | 1 14
-25: 1 1 | 1 14
-22: 14 14
-8: 42
-26: 14 22 | 1 20
-18: 15 15
-7: 14 5 | 1 21
-24: 14 1
-
-abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
-bbabbbbaabaabba
-babbbbaabbbbbabbbbbbaabaaabaaa
-aaabbbbbbaaaabaababaabababbabaaabbababababaaa
-bbbbbbbaaaabbbbaaabbabaaa
-bbbababbbbaaaaaaaabbababaaababaabab
-ababaaaaaabaaab
-ababaaaaabbbaba
-baabbaaaabbaaaababbaababb
-abbbbabbbbaaaababbbbbbaaaababb
-aaaaabbaabaaaaababaa
-aaaabbaaaabbaaa
-aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
-babaaabbbaaabaababbaabababaaab
-aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#;
-
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 3);
-        assert_eq!(part2(&parse_input(TEST_INPUT)), 12);
+        assert_eq!(part1(&parse(example(19, "part2")).unwrap()), 3);
+        assert_eq!(part2(&parse(example(19, "part2")).unwrap()), 12);
     }
 }
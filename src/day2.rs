@@ -1,26 +1,52 @@
+//! Day 2: Password Philosophy.
+
+use crate::parsing::{parse_lines, ParseError, Source, StreamError};
 use aoc_runner_derive::{aoc, aoc_generator};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+fn parse_line(source: &Source, line: &str) -> Result<PasswordEntry, ParseError> {
+    let mut tokens = line.split_ascii_whitespace().peekable();
+
+    let policy = match tokens.next_if(|token| token.starts_with('@')) {
+        Some("@") => return Err(source.error("@", "expected a policy name after '@'")),
+        Some(token) => Some(token[1..].to_string()),
+        None => None,
+    };
 
-fn parse_line(line: &str) -> PasswordEntry {
-    let mut tokens = line.split_ascii_whitespace();
-    let mut range_split = tokens.next().unwrap().split('-');
-    let min = range_split.next().unwrap().parse::<usize>().unwrap();
-    let max = range_split.next().unwrap().parse::<usize>().unwrap();
-    let character = tokens.next().unwrap().chars().next().unwrap();
-    let password = tokens.next().unwrap().to_string();
+    let range_str = source.next(&mut tokens, line, "a range")?;
+    let mut range_split = range_str.split('-');
+    let min = source.parse(source.next(&mut range_split, range_str, "a minimum")?)?;
+    let max = source.parse(source.next(&mut range_split, range_str, "a maximum")?)?;
 
-    PasswordEntry {
+    let character_str = source.next(&mut tokens, line, "a character")?;
+    let character = match character_str.strip_suffix(':') {
+        Some(c) if c.chars().count() == 1 => c.chars().next().unwrap(),
+        _ => return Err(source.error(character_str, "expected a character followed by ':'")),
+    };
+
+    let password = source.next(&mut tokens, line, "a password")?.to_string();
+
+    Ok(PasswordEntry {
         min,
         max,
         character,
         password,
-    }
+        policy,
+    })
 }
 
+/// Parses the password database, one `<min>-<max> <char>: <password>` entry per line, which
+/// may start with `@<name>` to audit it under the policy of that name.
 #[aoc_generator(day2)]
-fn parse_input(input: &str) -> Vec<PasswordEntry> {
+pub fn parse(input: &str) -> Result<Vec<PasswordEntry>, ParseError> {
+    let source = Source::new(2, input);
     input
         .lines()
-        .map(|line| -> _ { parse_line(line) })
+        .map(|line| parse_line(&source, line))
         .collect()
 }
 
@@ -33,20 +59,36 @@ fn validate_password_part1(entry: &PasswordEntry) -> bool {
     count >= entry.min && count <= entry.max
 }
 
+/// Counts passwords containing the policy character between `min` and `max` times.
 #[aoc(day2, part1)]
-fn part1(passwords: &[PasswordEntry]) -> usize {
+pub fn part1(passwords: &[PasswordEntry]) -> usize {
     passwords
         .iter()
         .filter(|&entry| validate_password_part1(entry))
         .count()
 }
 
+/// Counts the valid passwords of the database read from `reader`, one entry at a time.
+fn count_valid_stream(
+    reader: impl BufRead,
+    validate: fn(&PasswordEntry) -> bool,
+) -> Result<usize, StreamError> {
+    parse_lines(2, reader, parse_line)
+        .try_fold(0, |count, entry| Ok(count + validate(&entry?) as usize))
+}
+
+/// Same as [`part1`], reading the database line by line.
+pub fn part1_stream(reader: impl BufRead) -> Result<usize, StreamError> {
+    count_valid_stream(reader, validate_password_part1)
+}
+
+/// The character at a position of the password, from 1.
+fn nth(string: &str, index: usize) -> Option<char> {
+    string.chars().nth(index.checked_sub(1)?)
+}
+
 fn contains_nth(string: &str, character: &char, index: &usize) -> bool {
-    if let Some(c) = string.chars().nth(index - 1) {
-        c == *character
-    } else {
-        false
-    }
+    nth(string, *index) == Some(*character)
 }
 
 fn validate_password_part2(entry: &PasswordEntry) -> bool {
@@ -56,36 +98,668 @@ fn validate_password_part2(entry: &PasswordEntry) -> bool {
     (contains_min || contains_max) && !(contains_min && contains_max)
 }
 
+/// Counts passwords with the policy character at exactly one of the two positions.
 #[aoc(day2, part2)]
-fn part2(passwords: &[PasswordEntry]) -> usize {
+pub fn part2(passwords: &[PasswordEntry]) -> usize {
     passwords
         .iter()
         .filter(|&entry| validate_password_part2(entry))
         .count()
 }
 
-struct PasswordEntry {
-    min: usize,
-    max: usize,
-    character: char,
-    password: String,
+/// Same as [`part2`], reading the database line by line.
+pub fn part2_stream(reader: impl BufRead) -> Result<usize, StreamError> {
+    count_valid_stream(reader, validate_password_part2)
+}
+
+/// A password together with the policy it was set under.
+#[derive(Serialize, Deserialize)]
+pub struct PasswordEntry {
+    pub min: usize,
+    pub max: usize,
+    pub character: char,
+    pub password: String,
+    /// The name of the policy to audit the password under, instead of that of the run.
+    pub policy: Option<String>,
+}
+
+/// What a policy checked of a password.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Check {
+    /// Whether the password follows the rule.
+    pub valid: bool,
+    /// The rule checked, the one violated if any.
+    pub rule: String,
+    /// What the rule observed of the password, such as the number of times of a character.
+    pub observed: String,
+}
+
+impl Check {
+    fn new(valid: bool, rule: String, observed: String) -> Self {
+        Check {
+            valid,
+            rule,
+            observed,
+        }
+    }
+}
+
+/// A rule the passwords of a database must follow, using the policy of their entry or not.
+pub trait PasswordPolicy: Send + Sync {
+    fn validate(&self, entry: &PasswordEntry) -> bool;
+
+    /// The rule and what it observed, for audits; policies which cannot explain themselves
+    /// only tell whether the password is valid.
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        Check::new(
+            self.validate(entry),
+            "custom policy".to_string(),
+            String::new(),
+        )
+    }
+}
+
+impl<F: Fn(&PasswordEntry) -> bool + Send + Sync> PasswordPolicy for F {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        self(entry)
+    }
+}
+
+/// The policy of part 1: the character occurs from `min` to `max` times.
+pub struct CountInRange;
+
+impl PasswordPolicy for CountInRange {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        validate_password_part1(entry)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        let count = entry
+            .password
+            .chars()
+            .filter(|c| *c == entry.character)
+            .count();
+
+        Check::new(
+            self.validate(entry),
+            format!("{}-{} times {:?}", entry.min, entry.max, entry.character),
+            format!("{} time{}", count, if count == 1 { "" } else { "s" }),
+        )
+    }
+}
+
+/// The policy of part 2: the character is at exactly one of the positions `min` and `max`,
+/// from 1.
+pub struct ExactlyOnePosition;
+
+impl PasswordPolicy for ExactlyOnePosition {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        validate_password_part2(entry)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        let at = |position| match nth(&entry.password, position) {
+            Some(c) => format!("{:?}", c),
+            None => "nothing".to_string(),
+        };
+
+        Check::new(
+            self.validate(entry),
+            format!(
+                "{:?} at one of positions {} and {}",
+                entry.character, entry.min, entry.max
+            ),
+            format!("{} and {}", at(entry.min), at(entry.max)),
+        )
+    }
+}
+
+/// The password matches a regular expression, anchored or not.
+pub struct Matches(pub Regex);
+
+impl PasswordPolicy for Matches {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        self.0.is_match(&entry.password)
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        let valid = self.validate(entry);
+
+        Check::new(
+            valid,
+            format!("matches /{}/", self.0.as_str()),
+            if valid { "a match" } else { "no match" }.to_string(),
+        )
+    }
+}
+
+/// The Shannon entropy of the characters of the password, in bits per character, times its
+/// length.
+pub fn entropy(password: &str) -> f64 {
+    let mut counts = HashMap::<char, usize>::new();
+
+    for c in password.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+
+    let length = counts.values().sum::<usize>() as f64;
+
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / length;
+            -p * p.log2() * length
+        })
+        .sum()
+}
+
+/// The password has at least this many bits of [`entropy`].
+pub struct MinEntropy(pub f64);
+
+impl PasswordPolicy for MinEntropy {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        entropy(&entry.password) >= self.0
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        Check::new(
+            self.validate(entry),
+            format!("at least {} bits of entropy", self.0),
+            format!("{:.1} bits", entropy(&entry.password)),
+        )
+    }
+}
+
+/// The password has characters of at least this many of the four classes: lowercase and
+/// uppercase letters, digits and the others.
+pub struct CharacterClasses(pub usize);
+
+/// A class of characters, by name.
+type Class = (&'static str, fn(char) -> bool);
+
+impl CharacterClasses {
+    /// The names of the classes of the characters of the password.
+    fn classes(password: &str) -> Vec<&'static str> {
+        let classes: [Class; 4] = [
+            ("lowercase", char::is_lowercase),
+            ("uppercase", char::is_uppercase),
+            ("digits", char::is_numeric),
+            ("others", |c| !c.is_alphanumeric()),
+        ];
+
+        classes
+            .iter()
+            .filter(|(_, class)| password.chars().any(class))
+            .map(|&(name, _)| name)
+            .collect()
+    }
+}
+
+impl PasswordPolicy for CharacterClasses {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        CharacterClasses::classes(&entry.password).len() >= self.0
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        let classes = CharacterClasses::classes(&entry.password);
+
+        Check::new(
+            classes.len() >= self.0,
+            format!("at least {} of the 4 character classes", self.0),
+            match classes.len() {
+                0 => "no characters".to_string(),
+                _ => classes.join(", "),
+            },
+        )
+    }
+}
+
+/// The password contains none of these substrings, whatever their case.
+pub struct ForbiddenSubstrings(pub Vec<String>);
+
+impl ForbiddenSubstrings {
+    fn found<'a>(&'a self, password: &str) -> impl Iterator<Item = &'a String> {
+        let password = password.to_lowercase();
+
+        self.0
+            .iter()
+            .filter(move |forbidden| password.contains(&forbidden.to_lowercase()))
+    }
+}
+
+impl PasswordPolicy for ForbiddenSubstrings {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        self.found(&entry.password).next().is_none()
+    }
+
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        let quoted = |words: Vec<&String>| {
+            words
+                .iter()
+                .map(|word| format!("{:?}", word))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let found = self.found(&entry.password).collect::<Vec<_>>();
+
+        Check::new(
+            found.is_empty(),
+            format!("none of {}", quoted(self.0.iter().collect())),
+            match found.len() {
+                0 => "none of them".to_string(),
+                _ => format!("contains {}", quoted(found)),
+            },
+        )
+    }
+}
+
+/// The password follows every policy of a rule set.
+pub struct RuleSet(pub Vec<Arc<dyn PasswordPolicy>>);
+
+impl PasswordPolicy for RuleSet {
+    fn validate(&self, entry: &PasswordEntry) -> bool {
+        self.0.iter().all(|policy| policy.validate(entry))
+    }
+
+    /// The check of the first policy violated, or of them all joined if none is.
+    fn check(&self, entry: &PasswordEntry) -> Check {
+        let checks = self
+            .0
+            .iter()
+            .map(|policy| policy.check(entry))
+            .collect::<Vec<_>>();
+
+        checks
+            .iter()
+            .find(|check| !check.valid)
+            .cloned()
+            .unwrap_or_else(|| {
+                Check::new(
+                    true,
+                    checks
+                        .iter()
+                        .map(|check| check.rule.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" and "),
+                    checks
+                        .iter()
+                        .map(|check| check.observed.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                )
+            })
+    }
+}
+
+/// Builds a policy from its `<kind>[:<argument>]` spec: `count`, `position`,
+/// `regex:<pattern>`, `entropy:<bits>`, `classes:<1 to 4>` or `forbidden:<word>,<word>...`.
+pub fn policy(spec: &str) -> Result<Arc<dyn PasswordPolicy>, String> {
+    let spec = spec.trim();
+
+    Ok(match spec.split_once(':') {
+        None if spec == "count" => Arc::new(CountInRange),
+        None if spec == "position" => Arc::new(ExactlyOnePosition),
+        Some(("regex", pattern)) => Arc::new(Matches(
+            Regex::new(pattern).map_err(|error| format!("invalid regex: {}", error))?,
+        )),
+        Some(("entropy", bits)) => Arc::new(MinEntropy(
+            bits.trim()
+                .parse()
+                .ok()
+                .filter(|bits: &f64| *bits >= 0.0)
+                .ok_or("entropy must be a number of bits from 0")?,
+        )),
+        Some(("classes", count)) => Arc::new(CharacterClasses(
+            count
+                .trim()
+                .parse()
+                .ok()
+                .filter(|count| (1..=4).contains(count))
+                .ok_or("classes must be a number from 1 to 4")?,
+        )),
+        Some(("forbidden", words)) => Arc::new(ForbiddenSubstrings(
+            words
+                .split(',')
+                .map(|word| word.trim().to_string())
+                .filter(|word| !word.is_empty())
+                .collect(),
+        )),
+        _ => return Err(format!("unknown policy {:?}", spec)),
+    })
+}
+
+/// The policies by name, `count` and `position` for the two parts by default.
+#[derive(Clone)]
+pub struct Policies(HashMap<String, Arc<dyn PasswordPolicy>>);
+
+impl Default for Policies {
+    fn default() -> Self {
+        let mut policies = Policies(HashMap::new());
+        policies.register("count", Arc::new(CountInRange));
+        policies.register("position", Arc::new(ExactlyOnePosition));
+        policies
+    }
+}
+
+impl Policies {
+    /// Adds the policy under `name`, replacing any policy of that name.
+    pub fn register(&mut self, name: &str, policy: Arc<dyn PasswordPolicy>) {
+        self.0.insert(name.to_string(), policy);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn PasswordPolicy> {
+        self.0.get(name).map(|policy| policy.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        let mut names = self.0.keys().map(String::as_str).collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    /// Parses a file of `<name> = <spec>` lines on top of the default policies, the specs of
+    /// the lines of a name making up a [`RuleSet`]. Blank lines and lines starting with `#`
+    /// are ignored.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rule_sets = Vec::<(&str, Vec<Arc<dyn PasswordPolicy>>)>::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |error| format!("line {}: {}", i + 1, error);
+            let (name, spec) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected <name> = <spec>".to_string()))?;
+            let (name, policy) = (name.trim(), policy(spec).map_err(error)?);
+
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(error(format!("invalid policy name {:?}", name)));
+            }
+
+            match rule_sets.iter_mut().find(|(other, _)| *other == name) {
+                Some((_, policies)) => policies.push(policy),
+                None => rule_sets.push((name, vec![policy])),
+            }
+        }
+
+        let mut policies = Policies::default();
+
+        for (name, rule_set) in rule_sets {
+            policies.register(name, Arc::new(RuleSet(rule_set)));
+        }
+
+        Ok(policies)
+    }
+
+    /// The name and the policy of the entry of a line: the one it names, or else `default`.
+    fn policy_of<'a>(
+        &self,
+        line: usize,
+        entry: &'a PasswordEntry,
+        default: &'a str,
+    ) -> Result<(&'a str, &dyn PasswordPolicy), String> {
+        let name = entry.policy.as_deref().unwrap_or(default);
+
+        self.get(name)
+            .map(|policy| (name, policy))
+            .ok_or_else(|| format!("line {}: unknown policy {:?}", line, name))
+    }
+
+    /// Counts the passwords following their policy: the one named by their entry, or else
+    /// the one named `default`.
+    pub fn count_valid(&self, entries: &[PasswordEntry], default: &str) -> Result<usize, String> {
+        entries.iter().enumerate().try_fold(0, |count, (i, entry)| {
+            let (_, policy) = self.policy_of(i + 1, entry, default)?;
+            Ok(count + policy.validate(entry) as usize)
+        })
+    }
+
+    /// Checks every password under its policy, as for [`Policies::count_valid`].
+    pub fn audit(
+        &self,
+        entries: &[PasswordEntry],
+        default: &str,
+    ) -> Result<Vec<AuditRecord>, String> {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let (name, policy) = self.policy_of(i + 1, entry, default)?;
+
+                Ok(AuditRecord {
+                    line: i + 1,
+                    policy: name.to_string(),
+                    password: entry.password.clone(),
+                    check: policy.check(entry),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The audit of a password of the database.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AuditRecord {
+    /// The line of the entry, from 1.
+    pub line: usize,
+    /// The name of the policy the password was checked under.
+    pub policy: String,
+    pub password: String,
+    #[serde(flatten)]
+    pub check: Check,
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the records as CSV, after a header line.
+pub fn write_csv(records: &[AuditRecord], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "line,policy,password,valid,rule,observed")?;
+
+    for record in records {
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            record.line,
+            csv_field(&record.policy),
+            csv_field(&record.password),
+            record.check.valid,
+            csv_field(&record.check.rule),
+            csv_field(&record.check.observed)
+        )?;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert!(validate_password_part1(&parse_line("1-3 a: abcde")));
-        assert!(!validate_password_part1(&parse_line("1-3 b: cdefg")));
-        assert!(validate_password_part1(&parse_line("2-9 c: ccccccccc")));
+        let entries = parse(example(2, "example")).unwrap();
+
+        assert!(validate_password_part1(&entries[0]));
+        assert!(!validate_password_part1(&entries[1]));
+        assert!(validate_password_part1(&entries[2]));
     }
 
     #[test]
     fn part2_example() {
-        assert!(validate_password_part2(&parse_line("1-3 a: abcde")));
-        assert!(!validate_password_part2(&parse_line("1-3 b: cdefg")));
-        assert!(!validate_password_part2(&parse_line("2-9 c: ccccccccc")));
+        let entries = parse(example(2, "example")).unwrap();
+
+        assert!(validate_password_part2(&entries[0]));
+        assert!(!validate_password_part2(&entries[1]));
+        assert!(!validate_password_part2(&entries[2]));
+    }
+
+    #[test]
+    fn policies() {
+        let entries = parse(example(2, "example")).unwrap();
+        let policies = Policies::default();
+
+        assert_eq!(policies.names(), vec!["count", "position"]);
+        assert_eq!(policies.count_valid(&entries, "count"), Ok(part1(&entries)));
+        assert_eq!(
+            policies.count_valid(&entries, "position"),
+            Ok(part2(&entries))
+        );
+        assert!(policies.count_valid(&entries, "strict").is_err());
+
+        let valid = |spec: &str, password: &str| {
+            policy(spec).unwrap().validate(&PasswordEntry {
+                min: 1,
+                max: 3,
+                character: 'a',
+                password: password.to_string(),
+                policy: None,
+            })
+        };
+
+        assert!(valid("count", "abcde") && !valid("count", "bcde"));
+        assert!(valid("position", "cbade") && !valid("position", "abace"));
+        assert!(valid("regex:^[a-z]{5}$", "abcde") && !valid("regex:^[a-z]{5}$", "abcd"));
+        assert!(valid("entropy:8", "abcd") && !valid("entropy:8", "aaaaaaab"));
+        assert!(valid("classes:3", "aB3") && !valid("classes:3", "aB"));
+        assert!(valid("classes:4", "aB3!"));
+        assert!(!valid("forbidden:pass, 123", "myPASSword"));
+        assert!(valid("forbidden:pass, 123", "hunter2"));
+        assert_eq!(entropy("aabb"), 4.0);
+        assert_eq!(entropy(""), 0.0);
+
+        assert!(policy("regex:(").is_err());
+        assert!(policy("entropy:-1").is_err());
+        assert!(policy("classes:5").is_err());
+        assert!(policy("length:8").is_err());
+    }
+
+    #[test]
+    fn rule_sets() {
+        let mut policies = Policies::parse(
+            "# Corporate rules\nacme = classes:2\nacme = forbidden:acme\n\nlax = count\n",
+        )
+        .unwrap();
+        policies.register(
+            "short",
+            Arc::new(|entry: &PasswordEntry| entry.password.len() < 6),
+        );
+
+        let entries = parse(
+            "1-3 a: abcde\n@acme 1-3 a: Acme2020\n@acme 1-3 a: Hunter2\n@short 1-3 b: cdefgh",
+        )
+        .unwrap();
+
+        assert_eq!(entries[1].policy.as_deref(), Some("acme"));
+        assert_eq!(
+            policies.names(),
+            vec!["acme", "count", "lax", "position", "short"]
+        );
+        assert_eq!(policies.count_valid(&entries, "lax"), Ok(2));
+        assert_eq!(policies.count_valid(&entries[1..], "none"), Ok(1));
+        assert_eq!(
+            policies.count_valid(&parse("@strict 1-3 a: a").unwrap(), "count"),
+            Err("line 1: unknown policy \"strict\"".to_string())
+        );
+
+        assert!(Policies::parse("acme classes:2").is_err());
+        assert_eq!(
+            Policies::parse("acme = classes:2\nacme = classes:9").err(),
+            Some("line 2: classes must be a number from 1 to 4".to_string())
+        );
+        assert!(Policies::parse("my rules = count").is_err());
+        assert!(parse("@ 1-3 a: abcde").is_err());
+    }
+
+    #[test]
+    fn audits() {
+        let policies = Policies::parse("acme = classes:2\nacme = forbidden:acme, 20").unwrap();
+        let entries = parse(&format!(
+            "{}\n@acme 1-3 a: Acme2020\n@acme 1-3 a: Hunter2\n@position 1-9 c: ccc",
+            example(2, "example").trim_end()
+        ))
+        .unwrap();
+        let records = policies.audit(&entries, "count").unwrap();
+        let checks = records
+            .iter()
+            .map(|record| {
+                let check = &record.check;
+                (check.valid, check.rule.as_str(), check.observed.as_str())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            checks,
+            vec![
+                (true, "1-3 times 'a'", "1 time"),
+                (false, "1-3 times 'b'", "0 times"),
+                (true, "2-9 times 'c'", "9 times"),
+                (
+                    false,
+                    "none of \"acme\", \"20\"",
+                    "contains \"acme\", \"20\""
+                ),
+                (
+                    true,
+                    "at least 2 of the 4 character classes and none of \"acme\", \"20\"",
+                    "lowercase, uppercase, digits; none of them"
+                ),
+                (true, "'c' at one of positions 1 and 9", "'c' and nothing"),
+            ]
+        );
+        assert_eq!((records[3].line, records[3].policy.as_str()), (4, "acme"));
+        assert_eq!(
+            serde_json::to_string(&records[0]).unwrap(),
+            r#"{"line":1,"policy":"count","password":"abcde","valid":true,"rule":"1-3 times 'a'","observed":"1 time"}"#
+        );
+
+        let mut csv = Vec::new();
+        write_csv(&records[3..4], &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "line,policy,password,valid,rule,observed\n\
+             4,acme,Acme2020,false,\"none of \"\"acme\"\", \"\"20\"\"\",\
+             \"contains \"\"acme\"\", \"\"20\"\"\"\n"
+        );
+
+        let custom = |entry: &PasswordEntry| entry.password.len() > 8;
+        let check = custom.check(&entries[0]);
+
+        assert_eq!((check.valid, check.rule.as_str()), (false, "custom policy"));
+        assert!(ExactlyOnePosition.validate(&parse("0-1 a: a").unwrap()[0]));
+    }
+
+    #[test]
+    fn malformed_input() {
+        let error = parse("1-3 a: abcde\n1-x b: cdefg").err().unwrap();
+
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.token, "x");
+
+        let error = parse("1-3 a abcde").err().unwrap();
+
+        assert_eq!((error.line, error.column), (1, 5));
+    }
+
+    #[test]
+    fn streamed() {
+        assert_eq!(part1_stream(example(2, "example").as_bytes()).unwrap(), 2);
+        assert_eq!(part2_stream(example(2, "example").as_bytes()).unwrap(), 1);
+
+        match part1_stream("1-3 a: abcde\n1-x b: cdefg".as_bytes()) {
+            Err(StreamError::Parse(error)) => assert_eq!((error.line, error.column), (2, 3)),
+            _ => panic!("expected a parse error"),
+        }
     }
 }
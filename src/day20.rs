@@ -1,14 +1,20 @@
+//! Day 20: Jurassic Jigsaw.
+
+use crate::geometry::Vector2;
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::ops::{Add, Not};
+use std::ops::Not;
 
 const TILE_SIZE: i32 = 10;
 const CROPPED_TILE_SIZE: i32 = TILE_SIZE - 2;
 
-type Pixel = bool;
+pub type Pixel = bool;
 
+/// The edges of a tile, in clockwise order.
 #[derive(Copy, Clone)]
-enum EdgeIndex {
+pub enum EdgeIndex {
     Upper = 0,
     Right = 1,
     Lower = 2,
@@ -30,31 +36,8 @@ impl Not for &EdgeIndex {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-struct Vector2 {
-    x: i32,
-    y: i32,
-}
-
-type Position = Vector2;
-type Direction = Vector2;
-
-impl Add<Vector2> for Vector2 {
-    type Output = Self;
-
-    fn add(self, rhs: Vector2) -> Self::Output {
-        Vector2 {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl From<(i32, i32)> for Vector2 {
-    fn from((x, y): (i32, i32)) -> Self {
-        Vector2 { x, y }
-    }
-}
+pub type Position = Vector2;
+pub type Direction = Vector2;
 
 impl From<&EdgeIndex> for Direction {
     fn from(edge_index: &EdgeIndex) -> Self {
@@ -69,13 +52,14 @@ impl From<&EdgeIndex> for Direction {
     }
 }
 
-#[derive(Clone, Debug)]
-struct Image(HashMap<Position, Pixel>);
+/// An image, with `true` for the `#` pixels; `y` grows downwards.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Image(#[serde(with = "crate::model::pairs")] pub HashMap<Position, Pixel>);
 
 impl Image {
     fn normalize(&self) -> Image {
-        let min_x = self.0.keys().map(|position| position.x).min().unwrap();
-        let min_y = self.0.keys().map(|position| position.y).min().unwrap();
+        let min_x = self.0.keys().map(|position| position.x()).min().unwrap();
+        let min_y = self.0.keys().map(|position| position.y()).min().unwrap();
 
         Image(
             self.0
@@ -85,46 +69,37 @@ impl Image {
         )
     }
 
-    fn transform(&self, source_index: &EdgeIndex, target_index: &EdgeIndex, flip: bool) -> Image {
-        let source: Direction = source_index.into();
-        let target: Direction = target_index.into();
+    /// Rotates the image so that its `source_index` edge ends up at `target_index`, then flips
+    /// it along the axis through `target_index` if `flip` is set.
+    pub fn transform(
+        &self,
+        source_index: &EdgeIndex,
+        target_index: &EdgeIndex,
+        flip: bool,
+    ) -> Image {
+        use EdgeIndex::*;
 
-        let cos = source.x * target.x + source.y * target.y;
-        let sin = source.x * target.y - source.y * target.x;
+        // The edges are in clockwise order, and a quarter turn from x to y is clockwise as y
+        // grows downwards.
+        let quarter_turns = *target_index as i32 - *source_index as i32;
+        let flip_axis = match target_index {
+            Upper | Lower => 0,
+            Right | Left => 1,
+        };
 
-        let mut pixels: HashMap<Position, Pixel> = self
+        let pixels = self
             .0
             .iter()
-            .map(|(position, value)| {
-                (
-                    (
-                        position.x * cos - position.y * sin,
-                        position.x * sin + position.y * cos,
-                    )
-                        .into(),
-                    *value,
-                )
-            })
-            .collect();
+            .map(|(&position, &value)| {
+                let position = position.rotate(0, 1, quarter_turns);
 
-        use EdgeIndex::*;
-
-        if flip {
-            match &target_index {
-                Upper | Lower => {
-                    pixels = pixels
-                        .iter()
-                        .map(|(position, value)| ((-position.x, position.y).into(), *value))
-                        .collect()
+                if flip {
+                    (position.reflect(flip_axis), value)
+                } else {
+                    (position, value)
                 }
-                Right | Left => {
-                    pixels = pixels
-                        .iter()
-                        .map(|(position, value)| ((position.x, -position.y).into(), *value))
-                        .collect()
-                }
-            }
-        }
+            })
+            .collect();
 
         Image(pixels).normalize()
     }
@@ -140,15 +115,20 @@ impl Image {
     }
 }
 
-type TileId = u64;
-type EdgeChecksum = usize;
-
-#[derive(Clone)]
-struct Tile {
-    id: TileId,
-    image: Image,
-    edges_checksums: [EdgeChecksum; 4],
-    flipped_edges_checksums: [EdgeChecksum; 4],
+pub type TileId = u64;
+/// An edge read as a binary number, clockwise (or counterclockwise if flipped).
+pub type EdgeChecksum = usize;
+pub type Tiles = HashMap<TileId, Tile>;
+/// The tiles having each edge, flipped or not.
+pub type Edges = HashMap<EdgeChecksum, Vec<TileId>>;
+
+/// A camera tile: its image without the borders, and the checksums of its edges.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Tile {
+    pub id: TileId,
+    pub image: Image,
+    pub edges_checksums: [EdgeChecksum; 4],
+    pub flipped_edges_checksums: [EdgeChecksum; 4],
 }
 
 impl Tile {
@@ -193,21 +173,40 @@ impl Tile {
     }
 }
 
-fn parse_tile(tile_str: &str) -> Tile {
-    let id_str = tile_str.lines().next().unwrap();
-    let id = id_str[5..id_str.len() - 1].parse::<TileId>().unwrap();
+fn parse_tile(source: &Source, tile_str: &str) -> Result<Tile, ParseError> {
+    let mut lines = tile_str.lines();
+    let id_str = source.next(&mut lines, tile_str, "a tile header")?;
+    let id = match id_str
+        .strip_prefix("Tile ")
+        .and_then(|s| s.strip_suffix(':'))
+    {
+        Some(id_str) => source.parse::<TileId>(id_str)?,
+        None => return Err(source.error(id_str, "expected \"Tile <id>:\"")),
+    };
 
-    let pixels: HashMap<Position, Pixel> = tile_str
-        .lines()
-        .skip(1)
-        .enumerate()
-        .map(move |(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(x, c)| ((x as i32, y as i32).into(), c == '#'))
-        })
-        .flatten()
-        .collect();
+    let mut pixels: HashMap<Position, Pixel> = HashMap::new();
+
+    for y in 0..TILE_SIZE {
+        let line = source.next(&mut lines, tile_str, "a row of pixels")?;
+
+        if line.len() != TILE_SIZE as usize {
+            return Err(source.error(line, format!("expected {} pixels", TILE_SIZE)));
+        }
+
+        for (x, c) in line.char_indices() {
+            let pixel = match c {
+                '#' => true,
+                '.' => false,
+                _ => return Err(source.error(&line[x..x + c.len_utf8()], "expected '#' or '.'")),
+            };
+
+            pixels.insert((x as i32, y).into(), pixel);
+        }
+    }
+
+    if let Some(line) = lines.next() {
+        return Err(source.error(line, format!("expected {} rows of pixels", TILE_SIZE)));
+    }
 
     let edges_checksums = edges_checksums(&pixels);
     let flipped_edges_checksums = flipped_edges_checksums(&pixels);
@@ -215,47 +214,47 @@ fn parse_tile(tile_str: &str) -> Tile {
     let pixels = pixels
         .into_iter()
         .filter(|(position, _)| {
-            position.x != 0
-                && position.y != 0
-                && position.x != TILE_SIZE - 1
-                && position.y != TILE_SIZE - 1
+            position.x() != 0
+                && position.y() != 0
+                && position.x() != TILE_SIZE - 1
+                && position.y() != TILE_SIZE - 1
         })
         .collect();
 
-    Tile {
+    Ok(Tile {
         id,
         image: Image(pixels).normalize(),
         edges_checksums,
         flipped_edges_checksums,
-    }
+    })
 }
 
 fn edges_checksums(pixels: &HashMap<Position, Pixel>) -> [EdgeChecksum; 4] {
     let upper_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.y == 0)
-        .map(|(position, &pixel)| (2_usize.pow(position.x as u32) * (pixel as EdgeChecksum)))
+        .filter(|(&position, _)| position.y() == 0)
+        .map(|(position, &pixel)| 2_usize.pow(position.x() as u32) * (pixel as EdgeChecksum))
         .sum::<EdgeChecksum>();
 
     let right_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.x == (TILE_SIZE - 1))
-        .map(|(position, &pixel)| (2_usize.pow(position.y as u32) * (pixel as EdgeChecksum)))
+        .filter(|(&position, _)| position.x() == (TILE_SIZE - 1))
+        .map(|(position, &pixel)| 2_usize.pow(position.y() as u32) * (pixel as EdgeChecksum))
         .sum::<EdgeChecksum>();
 
     let lower_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.y == (TILE_SIZE - 1))
+        .filter(|(&position, _)| position.y() == (TILE_SIZE - 1))
         .map(|(position, &pixel)| {
-            2_usize.pow(((TILE_SIZE - 1) - position.x) as u32) * (pixel as EdgeChecksum)
+            2_usize.pow(((TILE_SIZE - 1) - position.x()) as u32) * (pixel as EdgeChecksum)
         })
         .sum::<EdgeChecksum>();
 
     let left_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.x == 0)
+        .filter(|(&position, _)| position.x() == 0)
         .map(|(position, &pixel)| {
-            2_usize.pow(((TILE_SIZE - 1) - position.y) as u32) * (pixel as EdgeChecksum)
+            2_usize.pow(((TILE_SIZE - 1) - position.y()) as u32) * (pixel as EdgeChecksum)
         })
         .sum::<EdgeChecksum>();
 
@@ -270,30 +269,30 @@ fn edges_checksums(pixels: &HashMap<Position, Pixel>) -> [EdgeChecksum; 4] {
 fn flipped_edges_checksums(pixels: &HashMap<Position, Pixel>) -> [EdgeChecksum; 4] {
     let upper_flipped_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.y == 0)
+        .filter(|(&position, _)| position.y() == 0)
         .map(|(position, &pixel)| {
-            2_usize.pow(((TILE_SIZE - 1) - position.x) as u32) * (pixel as EdgeChecksum)
+            2_usize.pow(((TILE_SIZE - 1) - position.x()) as u32) * (pixel as EdgeChecksum)
         })
         .sum::<EdgeChecksum>();
 
     let right_flipped_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.x == (TILE_SIZE - 1))
+        .filter(|(&position, _)| position.x() == (TILE_SIZE - 1))
         .map(|(position, &pixel)| {
-            2_usize.pow(((TILE_SIZE - 1) - position.y) as u32) * (pixel as EdgeChecksum)
+            2_usize.pow(((TILE_SIZE - 1) - position.y()) as u32) * (pixel as EdgeChecksum)
         })
         .sum::<EdgeChecksum>();
 
     let lower_flipped_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.y == (TILE_SIZE - 1))
-        .map(|(position, &pixel)| (2_usize.pow(position.x as u32) * (pixel as EdgeChecksum)))
+        .filter(|(&position, _)| position.y() == (TILE_SIZE - 1))
+        .map(|(position, &pixel)| 2_usize.pow(position.x() as u32) * (pixel as EdgeChecksum))
         .sum::<EdgeChecksum>();
 
     let left_flipped_checksum = pixels
         .iter()
-        .filter(|(&position, _)| position.x == 0)
-        .map(|(position, &pixel)| (2_usize.pow(position.y as u32) * (pixel as EdgeChecksum)))
+        .filter(|(&position, _)| position.x() == 0)
+        .map(|(position, &pixel)| 2_usize.pow(position.y() as u32) * (pixel as EdgeChecksum))
         .sum::<EdgeChecksum>();
 
     [
@@ -304,27 +303,27 @@ fn flipped_edges_checksums(pixels: &HashMap<Position, Pixel>) -> [EdgeChecksum;
     ]
 }
 
-fn parse_tiles(input: &str) -> HashMap<TileId, Tile> {
+fn parse_tiles(source: &Source, input: &str) -> Result<Tiles, ParseError> {
     input
         .split("\n\n")
         .map(|tile_str| {
-            let tile = parse_tile(tile_str);
-            (tile.id, tile)
+            let tile = parse_tile(source, tile_str)?;
+            Ok((tile.id, tile))
         })
         .collect()
 }
 
-fn parse_edges<'a>(tiles: impl Iterator<Item = &'a Tile>) -> HashMap<EdgeChecksum, Vec<TileId>> {
-    let mut edges: HashMap<EdgeChecksum, Vec<TileId>> = HashMap::new();
+fn parse_edges<'a>(tiles: impl Iterator<Item = &'a Tile>) -> Edges {
+    let mut edges: Edges = HashMap::new();
 
     for tile in tiles {
         for edge in tile.edges_checksums.iter() {
-            let edge_tiles = edges.entry(*edge).or_insert_with(Vec::new);
+            let edge_tiles = edges.entry(*edge).or_default();
             edge_tiles.push(tile.id);
         }
 
         for edge in tile.flipped_edges_checksums.iter() {
-            let edge_tiles = edges.entry(*edge).or_insert_with(Vec::new);
+            let edge_tiles = edges.entry(*edge).or_default();
             edge_tiles.push(tile.id);
         }
     }
@@ -332,16 +331,19 @@ fn parse_edges<'a>(tiles: impl Iterator<Item = &'a Tile>) -> HashMap<EdgeChecksu
     edges
 }
 
+/// Parses the blank-line separated camera tiles and indexes them by their edges.
 #[aoc_generator(day20)]
-fn parse_input(input: &str) -> (HashMap<TileId, Tile>, HashMap<EdgeChecksum, Vec<TileId>>) {
-    let tiles = parse_tiles(input);
+pub fn parse(input: &str) -> Result<(Tiles, Edges), ParseError> {
+    let source = Source::new(20, input);
+    let tiles = parse_tiles(&source, input)?;
     let edges = parse_edges(tiles.values());
 
-    (tiles, edges)
+    Ok((tiles, edges))
 }
 
+/// Multiplies the IDs of the four corner tiles.
 #[aoc(day20, part1)]
-fn part1((_tiles, edges): &(HashMap<TileId, Tile>, HashMap<EdgeChecksum, Vec<TileId>>)) -> u64 {
+pub fn part1((_tiles, edges): &(Tiles, Edges)) -> u64 {
     let edge_tiles: Vec<_> = edges
         .iter()
         .filter(|(_, tile_ids)| tile_ids.len() == 1)
@@ -365,9 +367,8 @@ fn part1((_tiles, edges): &(HashMap<TileId, Tile>, HashMap<EdgeChecksum, Vec<Til
     corner_tiles.iter().product::<u64>()
 }
 
-fn place_image_pieces(
-    (tiles, edges): &(HashMap<TileId, Tile>, HashMap<EdgeChecksum, Vec<TileId>>),
-) -> HashMap<Position, Image> {
+/// Arranges the tiles, returning the correctly oriented image of each by its grid position.
+pub fn place_image_pieces((tiles, edges): &(Tiles, Edges)) -> HashMap<Position, Image> {
     use EdgeIndex::*;
 
     let first_tile = tiles.values().next().unwrap();
@@ -423,18 +424,19 @@ fn place_image_pieces(
     image_pieces
 }
 
-fn assemble_image(image_pieces: HashMap<Position, Image>) -> Image {
+/// Joins the arranged tile images into the full picture.
+pub fn assemble_image(image_pieces: HashMap<Position, Image>) -> Image {
     let mut image = Image(HashMap::new());
 
     for (large_position, tile_image) in image_pieces.iter() {
         for x in 0..CROPPED_TILE_SIZE {
             for y in 0..CROPPED_TILE_SIZE {
-                let pixel = tile_image.0.get(&(x as i32, y as i32).into()).unwrap();
+                let pixel = tile_image.0.get(&(x, y).into()).unwrap();
 
                 image.0.insert(
                     (
-                        large_position.x * CROPPED_TILE_SIZE + (x - 1) as i32,
-                        large_position.y * CROPPED_TILE_SIZE + (y - 1) as i32,
+                        large_position.x() * CROPPED_TILE_SIZE + (x - 1),
+                        large_position.y() * CROPPED_TILE_SIZE + (y - 1),
                     )
                         .into(),
                     *pixel,
@@ -454,36 +456,36 @@ fn monster_pixels_positions() -> Vec<Position> {
     MONSTER_PATTERN
         .lines()
         .enumerate()
-        .map(move |(y, line)| {
+        .flat_map(move |(y, line)| {
             line.chars()
                 .enumerate()
                 .filter(move |(_, c)| *c == '#')
                 .map(move |(x, _)| (x as i32, y as i32).into())
         })
-        .flatten()
         .collect()
 }
 
-fn count_monsters(image: &mut Image, monster_pixels: &[Position]) -> usize {
-    let max_x = image.0.keys().map(|position| position.x).max().unwrap();
-    let max_y = image.0.keys().map(|position| position.y).max().unwrap();
+/// Turns the image until sea monsters show, returning the top-left corners of their patterns.
+fn find_monsters(image: &mut Image, monster_pixels: &[Position]) -> Vec<Position> {
+    let max_x = image.0.keys().map(|position| position.x()).max().unwrap();
+    let max_y = image.0.keys().map(|position| position.y()).max().unwrap();
 
     let monster_max_x = monster_pixels
         .iter()
-        .map(|position| position.x)
+        .map(|position| position.x())
         .max()
         .unwrap();
     let monster_max_y = monster_pixels
         .iter()
-        .map(|position| position.y)
+        .map(|position| position.y())
         .max()
         .unwrap();
 
-    let mut monsters_count = 0;
+    let mut monsters = Vec::new();
 
     for attempt in 0..8 {
-        for y in 0..=max_y - monster_max_y + 1 {
-            'next_pixel: for x in 0..=max_x - monster_max_x + 1 {
+        for y in 0..=max_y - monster_max_y {
+            'next_pixel: for x in 0..=max_x - monster_max_x {
                 let current_position: Position = (x, y).into();
 
                 for monster_position in monster_pixels.iter() {
@@ -496,11 +498,11 @@ fn count_monsters(image: &mut Image, monster_pixels: &[Position]) -> usize {
                     }
                 }
 
-                monsters_count += 1;
+                monsters.push(current_position);
             }
         }
 
-        if monsters_count > 0 {
+        if !monsters.is_empty() {
             break;
         } else if attempt == 3 {
             *image = image.flip();
@@ -509,140 +511,88 @@ fn count_monsters(image: &mut Image, monster_pixels: &[Position]) -> usize {
         }
     }
 
-    monsters_count
+    monsters
 }
 
-#[aoc(day20, part2)]
-fn part2(tiles_and_edges: &(HashMap<TileId, Tile>, HashMap<EdgeChecksum, Vec<TileId>>)) -> usize {
-    let pieces = place_image_pieces(tiles_and_edges);
+/// Turns the image until sea monsters show, returning their pixels. Overlapping monsters share
+/// theirs.
+fn monsters_in(image: &mut Image) -> HashSet<Position> {
+    let monster_pixels = monster_pixels_positions();
 
-    let mut image = assemble_image(pieces);
+    find_monsters(image, &monster_pixels)
+        .into_iter()
+        .flat_map(|monster| monster_pixels.iter().map(move |&pixel| monster + pixel))
+        .collect()
+}
 
-    let monster_pixels = monster_pixels_positions();
-    let monsters_count = count_monsters(&mut image, &monster_pixels);
+/// The `#` pixels of the image that are not part of any sea monster.
+fn roughness(image: &Image, monsters: &HashSet<Position>) -> usize {
+    image
+        .0
+        .iter()
+        .filter(|&(position, &pixel)| pixel && !monsters.contains(position))
+        .count()
+}
+
+/// The assembled image, turned so that the sea monsters show, and the pixels of the monsters.
+pub fn sea_monsters(tiles_and_edges: &(Tiles, Edges)) -> (Image, HashSet<Position>) {
+    let mut image = assemble_image(place_image_pieces(tiles_and_edges));
+    let pixels = monsters_in(&mut image);
 
-    image.0.values().filter(|&value| *value).count() - monsters_count * monster_pixels.len()
+    (image, pixels)
+}
+
+/// Counts the `#` pixels that are not part of any sea monster.
+#[aoc(day20, part2)]
+pub fn part2(tiles_and_edges: &(Tiles, Edges)) -> usize {
+    let (image, monsters) = sea_monsters(tiles_and_edges);
+
+    roughness(&image, &monsters)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"Tile 2311:
-..##.#..#.
-##..#.....
-#...##..#.
-####.#...#
-##.##.###.
-##...#.###
-.#.#.#..##
-..#....#..
-###...#.#.
-..###..###
-
-Tile 1951:
-#.##...##.
-#.####...#
-.....#..##
-#...######
-.##.#....#
-.###.#####
-###.##.##.
-.###....#.
-..#.#..#.#
-#...##.#..
-
-Tile 1171:
-####...##.
-#..##.#..#
-##.#..#.#.
-.###.####.
-..###.####
-.##....##.
-.#...####.
-#.##.####.
-####..#...
-.....##...
-
-Tile 1427:
-###.##.#..
-.#..#.##..
-.#.##.#..#
-#.#.#.##.#
-....#...##
-...##..##.
-...#.#####
-.#.####.#.
-..#..###.#
-..##.#..#.
-
-Tile 1489:
-##.#.#....
-..##...#..
-.##..##...
-..#...#...
-#####...#.
-#..#.#.#.#
-...#.#.#..
-##.#...##.
-..##.##.##
-###.##.#..
-
-Tile 2473:
-#....####.
-#..#.##...
-#.##..#...
-######.#.#
-.#...#.#.#
-.#########
-.###.#..#.
-########.#
-##...##.#.
-..###.#.#.
-
-Tile 2971:
-..#.#....#
-#...###...
-#.#.###...
-##.##..#..
-.#####..##
-.#..####.#
-#..#.#..#.
-..####.###
-..#.#.###.
-...#.#.#.#
-
-Tile 2729:
-...#.#.#.#
-####.#....
-..#.#.....
-....#..#.#
-.##..##.#.
-.#.####...
-####.#.#..
-##.####...
-##..#.##..
-#.##...##.
-
-Tile 3079:
-#.#.#####.
-.#..######
-..#.......
-######....
-####.#..#.
-.#...#.##.
-#.#####.##
-..#.###...
-..#.......
-..#.###...";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 20_899_048_083_289);
+        assert_eq!(
+            part1(&parse(example(20, "example")).unwrap()),
+            20_899_048_083_289
+        );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_INPUT)), 273);
+        assert_eq!(part2(&parse(example(20, "example")).unwrap()), 273);
+
+        let (image, monsters) = sea_monsters(&parse(example(20, "example")).unwrap());
+
+        assert_eq!(monsters.len(), 2 * 15);
+        assert!(monsters.iter().all(|pixel| image.0[pixel]));
+        assert_eq!(roughness(&image, &monsters), 273);
+    }
+
+    #[test]
+    fn overlapping_monsters() {
+        // Two monsters five pixels apart share three pixels of their backs, leaving 27 pixels.
+        let monster_pixels = monster_pixels_positions();
+        let offset: Position = (5, 0).into();
+        let pixels = monster_pixels
+            .iter()
+            .flat_map(|&pixel| [pixel, pixel + offset])
+            .collect::<HashSet<_>>();
+        let mut image = Image(
+            (0..25)
+                .flat_map(|x| (0..3).map(move |y| (x, y).into()))
+                .map(|position| (position, pixels.contains(&position)))
+                .collect(),
+        );
+
+        let monsters = monsters_in(&mut image);
+
+        assert_eq!(monsters.len(), 27);
+        assert_eq!(roughness(&image, &monsters), 0);
     }
 }
@@ -1,50 +1,66 @@
+//! Day 21: Allergen Assessment.
+
+use crate::parsing::{ParseError, Source};
+use crate::trace::{Event, NoTrace, Tracer};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
-type Ingredient = String;
-type Allergen = String;
+pub type Ingredient = String;
+pub type Allergen = String;
 
-struct Food {
-    ingredients: HashSet<Ingredient>,
-    allergens: HashSet<Allergen>,
+/// A food, with its ingredients and the allergens it is known to contain.
+#[derive(Serialize, Deserialize)]
+pub struct Food {
+    pub ingredients: HashSet<Ingredient>,
+    pub allergens: HashSet<Allergen>,
 }
 
-fn parse_food(food_str: &str) -> Food {
+fn parse_food(source: &Source, food_str: &str) -> Result<Food, ParseError> {
     let mut split = food_str.split(" (contains ");
 
-    let ingredients = split
-        .next()
-        .unwrap()
+    let ingredients = source
+        .next(&mut split, food_str, "ingredients")?
         .split_ascii_whitespace()
         .map(|ingredient| ingredient.to_string())
         .collect();
 
-    let allergens = split
-        .next()
-        .unwrap()
-        .trim_end_matches(')')
-        .split(", ")
-        .map(|allergen| allergen.to_string())
-        .collect();
+    let allergens_str = source.next(&mut split, food_str, "\" (contains \"")?;
+    let allergens = match allergens_str.strip_suffix(')') {
+        Some(allergens_str) => allergens_str
+            .split(", ")
+            .map(|allergen| allergen.to_string())
+            .collect(),
+        None => return Err(source.error(&allergens_str[allergens_str.len()..], "expected ')'")),
+    };
 
-    Food {
+    Ok(Food {
         ingredients,
         allergens,
-    }
+    })
 }
 
+/// Parses the food list, one `<ingredients> (contains <allergens>)` per line.
 #[aoc_generator(day21)]
-fn parse_input(input: &str) -> Vec<Food> {
-    input.lines().map(|line| parse_food(line)).collect()
+pub fn parse(input: &str) -> Result<Vec<Food>, ParseError> {
+    let source = Source::new(21, input);
+    input
+        .lines()
+        .map(|line| parse_food(&source, line))
+        .collect()
 }
 
-fn compile_dictionary_draft(shopping_list: &[Food]) -> HashMap<&str, HashSet<&str>> {
+/// Maps every allergen to the ingredients of all the foods known to contain it, tracing these
+/// candidates.
+fn compile_dictionary_draft<'a>(
+    shopping_list: &'a [Food],
+    tracer: &mut impl Tracer,
+) -> HashMap<&'a str, HashSet<&'a str>> {
     let mut allergens_to_possible_ingredients: HashMap<&str, HashSet<&str>> = HashMap::new();
 
     let all_allergens: HashSet<_> = shopping_list
         .iter()
-        .map(|food| &food.allergens)
-        .flatten()
+        .flat_map(|food| &food.allergens)
         .collect();
 
     for allergen in all_allergens {
@@ -76,12 +92,34 @@ fn compile_dictionary_draft(shopping_list: &[Food]) -> HashMap<&str, HashSet<&st
         }
     }
 
+    if tracer.enabled() {
+        let mut allergens = allergens_to_possible_ingredients.iter().collect::<Vec<_>>();
+        allergens.sort_unstable_by_key(|&(allergen, _)| *allergen);
+
+        for (&allergen, possible_ingredients) in allergens {
+            let mut ingredients = possible_ingredients.iter().copied().collect::<Vec<_>>();
+            ingredients.sort_unstable();
+
+            tracer.record(
+                Event::new(21, "candidates")
+                    .with("allergen", allergen)
+                    .with("ingredients", ingredients),
+            );
+        }
+    }
+
     allergens_to_possible_ingredients
 }
 
+/// Counts the appearances of ingredients that cannot contain any allergen.
 #[aoc(day21, part1)]
-fn part1(shopping_list: &[Food]) -> usize {
-    let allergens_to_possible_ingredients = compile_dictionary_draft(shopping_list);
+pub fn part1(shopping_list: &[Food]) -> usize {
+    part1_traced(shopping_list, &mut NoTrace)
+}
+
+/// Same as [`part1`], tracing the candidate ingredients of every allergen.
+pub fn part1_traced(shopping_list: &[Food], tracer: &mut impl Tracer) -> usize {
+    let allergens_to_possible_ingredients = compile_dictionary_draft(shopping_list, tracer);
 
     let ingredients_with_allergens: HashSet<_> = allergens_to_possible_ingredients
         .values()
@@ -91,8 +129,7 @@ fn part1(shopping_list: &[Food]) -> usize {
 
     let all_ingredients: HashSet<_> = shopping_list
         .iter()
-        .map(|food| &food.ingredients)
-        .flatten()
+        .flat_map(|food| &food.ingredients)
         .map(|ingredient| ingredient.as_str())
         .collect();
 
@@ -106,16 +143,23 @@ fn part1(shopping_list: &[Food]) -> usize {
     for ingredient in non_allergenic.iter() {
         count += shopping_list
             .iter()
-            .filter(|food| food.ingredients.contains(&ingredient.to_string()))
+            .filter(|food| food.ingredients.contains(*ingredient))
             .count();
     }
 
     count
 }
 
+/// Returns the dangerous ingredients sorted by their allergens, comma-separated.
 #[aoc(day21, part2)]
-fn part2(shopping_list: &[Food]) -> String {
-    let mut allergens_to_possible_ingredients = compile_dictionary_draft(shopping_list);
+pub fn part2(shopping_list: &[Food]) -> String {
+    part2_traced(shopping_list, &mut NoTrace)
+}
+
+/// Same as [`part2`], tracing the candidate ingredients of every allergen and the order in
+/// which they are deduced.
+pub fn part2_traced(shopping_list: &[Food], tracer: &mut impl Tracer) -> String {
+    let mut allergens_to_possible_ingredients = compile_dictionary_draft(shopping_list, tracer);
 
     let mut dictionary: Vec<(&str, &str)> = Vec::new();
 
@@ -126,6 +170,11 @@ fn part2(shopping_list: &[Food]) -> String {
             if possible_ingredients.len() == 1 {
                 ingredient = possible_ingredients.drain().next().unwrap();
                 dictionary.push((allergen, ingredient));
+                tracer.trace(|| {
+                    Event::new(21, "deduce")
+                        .with("allergen", allergen)
+                        .with("ingredient", ingredient)
+                });
 
                 allergens_to_possible_ingredients.remove(&allergen);
                 break;
@@ -157,22 +206,37 @@ fn part2(shopping_list: &[Food]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
-trh fvjkl sbzzf mxmxvkd (contains dairy)
-sqjhc fvjkl (contains soy)
-sqjhc mxmxvkd sbzzf (contains fish)";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 5);
+        assert_eq!(part1(&parse(example(21, "example")).unwrap()), 5);
     }
 
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse_input(TEST_INPUT)),
+            part2(&parse(example(21, "example")).unwrap()),
             "mxmxvkd,sqjhc,fvjkl".to_string()
         );
     }
+
+    #[test]
+    fn traced() {
+        let mut events = Vec::new();
+
+        part2_traced(&parse(example(21, "example")).unwrap(), &mut events);
+
+        assert_eq!(events[0].name, "candidates");
+        assert_eq!(events[0].get("allergen"), Some(&"dairy".into()));
+        assert_eq!(events[0].get("ingredients"), Some(&vec!["mxmxvkd"].into()));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| event.name == "deduce")
+                .map(|event| event.get("allergen").unwrap().clone())
+                .collect::<Vec<_>>(),
+            vec!["dairy".into(), "fish".into(), "soy".into()]
+        );
+    }
 }
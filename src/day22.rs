@@ -1,11 +1,16 @@
+//! Day 22: Crab Combat.
+
+use crate::parsing::{ParseError, Source};
+use crate::trace::{Event, NoTrace, Tracer};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
 
-type Card = u8;
-type Deck = VecDeque<Card>;
-type Score = usize;
+pub type Card = u8;
+/// A deck, from the top card down.
+pub type Deck = VecDeque<Card>;
+pub type Score = usize;
 
 #[derive(Eq, PartialEq)]
 enum Player {
@@ -13,6 +18,15 @@ enum Player {
     Player2,
 }
 
+impl Player {
+    fn number(&self) -> u8 {
+        match self {
+            Player::Player1 => 1,
+            Player::Player2 => 2,
+        }
+    }
+}
+
 enum RoundResult {
     Playing,
     Victory(Player),
@@ -22,24 +36,48 @@ type GameStateHash = u64;
 
 type GameStates = HashSet<GameStateHash>;
 
-fn parse_deck(deck: &str) -> Deck {
+fn parse_deck(source: &Source, deck: &str) -> Result<Deck, ParseError> {
     deck.lines()
         .skip(1)
-        .map(|line| line.parse().unwrap())
+        .map(|line| source.parse(line))
         .collect()
 }
 
+/// Parses the starting decks of both players.
 #[aoc_generator(day22)]
-fn parse_input(input: &str) -> (Deck, Deck) {
+pub fn parse(input: &str) -> Result<(Deck, Deck), ParseError> {
+    let source = Source::new(22, input);
     let mut spilt = input.split("\n\n");
 
-    (
-        parse_deck(spilt.next().unwrap()),
-        parse_deck(spilt.next().unwrap()),
-    )
+    Ok((
+        parse_deck(&source, source.next(&mut spilt, input, "a deck")?)?,
+        parse_deck(&source, source.next(&mut spilt, input, "a second deck")?)?,
+    ))
+}
+
+/// The event of a round of game `game`, won by `winner` because of `reason`.
+fn round_event(
+    game: usize,
+    round: usize,
+    (player1_card, player2_card): (Card, Card),
+    winner: &Player,
+    reason: &str,
+) -> Event {
+    Event::new(22, "round")
+        .with("game", game)
+        .with("round", round)
+        .with("player1", player1_card)
+        .with("player2", player2_card)
+        .with("winner", winner.number())
+        .with("reason", reason)
 }
 
-fn round(player1_deck: &mut Deck, player2_deck: &mut Deck) -> RoundResult {
+fn round(
+    player1_deck: &mut Deck,
+    player2_deck: &mut Deck,
+    round_number: usize,
+    tracer: &mut impl Tracer,
+) -> RoundResult {
     use Player::*;
     use RoundResult::*;
 
@@ -47,13 +85,25 @@ fn round(player1_deck: &mut Deck, player2_deck: &mut Deck) -> RoundResult {
         let player1_card = player1_deck.pop_front().unwrap();
         let player2_card = player2_deck.pop_front().unwrap();
 
-        if player1_card > player2_card {
+        let round_winner = if player1_card > player2_card {
             player1_deck.push_back(player1_card);
             player1_deck.push_back(player2_card);
+            Player1
         } else {
             player2_deck.push_back(player2_card);
             player2_deck.push_back(player1_card);
-        }
+            Player2
+        };
+
+        tracer.trace(|| {
+            round_event(
+                1,
+                round_number,
+                (player1_card, player2_card),
+                &round_winner,
+                "higher card",
+            )
+        });
 
         Playing
     } else if player1_deck.is_empty() {
@@ -73,24 +123,39 @@ fn count_score(winning_deck: &Deck) -> Score {
         .sum()
 }
 
-fn play_combat(player1_deck: &mut Deck, player2_deck: &mut Deck) -> Player {
+fn play_combat(
+    player1_deck: &mut Deck,
+    player2_deck: &mut Deck,
+    tracer: &mut impl Tracer,
+) -> Player {
     use RoundResult::*;
 
-    loop {
-        if let Victory(winner) = round(player1_deck, player2_deck) {
+    for round_number in 1.. {
+        if let Victory(winner) = round(player1_deck, player2_deck, round_number, tracer) {
             return winner;
         }
     }
+
+    unreachable!()
 }
 
+/// Returns the winning player's score in a game of Combat.
 #[aoc(day22, part1)]
-fn part1((player1_deck, player2_deck): &(Deck, Deck)) -> Score {
+pub fn part1(decks: &(Deck, Deck)) -> Score {
+    part1_traced(decks, &mut NoTrace)
+}
+
+/// Same as [`part1`], tracing the cards played and the winner of every round.
+pub fn part1_traced(
+    (player1_deck, player2_deck): &(Deck, Deck),
+    tracer: &mut impl Tracer,
+) -> Score {
     use Player::*;
 
     let mut player1_deck = player1_deck.clone();
     let mut player2_deck = player2_deck.clone();
 
-    let winner = play_combat(&mut player1_deck, &mut player2_deck);
+    let winner = play_combat(&mut player1_deck, &mut player2_deck, tracer);
 
     match winner {
         Player1 => count_score(&player1_deck),
@@ -98,56 +163,93 @@ fn part1((player1_deck, player2_deck): &(Deck, Deck)) -> Score {
     }
 }
 
+/// The games played so far, and the one being played.
+struct Games {
+    played: usize,
+    current: usize,
+}
+
 fn recursive_round(
     player1_deck: &mut Deck,
     player2_deck: &mut Deck,
     game_states: &mut GameStates,
     quick_mode: bool,
+    games: &mut Games,
+    tracer: &mut impl Tracer,
 ) -> RoundResult {
     use Player::*;
     use RoundResult::*;
 
     if !player1_deck.is_empty() && !player2_deck.is_empty() {
-        if quick_mode && player1_deck.iter().max().unwrap() > player2_deck.iter().max().unwrap() {
-            return Victory(Player1);
-        }
-
         let mut hasher = DefaultHasher::new();
 
         (&player1_deck, &player2_deck).hash(&mut hasher);
         let game_state = hasher.finish();
 
         if game_states.contains(&game_state) {
+            tracer.trace(|| {
+                Event::new(22, "repeat")
+                    .with("game", games.current)
+                    .with("round", game_states.len() + 1)
+            });
             return Victory(Player1);
         }
 
         game_states.insert(game_state);
+        let (game, round_number) = (games.current, game_states.len());
 
         let player1_card = player1_deck.pop_front().unwrap();
         let player2_card = player2_deck.pop_front().unwrap();
 
-        let round_winner = if player1_card as usize <= player1_deck.len()
+        let (round_winner, reason) = if player1_card as usize <= player1_deck.len()
             && player2_card as usize <= player2_deck.len()
         {
-            play_recursive_combat(
-                &mut player1_deck
-                    .iter()
-                    .take(player1_card as usize)
-                    .cloned()
-                    .collect(),
-                &mut player2_deck
-                    .iter()
-                    .take(player2_card as usize)
-                    .cloned()
-                    .collect(),
-                true,
-            )
+            let mut player1_subdeck: Deck = player1_deck
+                .iter()
+                .take(player1_card as usize)
+                .cloned()
+                .collect();
+            let mut player2_subdeck: Deck = player2_deck
+                .iter()
+                .take(player2_card as usize)
+                .cloned()
+                .collect();
+
+            // Player 1 can never lose their highest card, so they win any subgame they start
+            // holding it.
+            if quick_mode && player1_subdeck.iter().max() > player2_subdeck.iter().max() {
+                (Player1, "highest card")
+            } else {
+                games.played += 1;
+                games.current = games.played;
+
+                let winner = play_recursive_combat(
+                    &mut player1_subdeck,
+                    &mut player2_subdeck,
+                    quick_mode,
+                    games,
+                    tracer,
+                );
+
+                games.current = game;
+                (winner, "subgame")
+            }
         } else if player1_card > player2_card {
-            Player1
+            (Player1, "higher card")
         } else {
-            Player2
+            (Player2, "higher card")
         };
 
+        tracer.trace(|| {
+            round_event(
+                game,
+                round_number,
+                (player1_card, player2_card),
+                &round_winner,
+                reason,
+            )
+        });
+
         if round_winner == Player1 {
             player1_deck.push_back(player1_card);
             player1_deck.push_back(player2_card);
@@ -170,28 +272,48 @@ fn play_recursive_combat(
     player1_deck: &mut Deck,
     player2_deck: &mut Deck,
     quick_mode: bool,
+    games: &mut Games,
+    tracer: &mut impl Tracer,
 ) -> Player {
     use RoundResult::*;
 
     let mut game_states = HashSet::new();
 
     loop {
-        if let Victory(winner) =
-            recursive_round(player1_deck, player2_deck, &mut game_states, quick_mode)
-        {
+        if let Victory(winner) = recursive_round(
+            player1_deck,
+            player2_deck,
+            &mut game_states,
+            quick_mode,
+            games,
+            tracer,
+        ) {
             return winner;
         }
     }
 }
 
-#[aoc(day22, part2)]
-fn part2((player1_deck, player2_deck): &(Deck, Deck)) -> Score {
+fn recursive_combat_score(
+    (player1_deck, player2_deck): &(Deck, Deck),
+    quick_mode: bool,
+    tracer: &mut impl Tracer,
+) -> Score {
     use Player::*;
 
     let mut player1_deck = player1_deck.clone();
     let mut player2_deck = player2_deck.clone();
 
-    let winner = play_recursive_combat(&mut player1_deck, &mut player2_deck, false);
+    let mut games = Games {
+        played: 1,
+        current: 1,
+    };
+    let winner = play_recursive_combat(
+        &mut player1_deck,
+        &mut player2_deck,
+        quick_mode,
+        &mut games,
+        tracer,
+    );
 
     match winner {
         Player1 => count_score(&player1_deck),
@@ -199,31 +321,60 @@ fn part2((player1_deck, player2_deck): &(Deck, Deck)) -> Score {
     }
 }
 
+/// Returns the winning player's score in a game of Recursive Combat.
+#[aoc(day22, part2)]
+pub fn part2(decks: &(Deck, Deck)) -> Score {
+    part2_traced(decks, &mut NoTrace)
+}
+
+/// Same as [`part2`], tracing every round of every game, numbered in the order they start.
+pub fn part2_traced(decks: &(Deck, Deck), tracer: &mut impl Tracer) -> Score {
+    recursive_combat_score(decks, true, tracer)
+}
+
+/// Same as [`part2`], playing every subgame to the end.
+#[aoc(day22, part2, Full)]
+pub fn part2_full(decks: &(Deck, Deck)) -> Score {
+    recursive_combat_score(decks, false, &mut NoTrace)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"Player 1:
-9
-2
-6
-3
-1
-
-Player 2:
-5
-8
-4
-7
-10";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 306);
+        assert_eq!(part1(&parse(example(22, "example")).unwrap()), 306);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_INPUT)), 291)
+        assert_eq!(part2(&parse(example(22, "example")).unwrap()), 291);
+        assert_eq!(part2_full(&parse(example(22, "example")).unwrap()), 291);
+    }
+
+    #[test]
+    fn traced() {
+        let mut events = Vec::new();
+
+        assert_eq!(
+            part1_traced(&parse(example(22, "example")).unwrap(), &mut events),
+            306
+        );
+        assert_eq!(events.len(), 29);
+        assert_eq!(events[0].get("player1"), Some(&9.into()));
+        assert_eq!(events[0].get("winner"), Some(&1.into()));
+
+        let mut events = Vec::new();
+
+        assert_eq!(
+            part2_traced(
+                &parse("Player 1:\n43\n19\n\nPlayer 2:\n2\n29\n14").unwrap(),
+                &mut events
+            ),
+            105
+        );
+        assert_eq!(events.last().unwrap().name, "repeat");
     }
 }
@@ -1,10 +1,50 @@
+//! Day 23: Crab Cups.
+
+use crate::parsing::{ParseError, Source};
+use crate::progress::{Interrupted, Monitor};
 use aoc_runner_derive::{aoc, aoc_generator};
 
-type Cup = u32;
+/// A cup label.
+pub type Cup = u32;
 
+/// Parses the labels of the nine cups, clockwise from the current one.
 #[aoc_generator(day23)]
-fn parse_input(input: &str) -> Vec<Cup> {
-    input.chars().map(|c| c.to_digit(10).unwrap()).collect()
+pub fn parse(input: &str) -> Result<Vec<Cup>, ParseError> {
+    let source = Source::new(23, input);
+    let mut cups = Vec::with_capacity(9);
+    let input = input.trim_end();
+
+    for (i, c) in input.char_indices() {
+        match c.to_digit(10) {
+            Some(cup) if (1..=9).contains(&cup) && !cups.contains(&cup) => cups.push(cup),
+            _ => return Err(source.error(&input[i..i + c.len_utf8()], "expected a new cup label")),
+        }
+    }
+
+    if cups.len() != 9 {
+        return Err(source.error(&input[input.len()..], "expected 9 cups"));
+    }
+
+    Ok(cups)
+}
+
+/// The puzzle parameters, 100 moves, then ten million moves with a million cups by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    pub part1_moves: usize,
+    pub part2_moves: usize,
+    /// The number of cups in the second part, at least 10.
+    pub part2_cups: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            part1_moves: 100,
+            part2_moves: 10_000_000,
+            part2_cups: 1_000_000,
+        }
+    }
 }
 
 fn r#move(cups: &mut [Cup]) {
@@ -74,13 +114,35 @@ fn labels_after_first(next_cups: &[u32; 10]) -> String {
     result
 }
 
+/// Returns the labels of the cups after cup 1 following 100 moves.
 #[aoc(day23, part1)]
-fn part1(cups: &[Cup]) -> String {
-    labels_after_first(&move_few_cups(cups, 100))
+pub fn part1(cups: &[Cup]) -> String {
+    part1_with(cups, &Params::default())
+}
+
+/// Same as [`part1`], after the number of moves given by `params`.
+pub fn part1_with(cups: &[Cup], params: &Params) -> String {
+    labels_after_first(&move_few_cups(cups, params.part1_moves))
 }
 
+/// Multiplies the two cups after cup 1 following ten million moves with a million cups.
 #[aoc(day23, part2)]
-fn part2(cups: &[Cup]) -> u64 {
+pub fn part2(cups: &[Cup]) -> u64 {
+    part2_with(cups, &Params::default())
+}
+
+/// Same as [`part2`], with the numbers of moves and cups given by `params`.
+pub fn part2_with(cups: &[Cup], params: &Params) -> u64 {
+    part2_monitored(cups, params, &mut Monitor::new())
+        .expect("an unmonitored game is never interrupted")
+}
+
+/// Same as [`part2_with`], watched over by `monitor`, one step per move.
+pub fn part2_monitored(
+    cups: &[Cup],
+    params: &Params,
+    monitor: &mut Monitor,
+) -> Result<u64, Interrupted> {
     let mut first_next_cups = [0; 10];
 
     first_next_cups[0] = cups[0];
@@ -91,42 +153,68 @@ fn part2(cups: &[Cup]) -> u64 {
 
     first_next_cups[cups[cups.len() - 1] as usize] = (cups.len() + 1) as u32;
 
-    let mut all_next_cups: Vec<Cup> = Vec::with_capacity(1_000_000 + 1);
+    let mut all_next_cups: Vec<Cup> = Vec::with_capacity(params.part2_cups + 1);
     all_next_cups.extend(&first_next_cups);
 
-    for i in 10..1_000_000 {
+    for i in 10..params.part2_cups as Cup {
         all_next_cups.push(i + 1);
     }
 
     all_next_cups.push(cups[0]);
 
-    for _ in 0..10_000_000 {
+    for done in 0..params.part2_moves {
+        monitor.tick(done as u64, params.part2_moves as u64)?;
         r#move(&mut all_next_cups);
     }
 
+    monitor.finish();
+
     let first = all_next_cups[1];
     let second = all_next_cups[first as usize];
 
-    first as u64 * second as u64
+    Ok(first as u64 * second as u64)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"389125467";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            labels_after_first(&move_few_cups(&parse_input(TEST_INPUT), 10)),
+            labels_after_first(&move_few_cups(&parse(example(23, "example")).unwrap(), 10)),
+            "92658374"
+        );
+        assert_eq!(part1(&parse(example(23, "example")).unwrap()), "67384529");
+        assert_eq!(
+            part1_with(
+                &parse(example(23, "example")).unwrap(),
+                &Params {
+                    part1_moves: 10,
+                    ..Params::default()
+                }
+            ),
             "92658374"
         );
-        assert_eq!(part1(&parse_input(TEST_INPUT)), "67384529");
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_INPUT)), 149_245_887_792);
+        assert_eq!(
+            part2(&parse(example(23, "example")).unwrap()),
+            149_245_887_792
+        );
+        assert_eq!(
+            part2_with(
+                &parse(example(23, "example")).unwrap(),
+                &Params {
+                    part2_moves: 100,
+                    part2_cups: 20,
+                    ..Params::default()
+                }
+            ),
+            54
+        );
     }
 }
@@ -1,53 +1,19 @@
-use aoc_runner_derive::{aoc, aoc_generator};
-use std::collections::{HashMap, HashSet};
-use std::ops::{Add, AddAssign, Not};
-
-#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
-struct Vector3 {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl From<(i32, i32, i32)> for Vector3 {
-    fn from((x, y, z): (i32, i32, i32)) -> Self {
-        Vector3 { x, y, z }
-    }
-}
-
-impl Add<&Vector3> for Vector3 {
-    type Output = Vector3;
-
-    fn add(self, rhs: &Vector3) -> Self::Output {
-        (self.x + rhs.x, self.y + rhs.y, self.z + rhs.z).into()
-    }
-}
-
-impl AddAssign<&Vector3> for Vector3 {
-    fn add_assign(&mut self, rhs: &Vector3) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-        self.z += rhs.z;
-    }
-}
-
-type CubeCoordinates = Vector3;
-
-impl CubeCoordinates {
-    fn adjacent_coordinates(&self) -> Vec<CubeCoordinates> {
-        use Direction::*;
-
-        let mut result = Vec::with_capacity(6);
+//! Day 24: Lobby Layout.
 
-        for direction in &[East, Southeast, Southwest, West, Northwest, Northeast] {
-            result.push(*self + &direction.into())
-        }
+use crate::animation::{Animation, Colour, Frame};
+use crate::automaton::{Automaton, Hex, LifeLike};
+use crate::geometry::Vector3;
+use crate::parsing::{ParseError, Source};
+use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Not;
 
-        result
-    }
-}
+pub type CubeCoordinates = Vector3;
 
-enum Direction {
+/// A step to one of the six neighbouring hexagonal tiles.
+#[derive(Serialize, Deserialize)]
+pub enum Direction {
     East,
     Southeast,
     Southwest,
@@ -56,7 +22,7 @@ enum Direction {
     Northeast,
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
 enum TileColor {
     White,
     Black,
@@ -77,40 +43,12 @@ impl Not for TileColor {
 
 struct TileFloor(HashMap<CubeCoordinates, TileColor>);
 
-impl TileFloor {
-    fn count_adjacent_black(&self, coordinates: &CubeCoordinates) -> usize {
-        coordinates
-            .adjacent_coordinates()
-            .iter()
-            .filter(|&adjacent_position| self.0.get(adjacent_position) == Some(&TileColor::Black))
-            .count()
-    }
-
-    fn expand(&mut self) {
-        use TileColor::*;
-
-        let adjacent = self
-            .0
-            .iter()
-            .filter(|(_, color)| **color == Black)
-            .map(|(coordinates, _)| coordinates.adjacent_coordinates())
-            .flatten()
-            .collect::<HashSet<_>>();
-
-        for adjacent_coordinates in adjacent {
-            self.0
-                .entry(adjacent_coordinates)
-                .or_insert(TileColor::White);
-        }
-    }
-}
-
 impl From<&[Vec<Direction>]> for TileFloor {
     fn from(tiles_directions: &[Vec<Direction>]) -> Self {
         use TileColor::*;
 
         let mut floor: TileFloor = TileFloor(HashMap::new());
-        let start = (0, 0, 0).into();
+        let start = Vector3::ZERO;
 
         for tile_directions in tiles_directions {
             let coordinates = follow_directions(&start, tile_directions);
@@ -132,74 +70,68 @@ impl From<&Direction> for Vector3 {
     fn from(direction: &Direction) -> Self {
         use Direction::*;
 
-        match direction {
-            East => (1, -1, 0).into(),
-            Southeast => (0, -1, 1).into(),
-            Southwest => (-1, 0, 1).into(),
-            West => (-1, 1, 0).into(),
-            Northwest => (0, 1, -1).into(),
-            Northeast => (1, 0, -1).into(),
-        }
+        let index = match direction {
+            East => 0,
+            Southeast => 1,
+            Southwest => 2,
+            West => 3,
+            Northwest => 4,
+            Northeast => 5,
+        };
+
+        Vector3::HEX_DIRECTIONS[index]
     }
 }
 
-fn parse_tile_directions(tile_directions_str: &str) -> Vec<Direction> {
+fn parse_tile_directions(
+    source: &Source,
+    tile_directions_str: &str,
+) -> Result<Vec<Direction>, ParseError> {
     use Direction::*;
 
-    let mut i = 0;
+    let mut rest = tile_directions_str;
     let mut directions = Vec::new();
 
-    while i < tile_directions_str.len() {
-        match &tile_directions_str[i..=i] {
-            "e" => {
-                directions.push(East);
-                i += 1;
+    while !rest.is_empty() {
+        let (direction, len) = match rest.as_bytes() {
+            [b'e', ..] => (East, 1),
+            [b'w', ..] => (West, 1),
+            [b's', b'e', ..] => (Southeast, 2),
+            [b's', b'w', ..] => (Southwest, 2),
+            [b'n', b'w', ..] => (Northwest, 2),
+            [b'n', b'e', ..] => (Northeast, 2),
+            _ => {
+                let len = rest.chars().next().unwrap().len_utf8();
+                return Err(source.error(&rest[..len], "expected a direction"));
             }
-            "w" => {
-                directions.push(West);
-                i += 1;
-            }
-            _ => match &tile_directions_str[i..=i + 1] {
-                "se" => {
-                    directions.push(Southeast);
-                    i += 2;
-                }
-                "sw" => {
-                    directions.push(Southwest);
-                    i += 2;
-                }
-                "nw" => {
-                    directions.push(Northwest);
-                    i += 2;
-                }
-                "ne" => {
-                    directions.push(Northeast);
-                    i += 2;
-                }
-                _ => unreachable!(),
-            },
-        }
+        };
+
+        directions.push(direction);
+        rest = &rest[len..];
     }
 
-    directions
+    Ok(directions)
 }
 
+/// Parses the tile paths from the reference tile, one per line.
 #[aoc_generator(day24)]
-fn parse_input(input: &str) -> Vec<Vec<Direction>> {
+pub fn parse(input: &str) -> Result<Vec<Vec<Direction>>, ParseError> {
+    let source = Source::new(24, input);
     input
         .lines()
-        .map(|line| parse_tile_directions(line))
+        .map(|line| parse_tile_directions(&source, line))
         .collect()
 }
 
 fn follow_directions(start: &CubeCoordinates, directions: &[Direction]) -> CubeCoordinates {
     directions
         .iter()
-        .fold(*start, |acc, direction| acc + &direction.into())
+        .fold(*start, |acc, direction| acc + direction.into())
 }
 
+/// Counts the black tiles after flipping the tile at the end of every path.
 #[aoc(day24, part1)]
-fn part1(tiles_directions: &[Vec<Direction>]) -> usize {
+pub fn part1(tiles_directions: &[Vec<Direction>]) -> usize {
     let floor: TileFloor = tiles_directions.into();
 
     floor
@@ -209,104 +141,136 @@ fn part1(tiles_directions: &[Vec<Direction>]) -> usize {
         .count()
 }
 
-fn black_flip_rule(floor: &TileFloor, coordinates: &CubeCoordinates) -> bool {
-    let adjacent_black = floor.count_adjacent_black(coordinates);
-    adjacent_black == 0 || adjacent_black > 2
+/// The puzzle parameters, 100 days by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    pub days: usize,
 }
 
-fn white_flip_rule(floor: &TileFloor, coordinates: &CubeCoordinates) -> bool {
-    floor.count_adjacent_black(coordinates) == 2
+impl Default for Params {
+    fn default() -> Self {
+        Params { days: 100 }
+    }
 }
 
-fn update_floor(floor: &mut TileFloor) {
-    use TileColor::*;
+type Exhibit = Automaton<Hex, TileColor, LifeLike<TileColor>>;
 
-    floor.expand();
+fn exhibit(floor: TileFloor) -> Exhibit {
+    let rule = LifeLike {
+        live: TileColor::Black,
+        dead: TileColor::White,
+        birth: &[2],
+        survival: &[1, 2],
+    };
 
-    let mut to_flip = Vec::new();
+    Automaton::unbounded(floor.0, Hex, rule, TileColor::White)
+}
 
-    for (coordinates, color) in floor.0.iter() {
-        if (*color == Black && black_flip_rule(&(*floor), coordinates))
-            || (*color == White && white_flip_rule(&(*floor), coordinates))
-        {
-            to_flip.push(*coordinates)
-        }
-    }
+fn count_black_after(exhibit: &mut Exhibit, days: usize) -> usize {
+    exhibit.run(days);
+    exhibit.count(TileColor::Black)
+}
 
-    for coordinates in to_flip.iter() {
-        if let Some(color) = floor.0.get_mut(coordinates) {
-            *color = !(*color);
-        } else {
-            floor.0.insert(*coordinates, Black);
-        }
+/// The living art exhibit day after day, the hexagonal tiles of a row interleaved with those of
+/// the rows above and below.
+pub struct Exhibition {
+    exhibit: Exhibit,
+    days: usize,
+}
+
+impl Exhibition {
+    /// The black tiles of the current day.
+    pub fn black_tiles(&self) -> impl Iterator<Item = CubeCoordinates> + '_ {
+        self.exhibit.cells().map(|(tile, _)| tile)
     }
 }
 
-fn count_black_after(floor: &mut TileFloor, days: usize) -> usize {
-    for _ in 1..=days {
-        update_floor(floor);
+impl Animation for Exhibition {
+    fn frame(&self) -> Frame {
+        Frame::new(format!(
+            "day 24, day {}: {} black tiles",
+            self.exhibit.generation(),
+            self.exhibit.count(TileColor::Black)
+        ))
+        .grid(
+            self.exhibit
+                .cells()
+                .map(|(tile, _)| ((2 * tile.x() + tile.z(), tile.z()), ('#', Colour::Yellow))),
+        )
     }
 
-    floor
-        .0
-        .values()
-        .filter(|&color| *color == TileColor::Black)
-        .count()
+    fn step(&mut self) -> bool {
+        self.exhibit.generation() < self.days && self.exhibit.step()
+    }
 }
 
+/// The days of [`part2_with`].
+pub fn part2_animation(tiles_directions: &[Vec<Direction>], params: &Params) -> Exhibition {
+    Exhibition {
+        exhibit: exhibit(tiles_directions.into()),
+        days: params.days,
+    }
+}
+
+/// Counts the black tiles after 100 days of the living art exhibit.
 #[aoc(day24, part2)]
-fn part2(tiles_directions: &[Vec<Direction>]) -> usize {
-    let mut floor: TileFloor = tiles_directions.into();
-    count_black_after(&mut floor, 100)
+pub fn part2(tiles_directions: &[Vec<Direction>]) -> usize {
+    part2_with(tiles_directions, &Params::default())
+}
+
+/// Same as [`part2`], after the number of days given by `params`.
+pub fn part2_with(tiles_directions: &[Vec<Direction>], params: &Params) -> usize {
+    count_black_after(&mut exhibit(tiles_directions.into()), params.days)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
-    const TEST_INPUT: &str = r"sesenwnenenewseeswwswswwnenewsewsw
-neeenesenwnwwswnenewnwwsewnenwseswesw
-seswneswswsenwwnwse
-nwnwneseeswswnenewneswwnewseswneseene
-swweswneswnenwsewnwneneseenw
-eesenwseswswnenwswnwnwsewwnwsene
-sewnenenenesenwsewnenwwwse
-wenwwweseeeweswwwnwwe
-wsweesenenewnwwnwsenewsenwwsesesenwne
-neeswseenwwswnwswswnw
-nenwswwsewswnenenewsenwsenwnesesenew
-enewnwewneswsewnwswenweswnenwsenwsw
-sweneswneswneneenwnewenewwneswswnese
-swwesenesewenwneswnwwneseswwne
-enesenwswwswneneswsenwnewswseenwsese
-wnwnesenesenenwwnenwsewesewsesesew
-nenewswnwewswnenesenwnesewesw
-eneswnwswnwsenenwnwnwwseeswneewsenese
-neswnwewnwnwseenwseesewsenwsweewe
-wseweeenwnesenwwwswnew";
+    fn directions(input: &str) -> Vec<Direction> {
+        parse_tile_directions(&Source::new(24, input), input).unwrap()
+    }
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            follow_directions(&((0, 0, 0).into()), &parse_tile_directions("esenee")),
+            follow_directions(&((0, 0, 0).into()), &directions("esenee")),
             (3, -3, 0).into()
         );
         assert_eq!(
-            follow_directions(&((0, 0, 0).into()), &parse_tile_directions("esew")),
+            follow_directions(&((0, 0, 0).into()), &directions("esew")),
             (0, -1, 1).into()
         );
         assert_eq!(
-            follow_directions(&((0, 0, 0).into()), &parse_tile_directions("nwwswee")),
+            follow_directions(&((0, 0, 0).into()), &directions("nwwswee")),
             (0, 0, 0).into()
         );
 
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 10);
+        assert_eq!(part1(&parse(example(24, "example")).unwrap()), 10);
+    }
+
+    #[test]
+    fn animation() {
+        let params = Params { days: 1 };
+        let mut exhibition = part2_animation(&parse("e\nee\neee").unwrap(), &params);
+
+        assert_eq!(
+            exhibition.frame().to_plain(),
+            "day 24, day 0: 3 black tiles\n# # #"
+        );
+        assert!(exhibition.step());
+        assert_eq!(
+            exhibition.frame().to_plain(),
+            "day 24, day 1: 7 black tiles\n # #\n# # #\n # #"
+        );
+        assert!(!exhibition.step());
     }
 
     #[test]
     fn part2_example() {
-        let tiles_directions: &[_] = &parse_input(TEST_INPUT);
-        let mut floor: TileFloor = tiles_directions.into();
+        let tiles_directions: &[_] = &parse(example(24, "example")).unwrap();
+        let mut floor = exhibit(tiles_directions.into());
 
         assert_eq!(count_black_after(&mut floor, 1), 15);
         assert_eq!(count_black_after(&mut floor, 1), 12);
@@ -329,6 +293,13 @@ wseweeenwnesenwwwswnew";
         assert_eq!(count_black_after(&mut floor, 10), 1_844);
         assert_eq!(count_black_after(&mut floor, 10), 2_208);
 
-        assert_eq!(part2(&parse_input(TEST_INPUT)), 2_208);
+        assert_eq!(part2(&parse(example(24, "example")).unwrap()), 2_208);
+        assert_eq!(
+            part2_with(
+                &parse(example(24, "example")).unwrap(),
+                &Params { days: 20 }
+            ),
+            132
+        );
     }
 }
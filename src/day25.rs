@@ -1,42 +1,148 @@
+//! Day 25: Combo Breaker.
+
+use crate::parsing::{missing, parse_lines, ParseError, Source, StreamError};
+use crate::progress::{Interrupted, Monitor};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::error;
+use std::fmt;
+use std::io::BufRead;
+
+pub type PublicKey = u64;
+pub type EncryptionKey = u64;
+pub type LoopSize = u32;
+
+const DIVISOR: u64 = 20_201_227;
+const MULTIPLIER: u64 = 7;
+
+/// A public key no loop size transforms the subject number 7 into.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UnreachableKey(pub PublicKey);
+
+impl fmt::Display for UnreachableKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no loop size produces the public key {}", self.0)
+    }
+}
+
+impl error::Error for UnreachableKey {}
 
-type PublicKey = u64;
-type EncryptionKey = u64;
-type LoopSize = u32;
+/// A public key, from 1 to the divisor excluded as the others are never reached.
+fn public_key(source: &Source, line: &str) -> Result<PublicKey, ParseError> {
+    let public_key = source.parse(line)?;
+
+    if (1..DIVISOR).contains(&public_key) {
+        Ok(public_key)
+    } else {
+        Err(source.error(
+            line,
+            format!("expected a public key from 1 to {}", DIVISOR - 1),
+        ))
+    }
+}
 
+/// Parses the card and the door public keys.
 #[aoc_generator(day25)]
-fn parse_input(input: &str) -> Vec<PublicKey> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+pub fn parse(input: &str) -> Result<Vec<PublicKey>, ParseError> {
+    let source = Source::new(25, input);
+    let mut lines = input.lines();
+
+    let public_keys = vec![
+        public_key(
+            &source,
+            source.next(&mut lines, input, "a card public key")?,
+        )?,
+        public_key(
+            &source,
+            source.next(&mut lines, input, "a door public key")?,
+        )?,
+    ];
+
+    if let Some(line) = lines.next() {
+        return Err(source.error(line, "expected only two public keys"));
+    }
+
+    Ok(public_keys)
 }
 
-fn decrypt(public_key: PublicKey, another_public_key: PublicKey) -> (EncryptionKey, LoopSize) {
-    const DIVISOR: u64 = 20_201_227;
-    const MULTIPLIER: u64 = 7;
+/// Finds the loop size producing `public_key` and applies it to `another_public_key`.
+pub fn decrypt(
+    public_key: PublicKey,
+    another_public_key: PublicKey,
+) -> Result<(EncryptionKey, LoopSize), UnreachableKey> {
+    decrypt_monitored(public_key, another_public_key, &mut Monitor::new())
+        .expect("an unmonitored search is never interrupted")
+}
 
+/// Same as [`decrypt`], one step per loop. The progress is the fraction of all the possible
+/// loop sizes tried: the powers of 7 repeat after `DIVISOR - 1` loops at the latest, so the
+/// search gives up there.
+pub fn decrypt_monitored(
+    public_key: PublicKey,
+    another_public_key: PublicKey,
+    monitor: &mut Monitor,
+) -> Result<Result<(EncryptionKey, LoopSize), UnreachableKey>, Interrupted> {
+    let another_public_key = another_public_key % DIVISOR;
     let mut value = 1;
     let mut encryption_key = 1;
     let mut loop_size = 0;
 
     while value != public_key {
+        if u64::from(loop_size) == DIVISOR - 1 || !(1..DIVISOR).contains(&public_key) {
+            monitor.finish();
+            return Ok(Err(UnreachableKey(public_key)));
+        }
+
+        monitor.tick(loop_size as u64, DIVISOR - 1)?;
         value = value * MULTIPLIER % DIVISOR;
         encryption_key = encryption_key * another_public_key % DIVISOR;
         loop_size += 1;
     }
 
-    (encryption_key, loop_size)
+    monitor.finish();
+    Ok(Ok((encryption_key, loop_size)))
 }
 
+/// Returns the encryption key the card and the door agree on.
 #[aoc(day25, part1)]
-fn part1(public_keys: &[PublicKey]) -> EncryptionKey {
+pub fn part1(public_keys: &[PublicKey]) -> Result<EncryptionKey, UnreachableKey> {
     let card_pk = public_keys[0];
     let door_pk = public_keys[1];
 
-    decrypt(card_pk, door_pk).0
+    decrypt(card_pk, door_pk).map(|(key, _)| key)
+}
+
+/// Same as [`part1`], watched over by `monitor`.
+pub fn part1_monitored(
+    public_keys: &[PublicKey],
+    monitor: &mut Monitor,
+) -> Result<Result<EncryptionKey, UnreachableKey>, Interrupted> {
+    decrypt_monitored(public_keys[0], public_keys[1], monitor)
+        .map(|decrypted| decrypted.map(|(key, _)| key))
+}
+
+/// Same as [`part1`], reading only the first two lines of the input.
+pub fn part1_stream(reader: impl BufRead) -> Result<Option<EncryptionKey>, StreamError> {
+    let mut lines = parse_lines(25, reader, public_key);
+    let mut public_keys = [0; 2];
+
+    for (i, expected) in ["a card public key", "a door public key"]
+        .iter()
+        .enumerate()
+    {
+        public_keys[i] = lines
+            .next()
+            .unwrap_or_else(|| Err(missing(25, i + 1, expected)))?;
+    }
+
+    Ok(decrypt(public_keys[0], public_keys[1])
+        .ok()
+        .map(|(key, _)| key))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::progress::Budget;
 
     static TEST_CARD_PUBLIC_KEY: PublicKey = 5_764_801;
     static TEST_DOOR_PUBLIC_KEY: EncryptionKey = 17_807_724;
@@ -46,15 +152,51 @@ mod tests {
     fn part1_example() {
         assert_eq!(
             decrypt(TEST_CARD_PUBLIC_KEY, TEST_DOOR_PUBLIC_KEY),
-            (TEST_ENCRYPTION_KEY, 8)
+            Ok((TEST_ENCRYPTION_KEY, 8))
         );
         assert_eq!(
             decrypt(TEST_DOOR_PUBLIC_KEY, TEST_CARD_PUBLIC_KEY),
-            (TEST_ENCRYPTION_KEY, 11)
+            Ok((TEST_ENCRYPTION_KEY, 11))
         );
         assert_eq!(
             part1(&[TEST_CARD_PUBLIC_KEY, TEST_DOOR_PUBLIC_KEY]),
-            TEST_ENCRYPTION_KEY
+            Ok(TEST_ENCRYPTION_KEY)
+        );
+        assert_eq!(
+            part1_stream("5764801\n17807724\n".as_bytes()).unwrap(),
+            Some(TEST_ENCRYPTION_KEY)
+        );
+        assert!(part1_stream("5764801".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn unreachable_public_key() {
+        assert!(parse("20201227\n5").is_err());
+        assert!(parse("5\n0").is_err());
+        assert!(part1_stream("20201227\n5\n".as_bytes()).is_err());
+        assert_eq!(decrypt(1, 5), Ok((1, 0)));
+        assert_eq!(decrypt(0, 5), Err(UnreachableKey(0)));
+        assert_eq!(
+            part1(&[20_201_227, TEST_DOOR_PUBLIC_KEY]),
+            Err(UnreachableKey(20_201_227))
+        );
+        assert_eq!(
+            crate::solver::Error::from(UnreachableKey(0)).to_string(),
+            "no loop size produces the public key 0"
+        );
+    }
+
+    #[test]
+    fn budget() {
+        let mut monitor = Monitor::new().with_budget(Budget {
+            steps: Some(1_000_000),
+            time: None,
+        });
+
+        // Half of all the loop sizes are needed for the last possible key.
+        assert_eq!(
+            part1_monitored(&[20_201_226, TEST_DOOR_PUBLIC_KEY], &mut monitor),
+            Err(Interrupted::StepBudgetExceeded(1_000_000))
         );
     }
 }
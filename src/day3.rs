@@ -1,59 +1,173 @@
+//! Day 3: Toboggan Trajectory.
+
+use crate::geometry::Vector2;
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// A cell of the map, by its column (`x`) and row (`y`) from the top-left corner.
+pub type Point = Vector2;
+/// A step on the map, in columns to the right (`x`, to the left if negative) and rows down
+/// (`y`, from 1).
+pub type Slope = Vector2;
 
-struct Vec2 {
-    right: usize,
-    down: usize,
+/// The tree map, repeating endlessly to both sides.
+#[derive(Serialize, Deserialize)]
+pub struct Map {
+    trees: Vec<Point>,
+    columns: i32,
+    rows: i32,
 }
 
-impl From<(usize, usize)> for Vec2 {
-    fn from((right, down): (usize, usize)) -> Self {
-        Vec2 { right, down }
+impl Map {
+    pub fn trees(&self) -> &[Point] {
+        &self.trees
     }
-}
 
-type Point = Vec2;
-type Slope = Vec2;
+    /// The width of the map before it repeats.
+    pub fn columns(&self) -> i32 {
+        self.columns
+    }
 
-struct Map {
-    trees: Vec<Point>,
-    columns: usize,
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
 }
 
+/// Parses the map of open squares (`.`) and trees (`#`).
 #[aoc_generator(day3)]
-fn parse_input(input: &str) -> Map {
-    let columns = input.lines().next().unwrap().len();
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    let source = Source::new(3, input);
+    let width = input.lines().next().map_or(0, |line| line.len());
     let mut trees = Vec::new();
 
     for (down, line) in input.lines().enumerate() {
-        for (right, c) in line.chars().enumerate() {
-            if c == '#' {
-                trees.push((right, down).into());
-            } else {
-                assert_eq!(c, '.');
+        if line.len() != width {
+            return Err(source.error(line, format!("expected a row of {} cells", width)));
+        }
+
+        for (right, c) in line.char_indices() {
+            match c {
+                '#' => trees.push((right as i32, down as i32).into()),
+                '.' => (),
+                _ => {
+                    return Err(
+                        source.error(&line[right..right + c.len_utf8()], "expected '#' or '.'")
+                    )
+                }
             }
         }
     }
 
-    Map { trees, columns }
+    Ok(Map {
+        trees,
+        columns: width as i32,
+        rows: input.lines().count() as i32,
+    })
+}
+
+/// Whether the toboggan going along `slope` meets the tree: it is on a row the toboggan
+/// lands on, after some steps whose moves to the side reach its column modulo the width.
+fn on_path(map: &Map, slope: &Slope, tree: &Point) -> bool {
+    let (right, down, columns) = (
+        i64::from(slope.x()),
+        i64::from(slope.y()),
+        i64::from(map.columns),
+    );
+    let (x, y) = (i64::from(tree.x()), i64::from(tree.y()));
+
+    y % down == 0 && (y / down * right - x).rem_euclid(columns) == 0
 }
 
-fn count_trees(map: &Map, slope: &Slope) -> usize {
+/// Counts the trees met going from the top-left corner down the map along `slope`, the
+/// toboggan wrapping around on either side.
+///
+/// # Panics
+///
+/// Panics if the slope does not go down at least one row.
+pub fn count_trees(map: &Map, slope: &Slope) -> usize {
+    assert!(slope.y() >= 1, "the slope {:?} does not go down", slope);
+
     map.trees
         .iter()
-        .filter(|&point| {
-            point.down * slope.right % (map.columns * slope.down)
-                == point.right * slope.down % (map.columns * slope.down)
-        })
+        .filter(|&tree| on_path(map, slope, tree))
         .count()
 }
 
+/// Whether [`best_slope`] looks for the fewest trees or for the most.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Goal {
+    Fewest,
+    Most,
+}
+
+/// The slope within the bounds meeting the fewest or the most trees, with their number, the
+/// one going down the fewest rows and then the leftmost in case of a tie, or `None` if the
+/// bounds hold no slope going down.
+///
+/// As the trees met only depend on the moves to the side modulo the width, the slopes of
+/// the same row step are only counted once per column of the map.
+pub fn best_slope(
+    map: &Map,
+    rights: RangeInclusive<i32>,
+    downs: RangeInclusive<i32>,
+    goal: Goal,
+) -> Option<(Slope, usize)> {
+    let columns = map.columns.max(1);
+    let mut best: Option<(Slope, usize)> = None;
+
+    for down in *downs.start().max(&1)..=*downs.end() {
+        // The first slope of each class of moves to the side, in order.
+        let slopes = rights
+            .clone()
+            .take(columns as usize)
+            .map(|right| Slope::from((right, down)));
+
+        for slope in slopes {
+            let trees = count_trees(map, &slope);
+            let better = best.is_none_or(|(_, best)| match goal {
+                Goal::Fewest => trees < best,
+                Goal::Most => trees > best,
+            });
+
+            if better {
+                best = Some((slope, trees));
+            }
+        }
+    }
+
+    best
+}
+
+/// The puzzle parameters, the slope right 3, down 1 by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    pub slope: Slope,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            slope: (3, 1).into(),
+        }
+    }
+}
+
+/// Counts the trees met along the slope right 3, down 1.
 #[aoc(day3, part1)]
-fn part1(map: &Map) -> usize {
-    count_trees(map, &(3, 1).into())
+pub fn part1(map: &Map) -> usize {
+    part1_with(map, &Params::default())
+}
+
+/// Same as [`part1`], along the slope given by `params`.
+pub fn part1_with(map: &Map, params: &Params) -> usize {
+    count_trees(map, &params.slope)
 }
 
+/// Multiplies the tree counts along the five given slopes.
 #[aoc(day3, part2)]
-fn part2(map: &Map) -> usize {
+pub fn part2(map: &Map) -> usize {
     [
         (1, 1).into(),
         (3, 1).into(),
@@ -68,27 +182,28 @@ fn part2(map: &Map) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"..##.......
-#...#...#..
-.#....#..#.
-..#.#...#.#
-.#...##..#.
-..#.##.....
-.#.#.#....#
-.#........#
-#.##...#...
-#...##....#
-.#..#...#.#";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 7);
+        let map = parse(example(3, "example")).unwrap();
+
+        assert_eq!(part1(&map), 7);
+        assert_eq!(
+            part1_with(
+                &map,
+                &Params {
+                    slope: (1, 2).into()
+                }
+            ),
+            2
+        );
+        assert_eq!((map.columns(), map.rows()), (11, 11));
     }
 
     #[test]
     fn part2_example() {
-        let map = parse_input(TEST_INPUT);
+        let map = parse(example(3, "example")).unwrap();
 
         assert_eq!(count_trees(&map, &(1, 1).into()), 2);
         assert_eq!(count_trees(&map, &(3, 1).into()), 7);
@@ -97,4 +212,65 @@ mod tests {
         assert_eq!(count_trees(&map, &(1, 2).into()), 2);
         assert_eq!(part2(&map), 336);
     }
+
+    #[test]
+    fn malformed_input() {
+        let error = parse("..#\n.é").err().unwrap();
+
+        assert_eq!(
+            (error.line, error.column, error.token.as_str()),
+            (2, 2, "é")
+        );
+        assert!(parse("é.").is_err());
+        assert!(parse("#.\n#").is_err());
+    }
+
+    #[test]
+    fn any_slope() {
+        let map = parse(example(3, "example")).unwrap();
+        let walk = |right: i32, down: i32| {
+            (0..map.rows() / down + 1)
+                .map(|step| ((step * right).rem_euclid(map.columns()), step * down))
+                .filter(|&(x, y)| map.trees().contains(&(x, y).into()))
+                .count()
+        };
+
+        for right in -25..=25 {
+            for down in 1..=12 {
+                assert_eq!(
+                    count_trees(&map, &(right, down).into()),
+                    walk(right, down),
+                    "right {}, down {}",
+                    right,
+                    down
+                );
+            }
+        }
+
+        assert_eq!(count_trees(&map, &(-8, 1).into()), 7);
+        assert_eq!(count_trees(&map, &(2, 2).into()), 1);
+    }
+
+    #[test]
+    fn best_slopes() {
+        let map = parse(example(3, "example")).unwrap();
+
+        assert_eq!(
+            best_slope(&map, 1..=7, 1..=2, Goal::Most),
+            Some(((3, 1).into(), 7))
+        );
+        assert_eq!(
+            best_slope(&map, -10..=10, 1..=1, Goal::Most),
+            Some(((-8, 1).into(), 7))
+        );
+        assert_eq!(
+            best_slope(&map, -3..=3, 1..=3, Goal::Fewest),
+            Some(((1, 3).into(), 0))
+        );
+        assert_eq!(best_slope(&map, 0..=3, -1..=0, Goal::Fewest), None);
+        assert_eq!(
+            best_slope(&map, 0..=0, 12..=20, Goal::Most),
+            Some(((0, 12).into(), 0))
+        );
+    }
 }
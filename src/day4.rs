@@ -1,20 +1,27 @@
+//! Day 4: Passport Processing.
+
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 use lazy_static::lazy_static;
 use regex::Regex;
-
-#[derive(Default)]
-struct PassportEntry {
-    byr: Option<String>,
-    iyr: Option<String>,
-    eyr: Option<String>,
-    hgt: Option<String>,
-    hcl: Option<String>,
-    ecl: Option<String>,
-    pid: Option<String>,
+use serde::{Deserialize, Serialize};
+
+/// The raw fields of a passport; `cid` is ignored.
+#[derive(Default, Serialize, Deserialize)]
+pub struct PassportEntry {
+    pub byr: Option<String>,
+    pub iyr: Option<String>,
+    pub eyr: Option<String>,
+    pub hgt: Option<String>,
+    pub hcl: Option<String>,
+    pub ecl: Option<String>,
+    pub pid: Option<String>,
 }
 
+/// Parses the blank-line separated batch of passports.
 #[aoc_generator(day4)]
-fn parse_input(input: &str) -> Vec<PassportEntry> {
+pub fn parse(input: &str) -> Result<Vec<PassportEntry>, ParseError> {
+    let source = Source::new(4, input);
     let entries = input.split("\n\n");
     let mut result = Vec::new();
 
@@ -23,23 +30,30 @@ fn parse_input(input: &str) -> Vec<PassportEntry> {
 
         for token in entry.split_whitespace() {
             let mut key_value = token.split(':');
-            match key_value.next() {
-                Some("byr") => passport_entry.byr = Some(key_value.next().unwrap().to_owned()),
-                Some("iyr") => passport_entry.iyr = Some(key_value.next().unwrap().to_owned()),
-                Some("eyr") => passport_entry.eyr = Some(key_value.next().unwrap().to_owned()),
-                Some("hgt") => passport_entry.hgt = Some(key_value.next().unwrap().to_owned()),
-                Some("hcl") => passport_entry.hcl = Some(key_value.next().unwrap().to_owned()),
-                Some("ecl") => passport_entry.ecl = Some(key_value.next().unwrap().to_owned()),
-                Some("pid") => passport_entry.pid = Some(key_value.next().unwrap().to_owned()),
-                Some("cid") => (),
-                _ => unreachable!(),
+            let key = source.next(&mut key_value, token, "a field name")?;
+            let value = Some(
+                source
+                    .next(&mut key_value, token, "a field value")?
+                    .to_owned(),
+            );
+
+            match key {
+                "byr" => passport_entry.byr = value,
+                "iyr" => passport_entry.iyr = value,
+                "eyr" => passport_entry.eyr = value,
+                "hgt" => passport_entry.hgt = value,
+                "hcl" => passport_entry.hcl = value,
+                "ecl" => passport_entry.ecl = value,
+                "pid" => passport_entry.pid = value,
+                "cid" => (),
+                _ => return Err(source.error(key, "unknown passport field")),
             }
         }
 
         result.push(passport_entry);
     }
 
-    result
+    Ok(result)
 }
 
 fn validate_password_part1(entry: &PassportEntry) -> bool {
@@ -52,8 +66,9 @@ fn validate_password_part1(entry: &PassportEntry) -> bool {
         && entry.pid.is_some()
 }
 
+/// Counts passports with all required fields present.
 #[aoc(day4, part1)]
-fn part1(entries: &[PassportEntry]) -> usize {
+pub fn part1(entries: &[PassportEntry]) -> usize {
     entries
         .iter()
         .filter(|&entry| validate_password_part1(entry))
@@ -140,8 +155,9 @@ fn validate_password_part2(entry: &PassportEntry) -> bool {
         && validate_pid(&entry.pid)
 }
 
+/// Counts passports with all required fields present and valid.
 #[aoc(day4, part2)]
-fn part2(entries: &[PassportEntry]) -> usize {
+pub fn part2(entries: &[PassportEntry]) -> usize {
     entries
         .iter()
         .filter(|&entry| validate_password_part2(entry))
@@ -151,53 +167,41 @@ fn part2(entries: &[PassportEntry]) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
         assert!(validate_password_part1(
-            &parse_input(
+            &parse(
                 r"ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
 byr:1937 iyr:2017 cid:147 hgt:183cm"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(!validate_password_part1(
-            &parse_input(
+            &parse(
                 r"iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
 hcl:#cfa07d byr:1929"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(validate_password_part1(
-            &parse_input(
+            &parse(
                 r"hcl:#ae17e1 iyr:2013
 eyr:2024
 ecl:brn pid:760753108 byr:1931
 hgt:179cm"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(!validate_password_part1(
-            &parse_input(
+            &parse(
                 r"hcl:#cfa07d eyr:2025 pid:166559648
 iyr:2011 ecl:brn hgt:59in"
-            )[0]
+            )
+            .unwrap()[0]
         ));
-        assert_eq!(
-            part1(&parse_input(
-                r"ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
-byr:1937 iyr:2017 cid:147 hgt:183cm
-
-iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
-hcl:#cfa07d byr:1929
-
-hcl:#ae17e1 iyr:2013
-eyr:2024
-ecl:brn pid:760753108 byr:1931
-hgt:179cm
-
-hcl:#cfa07d eyr:2025 pid:166559648
-iyr:2011 ecl:brn hgt:59in"
-            )),
-            2
-        );
+        assert_eq!(part1(&parse(example(4, "example")).unwrap()), 2);
     }
 
     #[test]
@@ -221,55 +225,62 @@ iyr:2011 ecl:brn hgt:59in"
         assert!(!validate_pid(&Some("0123456789".to_string())));
 
         assert!(!validate_password_part2(
-            &parse_input(
+            &parse(
                 r"eyr:1972 cid:100
 hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(!validate_password_part2(
-            &parse_input(
+            &parse(
                 r"iyr:2019
 hcl:#602927 eyr:1967 hgt:170cm
 ecl:grn pid:012533040 byr:1946"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(!validate_password_part2(
-            &parse_input(
+            &parse(
                 r"hcl:dab227 iyr:2012
 ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(!validate_password_part2(
-            &parse_input(
+            &parse(
                 r"hgt:59cm ecl:zzz
 eyr:2038 hcl:74454a iyr:2023
 pid:3556412378 byr:2007"
-            )[0]
+            )
+            .unwrap()[0]
         ));
 
         assert!(validate_password_part2(
-            &parse_input(
+            &parse(
                 r"pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
 hcl:#623a2f"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(validate_password_part2(
-            &parse_input(
+            &parse(
                 r"eyr:2029 ecl:blu cid:129 byr:1989
 iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(validate_password_part2(
-            &parse_input(
+            &parse(
                 r"hcl:#888785
 hgt:164cm byr:2001 iyr:2015 cid:88
 pid:545766238 ecl:hzl
 eyr:2022"
-            )[0]
+            )
+            .unwrap()[0]
         ));
         assert!(validate_password_part2(
-            &parse_input(r"iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719")
-                [0]
+            &parse(r"iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719")
+                .unwrap()[0]
         ));
     }
 }
@@ -1,47 +1,52 @@
+//! Day 5: Binary Boarding.
+
+use crate::parsing::{parse_lines, ParseError, Source, StreamError};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+
+/// A boarding pass, holding its seat ID.
+#[derive(Serialize, Deserialize)]
+pub struct BoardingPass(pub usize);
 
-struct BoardingPass(usize);
-
-impl From<&str> for BoardingPass {
-    fn from(code: &str) -> Self {
-        BoardingPass(
-            usize::from_str_radix(
-                unsafe {
-                    std::str::from_utf8_unchecked(
-                        &code[0..7]
-                            .bytes()
-                            .map(|c| match c {
-                                b'F' => b'0',
-                                b'B' => b'1',
-                                _ => unreachable!(),
-                            })
-                            .chain(code[7..10].bytes().map(|c| match c {
-                                b'L' => b'0',
-                                b'R' => b'1',
-                                _ => unreachable!(),
-                            }))
-                            .collect::<Vec<_>>(),
-                    )
-                },
-                2,
-            )
-            .unwrap(),
-        )
+fn parse_boarding_pass(source: &Source, code: &str) -> Result<BoardingPass, ParseError> {
+    if code.len() != 10 {
+        return Err(source.error(code, "expected a 10 character seat code"));
     }
+
+    code.char_indices()
+        .try_fold(0, |id, (i, c)| {
+            let bit = match (i, c) {
+                (0..=6, 'F') | (7..=9, 'L') => 0,
+                (0..=6, 'B') | (7..=9, 'R') => 1,
+                (0..=6, _) => return Err(source.error(&code[i..=i], "expected 'F' or 'B'")),
+                _ => return Err(source.error(&code[i..=i], "expected 'L' or 'R'")),
+            };
+
+            Ok(id * 2 + bit)
+        })
+        .map(BoardingPass)
 }
 
+/// Parses the binary space partitioned seat codes, one per line.
 #[aoc_generator(day5)]
-fn parse_input(input: &str) -> Vec<BoardingPass> {
-    input.lines().map(BoardingPass::from).collect()
+pub fn parse(input: &str) -> Result<Vec<BoardingPass>, ParseError> {
+    let source = Source::new(5, input);
+    input
+        .lines()
+        .map(|line| parse_boarding_pass(&source, line))
+        .collect()
 }
 
+/// Returns the highest seat ID.
 #[aoc(day5, part1)]
-fn part1(boarding_passes: &[BoardingPass]) -> usize {
+pub fn part1(boarding_passes: &[BoardingPass]) -> usize {
     boarding_passes.iter().map(|pass| pass.0).max().unwrap()
 }
 
+/// Returns the ID of the only missing seat between the taken ones.
 #[aoc(day5, part2)]
-fn part2(boarding_passes: &[BoardingPass]) -> usize {
+pub fn part2(boarding_passes: &[BoardingPass]) -> usize {
     let mut ids: Vec<usize> = boarding_passes.iter().map(|pass| pass.0).collect();
     ids.sort_unstable();
 
@@ -51,9 +56,32 @@ fn part2(boarding_passes: &[BoardingPass]) -> usize {
         + 1
 }
 
+/// Same as [`part1`], reading the seat codes line by line.
+pub fn part1_stream(reader: impl BufRead) -> Result<Option<usize>, StreamError> {
+    parse_lines(5, reader, parse_boarding_pass)
+        .try_fold(None, |max: Option<usize>, pass| Ok(max.max(Some(pass?.0))))
+}
+
+/// Same as [`part2`], reading the seat codes line by line and marking the taken seats.
+pub fn part2_stream(reader: impl BufRead) -> Result<Option<usize>, StreamError> {
+    let mut taken = [false; 1 << 10];
+    let mut min = taken.len();
+    let mut max = 0;
+
+    for pass in parse_lines(5, reader, parse_boarding_pass) {
+        let id = pass?.0;
+        taken[id] = true;
+        min = min.min(id);
+        max = max.max(id);
+    }
+
+    Ok((min..max).find(|&id| !taken[id]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
 
     impl BoardingPass {
         fn row(&self) -> usize {
@@ -67,28 +95,44 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        let boarding_pass = BoardingPass::from("FBFBBFFRLR");
+        let boarding_passes = parse(example(5, "example")).unwrap();
+
+        let boarding_pass = &boarding_passes[0];
 
         assert_eq!(boarding_pass.row(), 44);
         assert_eq!(boarding_pass.column(), 5);
         assert_eq!(boarding_pass.0, 357);
 
-        let boarding_pass = BoardingPass::from("BFFFBBFRRR");
+        let boarding_pass = &boarding_passes[1];
 
         assert_eq!(boarding_pass.row(), 70);
         assert_eq!(boarding_pass.column(), 7);
         assert_eq!(boarding_pass.0, 567);
 
-        let boarding_pass = BoardingPass::from("FFFBBBFRRR");
+        let boarding_pass = &boarding_passes[2];
 
         assert_eq!(boarding_pass.row(), 14);
         assert_eq!(boarding_pass.column(), 7);
         assert_eq!(boarding_pass.0, 119);
 
-        let boarding_pass = BoardingPass::from("BBFFBBFRLL");
+        let boarding_pass = &boarding_passes[3];
 
         assert_eq!(boarding_pass.row(), 102);
         assert_eq!(boarding_pass.column(), 4);
         assert_eq!(boarding_pass.0, 820);
     }
+
+    #[test]
+    fn streamed() {
+        assert_eq!(
+            part1_stream(example(5, "example").as_bytes()).unwrap(),
+            Some(820)
+        );
+        assert_eq!(part1_stream("".as_bytes()).unwrap(), None);
+        assert_eq!(
+            part2_stream("FFFFFFFRLR\nFFFFFFBLLL\nFFFFFFFRRL".as_bytes()).unwrap(),
+            Some(7)
+        );
+        assert!(part2_stream("FFFFFFFRRL\nFFFFFFFRRX".as_bytes()).is_err());
+    }
 }
@@ -1,22 +1,41 @@
+//! Day 6: Custom Customs.
+
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashSet;
 
-type AnsweredYes = char;
-type Form = HashSet<AnsweredYes>;
-type Group = Vec<Form>;
+pub type AnsweredYes = char;
+/// The questions a single person answered "yes" to.
+pub type Form = HashSet<AnsweredYes>;
+pub type Group = Vec<Form>;
+
+fn parse_form(source: &Source, form_str: &str) -> Result<Form, ParseError> {
+    form_str
+        .char_indices()
+        .map(|(i, c)| {
+            if c.is_ascii_lowercase() {
+                Ok(c)
+            } else {
+                Err(source.error(&form_str[i..i + c.len_utf8()], "expected a question letter"))
+            }
+        })
+        .collect()
+}
 
-fn parse_group(group_str: &str) -> Group {
+fn parse_group(source: &Source, group_str: &str) -> Result<Group, ParseError> {
     group_str
         .lines()
-        .map(|form_str| form_str.chars().collect())
+        .map(|form_str| parse_form(source, form_str))
         .collect()
 }
 
+/// Parses the blank-line separated groups of answer forms.
 #[aoc_generator(day6)]
-fn parse_input(input: &str) -> Vec<Group> {
+pub fn parse(input: &str) -> Result<Vec<Group>, ParseError> {
+    let source = Source::new(6, input);
     input
         .split("\n\n")
-        .map(|group_str| parse_group(group_str))
+        .map(|group_str| parse_group(&source, group_str))
         .collect()
 }
 
@@ -29,8 +48,9 @@ fn count_any_yes(group: &[Form]) -> usize {
         .len()
 }
 
+/// Sums the number of questions anyone in each group answered "yes" to.
 #[aoc(day6, part1)]
-fn part1(groups: &[Group]) -> usize {
+pub fn part1(groups: &[Group]) -> usize {
     groups.iter().map(|group| count_any_yes(group)).sum()
 }
 
@@ -44,14 +64,20 @@ fn count_all_yes(group: &[Form]) -> usize {
         .len()
 }
 
+/// Sums the number of questions everyone in each group answered "yes" to.
 #[aoc(day6, part2)]
-fn part2(groups: &[Group]) -> usize {
+pub fn part2(groups: &[Group]) -> usize {
     groups.iter().map(|group| count_all_yes(group)).sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::inputs::example;
+
+    fn group(group_str: &str) -> Group {
+        parse_group(&Source::new(6, group_str), group_str).unwrap()
+    }
 
     static TEST_GROUP_1: &str = "abc";
     static TEST_GROUP_2: &str = r"a
@@ -64,26 +90,11 @@ a
 a
 a";
     static TEST_GROUP_5: &str = "b";
-    static TEST_INPUT: &str = r"abc
-
-a
-b
-c
-
-ab
-ac
-
-a
-a
-a
-a
-
-b";
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            count_any_yes(&parse_group(
+            count_any_yes(&group(
                 r"abcx
 abcy
 abcz"
@@ -91,23 +102,23 @@ abcz"
             6
         );
 
-        assert_eq!(count_any_yes(&parse_group(TEST_GROUP_1)), 3);
-        assert_eq!(count_any_yes(&parse_group(TEST_GROUP_2)), 3);
-        assert_eq!(count_any_yes(&parse_group(TEST_GROUP_3)), 3);
-        assert_eq!(count_any_yes(&parse_group(TEST_GROUP_4)), 1);
-        assert_eq!(count_any_yes(&parse_group(TEST_GROUP_5)), 1);
+        assert_eq!(count_any_yes(&group(TEST_GROUP_1)), 3);
+        assert_eq!(count_any_yes(&group(TEST_GROUP_2)), 3);
+        assert_eq!(count_any_yes(&group(TEST_GROUP_3)), 3);
+        assert_eq!(count_any_yes(&group(TEST_GROUP_4)), 1);
+        assert_eq!(count_any_yes(&group(TEST_GROUP_5)), 1);
 
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 11);
+        assert_eq!(part1(&parse(example(6, "example")).unwrap()), 11);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(count_all_yes(&parse_group(TEST_GROUP_1)), 3);
-        assert_eq!(count_all_yes(&parse_group(TEST_GROUP_2)), 0);
-        assert_eq!(count_all_yes(&parse_group(TEST_GROUP_3)), 1);
-        assert_eq!(count_all_yes(&parse_group(TEST_GROUP_4)), 1);
-        assert_eq!(count_all_yes(&parse_group(TEST_GROUP_5)), 1);
+        assert_eq!(count_all_yes(&group(TEST_GROUP_1)), 3);
+        assert_eq!(count_all_yes(&group(TEST_GROUP_2)), 0);
+        assert_eq!(count_all_yes(&group(TEST_GROUP_3)), 1);
+        assert_eq!(count_all_yes(&group(TEST_GROUP_4)), 1);
+        assert_eq!(count_all_yes(&group(TEST_GROUP_5)), 1);
 
-        assert_eq!(part2(&parse_input(TEST_INPUT)), 6);
+        assert_eq!(part2(&parse(example(6, "example")).unwrap()), 6);
     }
 }
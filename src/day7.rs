@@ -1,31 +1,66 @@
+//! Day 7: Handy Haversacks.
+
+use crate::parsing::{ParseError, Source};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::hash::Hash;
 
-#[derive(Eq, PartialEq, Hash)]
-struct Color {
-    primary: String,
-    shade: String,
+/// A bag color, e.g. "shiny gold" has the shade "shiny" and the primary color "gold".
+///
+/// It is serialised as its name, so that it can key the maps of the rules in JSON.
+#[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Color {
+    pub primary: String,
+    pub shade: String,
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        format!("{} {}", color.shade, color.primary)
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        match name.split_whitespace().collect::<Vec<_>>()[..] {
+            [shade, primary] => Ok(Color {
+                primary: primary.to_string(),
+                shade: shade.to_string(),
+            }),
+            _ => Err(format!("{:?} is not a shade and a color", name)),
+        }
+    }
 }
 
 type Rule = (Color, HashMap<Color, usize>);
-type Rules = HashMap<Color, HashMap<Color, usize>>;
+/// The bags (and their quantities) each bag must directly contain.
+pub type Rules = HashMap<Color, HashMap<Color, usize>>;
 
-fn parse_rule(rule_str: &str) -> Rule {
+fn parse_rule(source: &Source, rule_str: &str) -> Result<Rule, ParseError> {
     let mut rule_split = rule_str.split("contain ");
 
-    let mut outer_bag_split = rule_split.next().unwrap().split_whitespace();
+    let outer_bag_str = source.next(&mut rule_split, rule_str, "an outer bag")?;
+    let mut outer_bag_split = outer_bag_str.split_whitespace();
     let (shade, primary) = (
-        outer_bag_split.next().unwrap().to_string(),
-        outer_bag_split.next().unwrap().to_string(),
+        source
+            .next(&mut outer_bag_split, outer_bag_str, "a shade")?
+            .to_string(),
+        source
+            .next(&mut outer_bag_split, outer_bag_str, "a color")?
+            .to_string(),
     );
 
     let outer_color = Color { primary, shade };
 
-    let inner_bags_str = rule_split.next().unwrap();
+    let inner_bags_str = source.next(&mut rule_split, rule_str, "\"contain\"")?;
 
     if inner_bags_str == "no other bags." {
-        return (outer_color, HashMap::with_capacity(0));
+        return Ok((outer_color, HashMap::with_capacity(0)));
     }
 
     let inner_bags_split = inner_bags_str.split(", ");
@@ -34,20 +69,50 @@ fn parse_rule(rule_str: &str) -> Rule {
     for inner_bag_str in inner_bags_split {
         let mut inner_bag_split = inner_bag_str.split_whitespace();
         let (qty, shade, primary) = (
-            inner_bag_split.next().unwrap().parse::<usize>().unwrap(),
-            inner_bag_split.next().unwrap().to_string(),
-            inner_bag_split.next().unwrap().to_string(),
+            source.parse(source.next(&mut inner_bag_split, inner_bag_str, "a quantity")?)?,
+            source
+                .next(&mut inner_bag_split, inner_bag_str, "a shade")?
+                .to_string(),
+            source
+                .next(&mut inner_bag_split, inner_bag_str, "a color")?
+                .to_string(),
         );
 
         inner_colors_with_qty.insert(Color { primary, shade }, qty);
     }
 
-    (outer_color, inner_colors_with_qty)
+    Ok((outer_color, inner_colors_with_qty))
 }
 
+/// Parses the bag rules, one per line.
 #[aoc_generator(day7)]
-fn parse_input(input: &str) -> Rules {
-    input.lines().map(|rule_str| parse_rule(rule_str)).collect()
+pub fn parse(input: &str) -> Result<Rules, ParseError> {
+    let source = Source::new(7, input);
+    input
+        .lines()
+        .map(|rule_str| parse_rule(&source, rule_str))
+        .collect()
+}
+
+/// The puzzle parameters, the shiny gold bag by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    /// The bag to look for, as its shade and primary color.
+    pub target: String,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            target: "shiny gold".to_string(),
+        }
+    }
+}
+
+impl Params {
+    fn target_color(&self) -> Option<Color> {
+        Color::try_from(self.target.clone()).ok()
+    }
 }
 
 struct Node<'a> {
@@ -55,14 +120,17 @@ struct Node<'a> {
     previous_colors: Vec<&'a Color>,
 }
 
+/// Counts the bag colors that can eventually contain a shiny gold bag.
 #[aoc(day7, part1)]
-fn part1(rules: &Rules) -> usize {
-    let target_color = Color {
-        primary: "gold".to_string(),
-        shade: "shiny".to_string(),
-    };
+pub fn part1(rules: &Rules) -> Option<usize> {
+    part1_with(rules, &Params::default())
+}
+
+/// Same as [`part1`], for the bag given by `params`, or `None` if there is no rule for it.
+pub fn part1_with(rules: &Rules, params: &Params) -> Option<usize> {
+    let target_color = params.target_color()?;
 
-    let mut visited: HashSet<&Color> = rules.get(&target_color).unwrap().keys().collect();
+    let mut visited: HashSet<&Color> = rules.get(&target_color)?.keys().collect();
     let mut stack: Vec<Node> = rules
         .keys()
         .map(|color| Node {
@@ -72,9 +140,7 @@ fn part1(rules: &Rules) -> usize {
         .collect();
     let mut can_contain_target = HashSet::new();
 
-    while !stack.is_empty() {
-        let node = stack.pop().unwrap();
-
+    while let Some(node) = stack.pop() {
         if *node.color == target_color {
             can_contain_target.extend(node.previous_colors);
             continue;
@@ -97,7 +163,7 @@ fn part1(rules: &Rules) -> usize {
         }
     }
 
-    can_contain_target.len()
+    Some(can_contain_target.len())
 }
 
 fn count_inner_bags(target_color: &Color, rules: &HashMap<Color, HashMap<Color, usize>>) -> usize {
@@ -109,45 +175,47 @@ fn count_inner_bags(target_color: &Color, rules: &HashMap<Color, HashMap<Color,
         .sum::<usize>()
 }
 
+/// Counts the bags required inside a single shiny gold bag.
 #[aoc(day7, part2)]
-fn part2(rules: &Rules) -> usize {
-    let target_color = Color {
-        primary: "gold".to_string(),
-        shade: "shiny".to_string(),
-    };
+pub fn part2(rules: &Rules) -> Option<usize> {
+    part2_with(rules, &Params::default())
+}
+
+/// Same as [`part2`], for the bag given by `params`, or `None` if there is no rule for it.
+pub fn part2_with(rules: &Rules, params: &Params) -> Option<usize> {
+    let target_color = params.target_color()?;
 
-    count_inner_bags(&target_color, rules)
+    rules
+        .contains_key(&target_color)
+        .then(|| count_inner_bags(&target_color, rules))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT_1: &str = r"light red bags contain 1 bright white bag, 2 muted yellow bags.
-dark orange bags contain 3 bright white bags, 4 muted yellow bags.
-bright white bags contain 1 shiny gold bag.
-muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
-shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
-dark olive bags contain 3 faded blue bags, 4 dotted black bags.
-vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
-faded blue bags contain no other bags.
-dotted black bags contain no other bags.";
-    static TEST_INPUT_2: &str = r"shiny gold bags contain 2 dark red bags.
-dark red bags contain 2 dark orange bags.
-dark orange bags contain 2 dark yellow bags.
-dark yellow bags contain 2 dark green bags.
-dark green bags contain 2 dark blue bags.
-dark blue bags contain 2 dark violet bags.
-dark violet bags contain no other bags.";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_INPUT_1)), 4);
+        assert_eq!(part1(&parse(example(7, "example")).unwrap()), Some(4));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_INPUT_1)), 32);
-        assert_eq!(part2(&parse_input(TEST_INPUT_2)), 126);
+        assert_eq!(part2(&parse(example(7, "example")).unwrap()), Some(32));
+        assert_eq!(part2(&parse(example(7, "part2")).unwrap()), Some(126));
+    }
+
+    #[test]
+    fn other_target() {
+        let rules = parse(example(7, "example")).unwrap();
+        let params = |target: &str| Params {
+            target: target.to_string(),
+        };
+
+        assert_eq!(part1_with(&rules, &params("dark olive")), Some(5));
+        assert_eq!(part2_with(&rules, &params("vibrant plum")), Some(11));
+        assert_eq!(part2_with(&rules, &params("plum")), None);
+        assert_eq!(part1_with(&rules, &params("dull grey")), None);
     }
 }
@@ -1,54 +1,84 @@
+//! Day 8: Handheld Halting.
+
+use crate::parsing::{ParseError, Source};
+use crate::trace::{Event, NoTrace, Tracer};
 use aoc_runner_derive::{aoc, aoc_generator};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
-#[derive(Clone)]
-enum Operation {
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Operation {
     Accumulator,
     Jump,
     NoOp,
 }
 
-impl From<&str> for Operation {
-    fn from(operation_str: &str) -> Self {
-        match operation_str {
-            "acc" => Self::Accumulator,
-            "jmp" => Self::Jump,
-            "nop" => Self::NoOp,
-            _ => unreachable!(),
+fn parse_operation(source: &Source, operation_str: &str) -> Result<Operation, ParseError> {
+    match operation_str {
+        "acc" => Ok(Operation::Accumulator),
+        "jmp" => Ok(Operation::Jump),
+        "nop" => Ok(Operation::NoOp),
+        _ => Err(source.error(operation_str, "unknown operation")),
+    }
+}
+
+impl Operation {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Operation::Accumulator => "acc",
+            Operation::Jump => "jmp",
+            Operation::NoOp => "nop",
         }
     }
 }
 
-#[derive(Clone)]
-struct Instruction {
-    operation: Operation,
-    argument: isize,
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Instruction {
+    pub operation: Operation,
+    pub argument: isize,
 }
 
-impl From<&str> for Instruction {
-    fn from(instruction_str: &str) -> Self {
-        let mut iter = instruction_str.split_whitespace();
+fn parse_instruction(source: &Source, instruction_str: &str) -> Result<Instruction, ParseError> {
+    let mut iter = instruction_str.split_whitespace();
 
-        let operation: Operation = iter.next().unwrap().into();
+    let operation = parse_operation(
+        source,
+        source.next(&mut iter, instruction_str, "an operation")?,
+    )?;
 
-        let argument = iter.next().unwrap().parse::<isize>().unwrap();
+    let argument = source.parse(source.next(&mut iter, instruction_str, "an argument")?)?;
 
-        Self {
-            operation,
-            argument,
-        }
-    }
+    Ok(Instruction {
+        operation,
+        argument,
+    })
 }
 
-struct Computer(Vec<Instruction>);
+/// The boot code of the handheld game console.
+#[derive(Serialize, Deserialize)]
+pub struct Computer(pub Vec<Instruction>);
 
+/// Parses the boot code, one `<operation> <argument>` instruction per line.
 #[aoc_generator(day8)]
-fn parse_input(input: &str) -> Computer {
-    Computer(input.lines().map(|line| line.into()).collect())
+pub fn parse(input: &str) -> Result<Computer, ParseError> {
+    let source = Source::new(8, input);
+    input
+        .lines()
+        .map(|line| parse_instruction(&source, line))
+        .collect::<Result<_, _>>()
+        .map(Computer)
 }
 
 impl Computer {
-    fn execute(&self) -> Result<isize, isize> {
+    /// Runs the program, returning the accumulator either once it terminates (`Ok`) or just
+    /// before any instruction would be executed a second time (`Err`).
+    pub fn execute(&self) -> Result<isize, isize> {
+        self.execute_traced(&mut NoTrace)
+    }
+
+    /// Same as [`Computer::execute`], tracing every executed instruction with the accumulator
+    /// after it.
+    pub fn execute_traced(&self, tracer: &mut impl Tracer) -> Result<isize, isize> {
         let mut visited = HashSet::new();
 
         let mut accumulator = 0;
@@ -56,21 +86,38 @@ impl Computer {
 
         while i < self.0.len() {
             if visited.contains(&i) {
+                tracer.trace(|| {
+                    Event::new(8, "loop")
+                        .with("index", i)
+                        .with("accumulator", accumulator)
+                });
                 return Err(accumulator);
             }
 
             visited.insert(i);
+            let (index, instruction) = (i, &self.0[i]);
 
-            match self.0[i].operation {
+            match instruction.operation {
                 Operation::Accumulator => {
-                    accumulator += self.0[i].argument;
+                    accumulator += instruction.argument;
                     i += 1;
                 }
-                Operation::Jump => i = (i as isize + self.0[i].argument) as usize,
+                Operation::Jump => i = (i as isize + instruction.argument) as usize,
                 Operation::NoOp => i += 1,
             }
+
+            tracer.trace(|| {
+                Event::new(8, "step")
+                    .with("index", index)
+                    .with("operation", instruction.operation.mnemonic())
+                    .with("argument", instruction.argument)
+                    .with("accumulator", accumulator)
+                    .with("next", i)
+            });
         }
 
+        tracer.trace(|| Event::new(8, "terminate").with("accumulator", accumulator));
+
         Ok(accumulator)
     }
 }
@@ -83,13 +130,25 @@ fn flip_operation(operation: &Operation) -> Operation {
     }
 }
 
+/// Returns the accumulator value right before the infinite loop repeats.
 #[aoc(day8, part1)]
-fn part1(computer: &Computer) -> isize {
-    computer.execute().unwrap_err()
+pub fn part1(computer: &Computer) -> isize {
+    part1_traced(computer, &mut NoTrace)
 }
 
+/// Same as [`part1`], tracing every step of the program.
+pub fn part1_traced(computer: &Computer, tracer: &mut impl Tracer) -> isize {
+    computer.execute_traced(tracer).unwrap_err()
+}
+
+/// Returns the accumulator value after the program terminates with one `jmp`/`nop` swapped.
 #[aoc(day8, part2)]
-fn part2(computer: &Computer) -> isize {
+pub fn part2(computer: &Computer) -> isize {
+    part2_traced(computer, &mut NoTrace)
+}
+
+/// Same as [`part2`], tracing the outcome of every swap tried.
+pub fn part2_traced(computer: &Computer, tracer: &mut impl Tracer) -> isize {
     for i in 0..computer.0.len() {
         let instructions = &computer.0;
 
@@ -99,7 +158,20 @@ fn part2(computer: &Computer) -> isize {
                 fixed_instructions[i].operation = flip_operation(&fixed_instructions[i].operation);
                 let fixed_computer = Computer(fixed_instructions);
 
-                if let Ok(result) = fixed_computer.execute() {
+                let outcome = fixed_computer.execute();
+
+                tracer.trace(|| {
+                    Event::new(8, "swap")
+                        .with("index", i)
+                        .with("operation", fixed_computer.0[i].operation.mnemonic())
+                        .with("terminates", outcome.is_ok())
+                        .with(
+                            "accumulator",
+                            outcome.unwrap_or_else(|accumulator| accumulator),
+                        )
+                });
+
+                if let Ok(result) = outcome {
                     return result;
                 }
             }
@@ -113,24 +185,49 @@ fn part2(computer: &Computer) -> isize {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"nop +0
-acc +1
-jmp +4
-acc +3
-jmp -3
-acc -99
-acc +1
-jmp -4
-acc +6";
+    use crate::inputs::example;
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse_input(TEST_INPUT)), 5);
+        assert_eq!(part1(&parse(example(8, "example")).unwrap()), 5);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse_input(TEST_INPUT)), 8);
+        assert_eq!(part2(&parse(example(8, "example")).unwrap()), 8);
+    }
+
+    #[test]
+    fn traced() {
+        let computer = parse(example(8, "example")).unwrap();
+        let mut events = Vec::new();
+
+        assert_eq!(part1_traced(&computer, &mut events), 5);
+        assert_eq!(events.len(), 8);
+        assert_eq!(events[1].get("accumulator"), Some(&1.into()));
+        assert_eq!(events[7].name, "loop");
+
+        let mut events = Vec::new();
+
+        assert_eq!(part2_traced(&computer, &mut events), 8);
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.get("index").unwrap().clone())
+                .collect::<Vec<_>>(),
+            vec![0.into(), 2.into(), 4.into(), 7.into()]
+        );
+    }
+
+    #[test]
+    fn malformed_input() {
+        let error = parse("nop +0\nacc +1\njump +4").err().unwrap();
+
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(error.token, "jump");
+
+        let error = parse("nop +0\nacc").err().unwrap();
+
+        assert_eq!((error.line, error.column), (2, 4));
     }
 }
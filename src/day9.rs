@@ -1,11 +1,31 @@
+//! Day 9: Encoding Error.
+
+use crate::parsing::{parse_lines, ParseError, Source, StreamError};
 use aoc_runner_derive::{aoc, aoc_generator};
-use std::num::ParseIntError;
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+/// A number of the XMAS-encrypted data.
+pub type Entry = i64;
+
+/// The puzzle parameters, a preamble of 25 by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Params {
+    /// The number of previous entries each entry must be the sum of two of.
+    pub preamble: usize,
+}
 
-type Entry = i64;
+impl Default for Params {
+    fn default() -> Self {
+        Params { preamble: 25 }
+    }
+}
 
+/// Parses the XMAS data, one number per line.
 #[aoc_generator(day9)]
-fn parse_input(input: &str) -> Result<Vec<Entry>, ParseIntError> {
-    input.lines().map(|l| l.parse()).collect()
+pub fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+    let source = Source::new(9, input);
+    input.lines().map(|l| source.parse(l)).collect()
 }
 
 fn is_valid_entry(entry: &Entry, previous_entries: &[Entry]) -> bool {
@@ -14,7 +34,8 @@ fn is_valid_entry(entry: &Entry, previous_entries: &[Entry]) -> bool {
         .any(|&first| 2 * first != *entry && previous_entries.contains(&(entry - first)))
 }
 
-fn first_invalid_entry(entries: &[Entry], preamble_len: usize) -> Option<Entry> {
+/// Finds the first entry that is not the sum of two of the `preamble_len` entries before it.
+pub fn first_invalid_entry(entries: &[Entry], preamble_len: usize) -> Option<Entry> {
     for i in preamble_len..entries.len() {
         if !is_valid_entry(&entries[i], &entries[i - preamble_len..i]) {
             return Some(entries[i]);
@@ -24,15 +45,54 @@ fn first_invalid_entry(entries: &[Entry], preamble_len: usize) -> Option<Entry>
     None
 }
 
+/// Reads the data from `reader` line by line, yielding each entry that is not the sum of two of
+/// the `preamble_len` entries before it as soon as it is read.
+pub fn invalid_entries(
+    reader: impl BufRead,
+    preamble_len: usize,
+) -> impl Iterator<Item = Result<Entry, StreamError>> {
+    let mut window = VecDeque::with_capacity(preamble_len + 1);
+
+    parse_lines(9, reader, |source, line| source.parse(line)).filter_map(move |entry| {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => return Some(Err(error)),
+        };
+        let invalid =
+            window.len() == preamble_len && !is_valid_entry(&entry, window.make_contiguous());
+
+        window.push_back(entry);
+
+        if window.len() > preamble_len {
+            window.pop_front();
+        }
+
+        invalid.then_some(Ok(entry))
+    })
+}
+
+/// Returns the first invalid entry with a preamble of 25.
 #[aoc(day9, part1)]
-fn part1(entries: &[Entry]) -> Option<Entry> {
-    first_invalid_entry(entries, 25)
+pub fn part1(entries: &[Entry]) -> Option<Entry> {
+    part1_with(entries, &Params::default())
+}
+
+/// Same as [`part1`], with the preamble given by `params`.
+pub fn part1_with(entries: &[Entry], params: &Params) -> Option<Entry> {
+    first_invalid_entry(entries, params.preamble)
 }
 
-fn encryption_weakness(entries: &[Entry], preamble_len: usize) -> i64 {
-    let target = first_invalid_entry(entries, preamble_len).unwrap();
+/// Same as [`part1`], reading the data line by line up to the first invalid entry.
+pub fn part1_stream(reader: impl BufRead) -> Result<Option<Entry>, StreamError> {
+    invalid_entries(reader, 25).next().transpose()
+}
+
+/// Sums the smallest and largest numbers of the contiguous range adding up to the first
+/// invalid entry, or `None` if there is no such entry or range.
+pub fn encryption_weakness(entries: &[Entry], preamble_len: usize) -> Option<i64> {
+    let target = first_invalid_entry(entries, preamble_len)?;
 
-    for i in 0..entries.len() - 1 {
+    for i in 0..entries.len().saturating_sub(1) {
         let mut acc = entries[i];
 
         if acc > target {
@@ -45,7 +105,7 @@ fn encryption_weakness(entries: &[Entry], preamble_len: usize) -> i64 {
             match acc {
                 acc if acc == target => {
                     let range = &entries[i..j + 1];
-                    return range.iter().min().unwrap() + range.iter().max().unwrap();
+                    return Some(range.iter().min()? + range.iter().max()?);
                 }
                 acc if acc > target => break,
                 _ => (),
@@ -53,38 +113,25 @@ fn encryption_weakness(entries: &[Entry], preamble_len: usize) -> i64 {
         }
     }
 
-    unreachable!();
+    None
 }
 
+/// Returns the encryption weakness with a preamble of 25.
 #[aoc(day9, part2)]
-fn part2(entries: &[Entry]) -> i64 {
-    encryption_weakness(entries, 25)
+pub fn part2(entries: &[Entry]) -> Option<i64> {
+    part2_with(entries, &Params::default())
+}
+
+/// Same as [`part2`], with the preamble given by `params`.
+pub fn part2_with(entries: &[Entry], params: &Params) -> Option<i64> {
+    encryption_weakness(entries, params.preamble)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    static TEST_INPUT: &str = r"35
-20
-15
-25
-47
-40
-62
-55
-65
-95
-102
-117
-150
-182
-127
-219
-299
-277
-309
-576";
+    use crate::inputs::example;
+    use crate::solver;
 
     #[test]
     fn part1_example() {
@@ -105,16 +152,71 @@ mod tests {
         assert!(is_valid_entry(&66, &other_25));
 
         assert_eq!(
-            first_invalid_entry(&parse_input(TEST_INPUT).unwrap(), 5),
+            first_invalid_entry(&parse(example(9, "example")).unwrap(), 5),
             Some(127)
         );
     }
 
+    #[test]
+    fn streamed() {
+        let mut entries = invalid_entries(example(9, "example").as_bytes(), 5);
+
+        assert_eq!(entries.next().unwrap().unwrap(), 127);
+        assert_eq!(
+            entries.map(Result::unwrap).collect::<Vec<_>>(),
+            parse(example(9, "example"))
+                .unwrap()
+                .windows(6)
+                .filter(|window| !is_valid_entry(&window[5], &window[..5]))
+                .skip(1)
+                .map(|window| window[5])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            part1_stream(
+                (1..=30)
+                    .map(|n| format!("{}\n", n))
+                    .collect::<String>()
+                    .as_bytes()
+            )
+            .unwrap(),
+            None
+        );
+        assert!(invalid_entries("1\n2\nx".as_bytes(), 1).any(|entry| entry.is_err()));
+    }
+
     #[test]
     fn part2_example() {
         assert_eq!(
-            encryption_weakness(&parse_input(TEST_INPUT).unwrap(), 5),
-            62
+            encryption_weakness(&parse(example(9, "example")).unwrap(), 5),
+            Some(62)
+        );
+        assert_eq!(
+            part2_with(
+                &parse(example(9, "example")).unwrap(),
+                &Params { preamble: 5 }
+            ),
+            Some(62)
         );
     }
+
+    #[test]
+    fn no_encryption_weakness() {
+        let params = Params { preamble: 2 };
+
+        // Every entry is valid, or the invalid one is no sum of a contiguous range.
+        assert_eq!(part2_with(&[1, 2, 3, 5, 8, 13], &params), None);
+        assert_eq!(part2_with(&[1, 2, 10], &params), None);
+        assert_eq!(part2_with(&[], &params), None);
+
+        let mut params = crate::params::Params::default();
+        params.set("day9.preamble", "2").unwrap();
+
+        assert!(matches!(
+            crate::params::solver(9, 2, &params)
+                .unwrap()
+                .run("1\n2\n3\n5\n8\n13\n"),
+            Err(solver::Error::NoSolution)
+        ));
+    }
 }
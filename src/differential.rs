@@ -0,0 +1,161 @@
+//! Differential testing, running every variant of a part on the same inputs and reporting
+//! the inputs they disagree on.
+
+use crate::registry;
+use crate::solver::Solver;
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+/// What a variant made of an input: its answer, or its error or panic message.
+pub type Outcome = Result<String, String>;
+
+pub fn outcome(solver: &dyn Solver, input: &str) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| solver.run(input))) {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(error)) => Err(error.to_string()),
+        Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+    }
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// The outcome of every solver on `input`, if they are not all the same.
+pub fn compare(solvers: &[&dyn Solver], input: &str) -> Option<Vec<(&'static str, Outcome)>> {
+    let outcomes = solvers
+        .iter()
+        .map(|solver| (solver.metadata().variant_name(), outcome(*solver, input)))
+        .collect::<Vec<_>>();
+
+    if outcomes.windows(2).all(|pair| pair[0].1 == pair[1].1) {
+        None
+    } else {
+        Some(outcomes)
+    }
+}
+
+/// Removes as many lines as possible from `input` while `keep` holds, first in large chunks,
+/// then line by line.
+pub fn minimise(input: &str, keep: impl Fn(&str) -> bool) -> String {
+    let mut lines = input.lines().collect::<Vec<_>>();
+    let mut chunk = (lines.len() / 2).max(1);
+
+    loop {
+        let mut start = 0;
+        let mut removed = false;
+
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate = [&lines[..start], &lines[end..]].concat();
+
+            if keep(&candidate.join("\n")) {
+                lines = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+
+        if !removed && chunk == 1 {
+            return lines.join("\n");
+        } else if !removed {
+            chunk /= 2;
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Disagreement {
+    pub day: u8,
+    pub part: u8,
+    /// The smallest input found on which the variants still disagree.
+    pub input: String,
+    pub outcomes: Vec<(&'static str, Outcome)>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {} part {}: the variants disagree on this input:",
+            self.day, self.part
+        )?;
+        writeln!(f, "{}", self.input)?;
+
+        for (variant, outcome) in &self.outcomes {
+            match outcome {
+                Ok(answer) => writeln!(f, "  {}: {}", variant, answer)?,
+                Err(error) => writeln!(f, "  {}: error: {}", variant, error)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs every variant of the part on each input, returning the number of inputs they agreed
+/// on, or the first disagreement, with its input minimised.
+pub fn check(
+    day: u8,
+    part: u8,
+    inputs: impl IntoIterator<Item = String>,
+) -> Result<usize, Disagreement> {
+    let solvers = registry().variants(day, part).collect::<Vec<_>>();
+    let mut checked = 0;
+
+    for input in inputs {
+        if compare(&solvers, &input).is_some() {
+            let input = minimise(&input, |candidate| compare(&solvers, candidate).is_some());
+            let outcomes = compare(&solvers, &input).unwrap();
+
+            return Err(Disagreement {
+                day,
+                part,
+                input,
+                outcomes,
+            });
+        }
+
+        checked += 1;
+    }
+
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate;
+
+    #[test]
+    fn variants_agree() {
+        for (day, part, size) in [(1, 1, 30), (1, 2, 30), (22, 2, 10)] {
+            let inputs = (0..100).map(|seed| generate::input(day, seed, size));
+            assert_eq!(check(day, part, inputs).unwrap(), 100);
+        }
+    }
+
+    #[test]
+    fn minimised_disagreement() {
+        let input = "1\n2\n3\n4\n5\n6\n7\n8";
+
+        assert_eq!(
+            minimise(input, |candidate| candidate.contains('3')
+                && candidate.contains('6')),
+            "3\n6"
+        );
+
+        let solvers = registry().variants(1, 1).collect::<Vec<_>>();
+
+        assert!(compare(&solvers, "1721\n299\n1").is_none());
+        assert!(compare(&solvers, "x").is_none());
+    }
+}
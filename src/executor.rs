@@ -0,0 +1,137 @@
+//! Runs many solvers on a pool of worker threads, reporting each result as soon as it is ready.
+
+use crate::differential::panic_message;
+use crate::solver::{Metadata, Solver};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The parts taking seconds rather than milliseconds on the actual inputs.
+pub const SLOW: [(u8, u8); 3] = [(15, 2), (23, 2), (25, 1)];
+
+pub fn is_slow(metadata: &Metadata) -> bool {
+    SLOW.contains(&(metadata.day, metadata.part))
+}
+
+pub struct Task {
+    pub solver: &'static dyn Solver,
+    /// The input, shared by the tasks of the same part.
+    pub input: Arc<str>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub metadata: &'static Metadata,
+    /// The answer, or the error or panic message.
+    pub answer: Result<String, String>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// The index of the worker that ran the task.
+    pub worker: usize,
+}
+
+fn run(task: &Task, worker: usize) -> Report {
+    let mut parse_time = Duration::ZERO;
+    let mut solve_time = Duration::ZERO;
+
+    let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+        let start = Instant::now();
+        let parsed = task.solver.parse(&task.input);
+        parse_time = start.elapsed();
+
+        let start = Instant::now();
+        let answer = task.solver.solve(&parsed?);
+        solve_time = start.elapsed();
+
+        answer
+    }));
+
+    Report {
+        metadata: task.solver.metadata(),
+        answer: match answer {
+            Ok(Ok(answer)) => Ok(answer),
+            Ok(Err(error)) => Err(error.to_string()),
+            Err(payload) => Err(format!("panicked: {}", panic_message(payload.as_ref()))),
+        },
+        parse_time,
+        solve_time,
+        worker,
+    }
+}
+
+/// Runs the tasks on `workers` threads, starting with the slow ones so that the others run
+/// alongside them, and calls `report` with each result in the order they finish.
+pub fn execute(
+    mut tasks: Vec<Task>,
+    workers: usize,
+    mut report: impl FnMut(&Report),
+) -> Vec<Report> {
+    tasks.sort_by_key(|task| !is_slow(task.solver.metadata()));
+
+    let queue = Mutex::new(tasks.into_iter());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for worker in 0..workers.max(1) {
+            let queue = &queue;
+            let sender = sender.clone();
+
+            scope.spawn(move || loop {
+                let task = queue.lock().unwrap().next();
+
+                match task {
+                    Some(task) => sender.send(run(&task, worker)).unwrap(),
+                    None => break,
+                }
+            });
+        }
+
+        drop(sender);
+
+        receiver.iter().inspect(|result| report(result)).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::example;
+    use crate::registry;
+
+    #[test]
+    fn reports() {
+        let day1: Arc<str> = Arc::from(example(1, "example"));
+        let day5: Arc<str> = Arc::from("");
+        let tasks = registry()
+            .all()
+            .filter(|solver| solver.metadata().day == 1)
+            .map(|solver| Task {
+                solver,
+                input: day1.clone(),
+            })
+            .chain(registry().variants(5, 1).map(|solver| Task {
+                solver,
+                input: day5.clone(),
+            }))
+            .collect::<Vec<_>>();
+
+        let mut reported = 0;
+        let reports = execute(tasks, 3, |_| reported += 1);
+
+        assert_eq!((reports.len(), reported), (9, 9));
+        assert!(reports.iter().all(|report| report.worker < 3));
+        assert!(reports
+            .iter()
+            .filter(|report| report.metadata.day == 1)
+            .all(|report| report.answer.is_ok()));
+        assert!(reports
+            .iter()
+            .find(|report| report.metadata.day == 5)
+            .unwrap()
+            .answer
+            .as_ref()
+            .unwrap_err()
+            .starts_with("panicked"));
+    }
+}
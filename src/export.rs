@@ -0,0 +1,467 @@
+//! Pictures of the simulation states and results, written to PBM, PPM or SVG files.
+
+use crate::animation::Animation;
+use crate::params::Params;
+use crate::parsing::ParseError;
+use crate::{day11, day12, day20, day24, day3};
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+/// A colour, as its red, green and blue components.
+pub type Rgb = [u8; 3];
+
+const WHITE: Rgb = [255, 255, 255];
+const BLACK: Rgb = [0, 0, 0];
+const GREEN: Rgb = [34, 139, 34];
+const LIGHT_GREEN: Rgb = [144, 238, 144];
+const RED: Rgb = [220, 20, 60];
+const DARK_RED: Rgb = [178, 34, 34];
+const BLUE: Rgb = [30, 144, 255];
+const STEEL_BLUE: Rgb = [70, 130, 180];
+const PALE_BLUE: Rgb = [220, 235, 250];
+const GREY: Rgb = [224, 224, 224];
+
+/// A picture made of pixels, from the top-left corner.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Raster {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Raster {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// The bounding box of the cells, the gaps between them filled with `background`.
+    pub fn from_cells(cells: impl IntoIterator<Item = ((i32, i32), Rgb)>, background: Rgb) -> Self {
+        let cells = cells.into_iter().collect::<Vec<_>>();
+        let (xs, ys) = cells
+            .iter()
+            .map(|&(position, _)| position)
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+        let (x_min, x_max) = (xs.iter().min(), xs.iter().max());
+        let (y_min, y_max) = (ys.iter().min(), ys.iter().max());
+
+        let (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) = (x_min, x_max, y_min, y_max)
+        else {
+            return Raster::new(0, 0, background);
+        };
+
+        let mut raster = Raster::new(
+            (x_max - x_min + 1) as usize,
+            (y_max - y_min + 1) as usize,
+            background,
+        );
+
+        for ((x, y), colour) in cells {
+            raster.set((x - x_min) as usize, (y - y_min) as usize, colour);
+        }
+
+        raster
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, colour: Rgb) {
+        self.pixels[y * self.width + x] = colour;
+    }
+
+    /// The raster with every pixel turned into a square of `factor` by `factor` pixels.
+    pub fn scaled(&self, factor: usize) -> Self {
+        let mut scaled = Raster::new(self.width * factor, self.height * factor, BLACK);
+
+        for y in 0..scaled.height {
+            for x in 0..scaled.width {
+                scaled.set(x, y, self.get(x / factor, y / factor));
+            }
+        }
+
+        scaled
+    }
+
+    /// Writes a binary PBM file, the dark pixels in black and the light ones in white.
+    pub fn write_pbm(&self, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "P4\n{} {}\n", self.width, self.height)?;
+
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            let mut bytes = vec![0u8; self.width.div_ceil(8)];
+
+            for (x, &[r, g, b]) in row.iter().enumerate() {
+                let luminance = 299 * r as u32 + 587 * g as u32 + 114 * b as u32;
+
+                if luminance < 128_000 {
+                    bytes[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+
+            out.write_all(&bytes)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a binary PPM file.
+    pub fn write_ppm(&self, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels.concat())
+    }
+}
+
+/// Writes the points joined by a line as an SVG file, with `y` going up, the start marked in
+/// green and the end in red.
+pub fn write_svg(points: &[(i32, i32)], out: &mut dyn Write) -> io::Result<()> {
+    let (x_min, x_max, y_min, y_max) =
+        points
+            .iter()
+            .fold((0, 0, 0, 0), |(x_min, x_max, y_min, y_max), &(x, y)| {
+                (x_min.min(x), x_max.max(x), y_min.min(y), y_max.max(y))
+            });
+    let margin = ((x_max - x_min).max(y_max - y_min) / 50).max(1);
+    let polyline = points
+        .iter()
+        .map(|&(x, y)| format!("{},{}", x, -y))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+        x_min - margin,
+        -y_max - margin,
+        x_max - x_min + 2 * margin,
+        y_max - y_min + 2 * margin
+    )?;
+    writeln!(
+        out,
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"steelblue\" stroke-width=\"1.5\" \
+         vector-effect=\"non-scaling-stroke\"/>",
+        polyline
+    )?;
+
+    for (point, colour) in [(points.first(), "green"), (points.last(), "red")] {
+        if let Some(&(x, y)) = point {
+            writeln!(
+                out,
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                x, -y, margin, colour
+            )?;
+        }
+    }
+
+    writeln!(out, "</svg>")
+}
+
+/// The file formats of the pictures.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    Pbm,
+    Ppm,
+    Svg,
+}
+
+impl Format {
+    /// The format named by the extension of the path, if any.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "pbm" => Some(Format::Pbm),
+            "ppm" => Some(Format::Ppm),
+            "svg" => Some(Format::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// A state or result of a simulation, as pixels or as a path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Picture {
+    Raster(Raster),
+    Path(Vec<(i32, i32)>),
+}
+
+impl Picture {
+    /// The formats the picture can be written in.
+    pub fn formats(&self) -> &'static [Format] {
+        match self {
+            Picture::Raster(_) => &[Format::Pbm, Format::Ppm],
+            Picture::Path(_) => &[Format::Svg],
+        }
+    }
+
+    pub fn write(&self, format: Format, out: &mut dyn Write) -> io::Result<()> {
+        match (self, format) {
+            (Picture::Raster(raster), Format::Pbm) => raster.write_pbm(out),
+            (Picture::Raster(raster), Format::Ppm) => raster.write_ppm(out),
+            (Picture::Path(points), Format::Svg) => write_svg(points, out),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot write the picture as {:?}", format),
+            )),
+        }
+    }
+}
+
+/// The parts with a picture.
+pub const PARTS: [(u8, u8); 8] = [
+    (3, 1),
+    (11, 1),
+    (11, 2),
+    (12, 1),
+    (12, 2),
+    (20, 2),
+    (24, 1),
+    (24, 2),
+];
+
+/// The picture of the part's result on the input with `params`, or `None` if it has none.
+pub fn picture(
+    day: u8,
+    part: u8,
+    input: &str,
+    params: &Params,
+) -> Option<Result<Picture, ParseError>> {
+    fn finished<A: Animation>(mut animation: A) -> A {
+        while animation.step() {}
+        animation
+    }
+
+    Some(match (day, part) {
+        (3, 1) => day3::parse(input).map(|map| toboggan(&map, &params.day3.slope)),
+        (11, 1) => {
+            day11::parse(input).map(|layout| seating(&finished(day11::part1_animation(&layout))))
+        }
+        (11, 2) => {
+            day11::parse(input).map(|layout| seating(&finished(day11::part2_animation(&layout))))
+        }
+        (12, 1) => day12::parse(input)
+            .map(|instructions| course(&finished(day12::part1_animation(&instructions)))),
+        (12, 2) => day12::parse(input)
+            .map(|instructions| course(&finished(day12::part2_animation(&instructions)))),
+        (20, 2) => day20::parse(input).map(|tiles_and_edges| {
+            let (image, monsters) = day20::sea_monsters(&tiles_and_edges);
+            sea_monsters(&image, &monsters)
+        }),
+        (24, 1) => day24::parse(input).map(|directions| {
+            exhibit(&day24::part2_animation(
+                &directions,
+                &day24::Params { days: 0 },
+            ))
+        }),
+        (24, 2) => day24::parse(input).map(|directions| {
+            exhibit(&finished(day24::part2_animation(
+                &directions,
+                &params.day24,
+            )))
+        }),
+        _ => return None,
+    })
+}
+
+/// The map, repeated as far to either side as the toboggan goes, with the trees in green and
+/// the squares of its path in red when a tree and in blue otherwise.
+fn toboggan(map: &day3::Map, slope: &day3::Slope) -> Picture {
+    let columns = map.columns().max(1);
+    let path = (0..)
+        .map(|step| (step * slope.x(), step * slope.y()))
+        .take_while(|&(_, y)| y < map.rows())
+        .collect::<Vec<_>>();
+    // The copies of the map to the left of the first one, for a path going left.
+    let left = path.last().map_or(0, |&(x, _)| (-x).max(0) + columns - 1) / columns;
+    let path = path
+        .into_iter()
+        .map(|(x, y)| (x + left * columns, y))
+        .collect::<Vec<_>>();
+    let width = path
+        .iter()
+        .map(|&(x, _)| x / columns + 1)
+        .max()
+        .unwrap_or(1)
+        * columns;
+    let mut raster = Raster::new(width as usize, map.rows() as usize, WHITE);
+    let trees = map
+        .trees()
+        .iter()
+        .map(|tree| (tree.x(), tree.y()))
+        .collect::<HashSet<_>>();
+
+    for &(x, y) in &trees {
+        for repeat in (x..width).step_by(columns as usize) {
+            raster.set(repeat as usize, y as usize, GREEN);
+        }
+    }
+
+    for (x, y) in path {
+        let colour = if trees.contains(&(x % columns, y)) {
+            RED
+        } else {
+            BLUE
+        };
+        raster.set(x as usize, y as usize, colour);
+    }
+
+    Picture::Raster(raster)
+}
+
+/// The floor in grey, the empty seats in green and the occupied ones in red.
+fn seating<T>(seating: &day11::Seating<T>) -> Picture
+where
+    T: crate::automaton::Topology<Position = day11::Position>,
+{
+    let layout = seating.layout();
+
+    Picture::Raster(Raster::from_cells(
+        layout.0.iter().map(|(position, tile)| {
+            let colour = match tile {
+                day11::TileKind::Floor => GREY,
+                day11::TileKind::EmptySeat => LIGHT_GREEN,
+                day11::TileKind::OccupiedSeat => DARK_RED,
+            };
+            ((position.x(), position.y()), colour)
+        }),
+        WHITE,
+    ))
+}
+
+/// The course of the ship, without the turns on the spot.
+fn course(voyage: &day12::Voyage) -> Picture {
+    let mut points = voyage
+        .course()
+        .iter()
+        .map(|position| (position.x(), position.y()))
+        .collect::<Vec<_>>();
+    points.dedup();
+
+    Picture::Path(points)
+}
+
+/// The water in pale blue, the waves in steel blue and the sea monsters in red.
+fn sea_monsters(image: &day20::Image, monsters: &HashSet<day20::Position>) -> Picture {
+    Picture::Raster(Raster::from_cells(
+        image.0.iter().map(|(position, &pixel)| {
+            let colour = match (monsters.contains(position), pixel) {
+                (true, _) => RED,
+                (false, true) => STEEL_BLUE,
+                (false, false) => PALE_BLUE,
+            };
+            ((position.x(), position.y()), colour)
+        }),
+        PALE_BLUE,
+    ))
+}
+
+/// The black tiles, two pixels wide, a row shifted by one pixel from the rows above and below.
+fn exhibit(exhibition: &day24::Exhibition) -> Picture {
+    Picture::Raster(Raster::from_cells(
+        exhibition.black_tiles().flat_map(|tile| {
+            let x = 2 * tile.x() + tile.z();
+            [((x, tile.z()), BLACK), ((x + 1, tile.z()), BLACK)]
+        }),
+        WHITE,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasters() {
+        let mut raster = Raster::from_cells(vec![((1, 1), BLACK), ((9, 2), RED)], WHITE);
+
+        assert_eq!((raster.width(), raster.height()), (9, 2));
+        raster.set(1, 1, GREY);
+
+        let mut pbm = Vec::new();
+        raster.write_pbm(&mut pbm).unwrap();
+        assert_eq!(pbm, b"P4\n9 2\n\x80\x00\x00\x80");
+
+        let mut ppm = Vec::new();
+        raster.scaled(2).write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n18 4\n255\n\0\0\0\0\0\0\xff\xff\xff"));
+        assert_eq!(ppm.len(), 12 + 18 * 4 * 3);
+    }
+
+    #[test]
+    fn paths() {
+        let mut svg = Vec::new();
+        write_svg(&[(0, 0), (10, 4), (10, -2)], &mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+
+        assert!(svg.contains("viewBox=\"-1 -5 12 8\""));
+        assert!(svg.contains("points=\"0,0 10,-4 10,2\""));
+        assert!(svg.contains("<circle cx=\"10\" cy=\"2\" r=\"1\" fill=\"red\"/>"));
+        assert_eq!(Format::from_path("ship.SVG"), Some(Format::Svg));
+        assert_eq!(Format::from_path("seats.png"), None);
+        assert!(Picture::Path(Vec::new())
+            .write(Format::Pbm, &mut Vec::new())
+            .is_err());
+    }
+
+    #[test]
+    fn pictures() {
+        let mut params = Params::default();
+        let map = "..##.\n#...#\n.#..#\n";
+
+        let Some(Ok(Picture::Raster(raster))) = picture(3, 1, map, &params) else {
+            panic!("expected a raster");
+        };
+        assert_eq!((raster.width(), raster.height()), (10, 3));
+        assert_eq!(
+            [raster.get(0, 0), raster.get(3, 1), raster.get(6, 2)],
+            [BLUE, BLUE, RED]
+        );
+        assert_eq!(raster.get(9, 2), GREEN);
+
+        params.set("day3.slope", "1,2").unwrap();
+        let Some(Ok(Picture::Raster(raster))) = picture(3, 1, map, &params) else {
+            panic!("expected a raster");
+        };
+        assert_eq!(raster.width(), 5);
+
+        params.set("day3.slope", "-3,1").unwrap();
+        let Some(Ok(Picture::Raster(raster))) = picture(3, 1, map, &params) else {
+            panic!("expected a raster");
+        };
+        assert_eq!(raster.width(), 15);
+        assert_eq!(
+            [raster.get(10, 0), raster.get(7, 1), raster.get(4, 2)],
+            [BLUE, BLUE, RED]
+        );
+
+        let Some(Ok(Picture::Raster(raster))) = picture(11, 1, "L.\nLL", &params) else {
+            panic!("expected a raster");
+        };
+        assert_eq!(raster.get(0, 1), DARK_RED);
+        assert_eq!(raster.get(1, 0), GREY);
+
+        assert_eq!(
+            picture(12, 1, "F10\nN3\nF7\nR90\nF11", &params),
+            Some(Ok(Picture::Path(vec![
+                (0, 0),
+                (10, 0),
+                (10, 3),
+                (17, 3),
+                (17, -8)
+            ])))
+        );
+        assert!(picture(24, 1, "x", &params).unwrap().is_err());
+        assert!(picture(1, 1, "1", &params).is_none());
+    }
+}
@@ -0,0 +1,1227 @@
+//! Seeded generators of valid random puzzle inputs, for stress, performance and differential
+//! testing.
+
+use crate::day11;
+use crate::random::Rng;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::iter;
+
+/// The size of the actual puzzle input of `day`, in the unit its generator takes.
+pub fn puzzle_size(day: u8) -> usize {
+    match day {
+        1 => 200,
+        2 => 1000,
+        3 => 323,
+        4 => 290,
+        5 => 850,
+        6 => 490,
+        7 => 594,
+        8 => 650,
+        9 => 1000,
+        10 => 100,
+        11 => 95,
+        12 => 780,
+        13 => 9,
+        14 => 580,
+        15 => 7,
+        16 => 240,
+        17 => 8,
+        18 => 370,
+        19 => 400,
+        20 => 12,
+        21 => 40,
+        22 => 25,
+        23 => 9,
+        24 => 500,
+        25 => 10_000_000,
+        _ => panic!("no puzzle on day {}", day),
+    }
+}
+
+/// A random valid input for `day`, always the same for the same `seed` and `size`.
+pub fn input(day: u8, seed: u64, size: usize) -> String {
+    let rng = &mut Rng::new(seed);
+
+    match day {
+        1 => expense_report(rng, size),
+        2 => password_database(rng, size),
+        3 => tree_map(rng, size),
+        4 => passports(rng, size),
+        5 => boarding_passes(rng, size),
+        6 => customs_answers(rng, size),
+        7 => bag_rules(rng, size),
+        8 => boot_code(rng, size),
+        9 => xmas_data(rng, size),
+        10 => adapters(rng, size),
+        11 => seat_layout(rng, size),
+        12 => navigation(rng, size),
+        13 => bus_notes(rng, size),
+        14 => initialization_program(rng, size),
+        15 => starting_numbers(rng, size),
+        16 => ticket_notes(rng, size),
+        17 => grid(rng, size, '#', 1, '.'),
+        18 => homework(rng, size),
+        19 => messages(rng, size),
+        20 => tiles(rng, size).0,
+        21 => foods(rng, size),
+        22 => decks(rng, size),
+        23 => cups(rng),
+        24 => tile_paths(rng, size),
+        25 => public_keys(rng, size),
+        _ => panic!("no puzzle on day {}", day),
+    }
+}
+
+fn letter(rng: &mut Rng) -> char {
+    (b'a' + rng.below(26) as u8) as char
+}
+
+fn word(rng: &mut Rng, min_len: i64, max_len: i64) -> String {
+    (0..rng.between(min_len, max_len))
+        .map(|_| letter(rng))
+        .collect()
+}
+
+/// A `size` by `size` grid, each cell `c` with a chance of `c_in_5` in 5, or `other`.
+fn grid(rng: &mut Rng, size: usize, c: char, c_in_5: u64, other: char) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..size.max(1))
+                .map(|_| if rng.chance(c_in_5, 5) { c } else { other })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+/// A `size` by `size` seat layout on which the seats settle with both rules, which they may not.
+fn seat_layout(rng: &mut Rng, size: usize) -> String {
+    loop {
+        let text = grid(rng, size, 'L', 4, '.');
+        let layout = day11::parse(&text).unwrap();
+
+        if day11::part1(&layout).is_some() && day11::part2(&layout).is_some() {
+            return text;
+        }
+    }
+}
+
+/// `size` distinct entries (at least 5), exactly one pair and one triple of which add up to
+/// 2020.
+fn expense_report(rng: &mut Rng, size: usize) -> String {
+    loop {
+        let pair = rng.between(1, 1009);
+        let first = rng.between(2, 1009);
+        let second = rng.between(1011 - first, 1009);
+        let small = [pair, first, second, 2020 - first - second];
+
+        // Entries above 1010 only add up to 2020 with smaller ones.
+        let mut fillers = (1011..=2020)
+            .filter(|&filler| {
+                small.iter().all(|&entry| filler + entry != 2020)
+                    && small
+                        .iter()
+                        .tuple_combinations()
+                        .all(|(a, b)| filler + a + b != 2020)
+            })
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut fillers);
+
+        let mut entries = small.to_vec();
+        entries.push(2020 - pair);
+        entries.extend(fillers.into_iter().take(size.saturating_sub(5)));
+        rng.shuffle(&mut entries);
+
+        let set = entries.iter().collect::<HashSet<_>>();
+        let pairs = entries
+            .iter()
+            .filter(|&&a| a < 2020 - a && set.contains(&(2020 - a)))
+            .count();
+        let triples = entries
+            .iter()
+            .tuple_combinations()
+            .filter(|&(&a, &b)| a < b && b < 2020 - a - b && set.contains(&(2020 - a - b)))
+            .count();
+
+        if set.len() == entries.len() && pairs == 1 && triples == 1 {
+            return entries.iter().join("\n");
+        }
+    }
+}
+
+/// `size` password entries.
+fn password_database(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let min = rng.between(1, 8);
+            let max = rng.between(min + 1, min + 10);
+            let character = letter(rng);
+            let password = (0..rng.between(1, max + 3))
+                .map(|_| {
+                    if rng.chance(1, 3) {
+                        character
+                    } else {
+                        letter(rng)
+                    }
+                })
+                .collect::<String>();
+
+            format!("{}-{} {}: {}", min, max, character, password)
+        })
+        .join("\n")
+}
+
+/// A map of `size` rows of 31 cells.
+fn tree_map(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..31)
+                .map(|_| if rng.chance(1, 4) { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+const EYE_COLORS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+/// `size` passports, some with missing or invalid fields.
+fn passports(rng: &mut Rng, size: usize) -> String {
+    let hex = |rng: &mut Rng| {
+        (0..6)
+            .map(|_| *rng.choose(b"0123456789abcdefz") as char)
+            .collect::<String>()
+    };
+
+    (0..size.max(1))
+        .map(|_| {
+            let valid = rng.chance(4, 5);
+            let mut year = |min, max| rng.between(min, max).to_string();
+            let fields = [
+                ("byr", year(1920, 2002), year(1900, 2030)),
+                ("iyr", year(2010, 2020), year(2000, 2030)),
+                ("eyr", year(2020, 2030), year(2010, 2040)),
+                (
+                    "hgt",
+                    if rng.chance(1, 2) {
+                        format!("{}cm", rng.between(150, 193))
+                    } else {
+                        format!("{}in", rng.between(59, 76))
+                    },
+                    format!("{}{}", rng.between(50, 200), rng.choose(&["cm", "in", ""])),
+                ),
+                ("hcl", format!("#{}", hex(rng)), hex(rng)),
+                (
+                    "ecl",
+                    rng.choose(&EYE_COLORS).to_string(),
+                    rng.choose(&["gmt", "lzr", "xry", "zzz"]).to_string(),
+                ),
+                (
+                    "pid",
+                    format!("{:09}", rng.below(1_000_000_000)),
+                    rng.below(100_000_000_000).to_string(),
+                ),
+                (
+                    "cid",
+                    rng.between(100, 350).to_string(),
+                    rng.between(100, 350).to_string(),
+                ),
+            ];
+
+            let mut passport = String::new();
+
+            for (key, value, invalid_value) in fields {
+                let present = if key == "cid" {
+                    rng.chance(1, 2)
+                } else {
+                    passport.is_empty() || rng.chance(9, 10)
+                };
+
+                if present {
+                    if !passport.is_empty() {
+                        passport.push(if rng.chance(1, 3) { '\n' } else { ' ' });
+                    }
+
+                    let value = if valid || rng.chance(2, 3) {
+                        value
+                    } else {
+                        invalid_value
+                    };
+                    passport.push_str(&format!("{}:{}", key, value));
+                }
+            }
+
+            passport
+        })
+        .join("\n\n")
+}
+
+/// The boarding passes of `size` consecutive seats (between 3 and 1000), all taken but one.
+fn boarding_passes(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(3, 1000) as i64;
+    let first = rng.between(0, 1023 - size);
+    let missing = rng.between(first + 1, first + size - 2);
+    let mut ids = (first..first + size)
+        .filter(|&id| id != missing)
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut ids);
+
+    ids.iter()
+        .map(|id| {
+            (0..10)
+                .rev()
+                .map(|bit| match (bit >= 3, id >> bit & 1) {
+                    (true, 0) => 'F',
+                    (true, _) => 'B',
+                    (false, 0) => 'L',
+                    (false, _) => 'R',
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+/// `size` groups of one to five people, who mostly answered the same questions.
+fn customs_answers(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let common = ('a'..='z')
+                .filter(|_| rng.chance(1, 4))
+                .collect::<HashSet<_>>();
+
+            (0..rng.between(1, 5))
+                .map(|_| {
+                    let mut answers = ('a'..='z')
+                        .filter(|c| rng.chance(if common.contains(c) { 9 } else { 1 }, 10))
+                        .collect::<Vec<_>>();
+
+                    if answers.is_empty() {
+                        answers.push(letter(rng));
+                    }
+
+                    rng.shuffle(&mut answers);
+                    answers.into_iter().collect::<String>()
+                })
+                .join("\n")
+        })
+        .join("\n\n")
+}
+
+const SHADES: [&str; 18] = [
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+    "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy",
+];
+
+const PRIMARY_COLORS: [&str; 33] = [
+    "aqua",
+    "beige",
+    "black",
+    "blue",
+    "bronze",
+    "brown",
+    "chartreuse",
+    "coral",
+    "crimson",
+    "cyan",
+    "fuchsia",
+    "gold",
+    "gray",
+    "green",
+    "indigo",
+    "lavender",
+    "lime",
+    "magenta",
+    "maroon",
+    "olive",
+    "orange",
+    "plum",
+    "purple",
+    "red",
+    "salmon",
+    "silver",
+    "tan",
+    "teal",
+    "tomato",
+    "turquoise",
+    "violet",
+    "white",
+    "yellow",
+];
+
+/// The rules of `size` bag colors (between 2 and 594), in nine levels where bags only contain
+/// bags of the next two levels, shiny gold bags being on the sixth, so that they have many
+/// ancestors and not too many bags inside.
+fn bag_rules(rng: &mut Rng, size: usize) -> String {
+    let mut colors = SHADES
+        .iter()
+        .cartesian_product(PRIMARY_COLORS.iter())
+        .map(|(shade, primary)| format!("{} {}", shade, primary))
+        .filter(|color| color != "shiny gold")
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut colors);
+    colors.truncate(size.clamp(2, 594) - 1);
+
+    let mut levels = colors
+        .iter()
+        .map(|color| (rng.between(0, 8), color.as_str()))
+        .collect::<Vec<_>>();
+    levels.push((5, "shiny gold"));
+
+    let mut rules = levels
+        .iter()
+        .map(|&(level, color)| {
+            let mut deeper = levels
+                .iter()
+                .filter(|&&(other_level, _)| (level + 1..=level + 2).contains(&other_level))
+                .map(|&(_, other)| other)
+                .collect::<Vec<_>>();
+            rng.shuffle(&mut deeper);
+            let kinds = if rng.chance(1, 10) {
+                0
+            } else {
+                rng.between(1, 4)
+            };
+            deeper.truncate(kinds as usize);
+
+            if deeper.is_empty() {
+                format!("{} bags contain no other bags.", color)
+            } else {
+                let contents = deeper
+                    .iter()
+                    .map(|inner| match rng.between(1, 5) {
+                        1 => format!("1 {} bag", inner),
+                        quantity => format!("{} {} bags", quantity, inner),
+                    })
+                    .join(", ");
+
+                format!("{} bags contain {}.", color, contents)
+            }
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut rules);
+
+    rules.join("\n")
+}
+
+/// A boot code of `size` instructions that loops, and terminates once its one faulty `jmp` is
+/// changed to a `nop`.
+///
+/// Before the faulty `jmp`, every `jmp` stays before it and every `nop` points backwards, so
+/// that no other change can get past it. Changed, it leads to the rest of the code, where
+/// every `jmp` goes forwards.
+fn boot_code(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2) as i64;
+    let faulty = rng.between(1, size - 1);
+    let mut executed = Vec::new();
+    let mut next = 0;
+    let mut instructions = Vec::new();
+
+    for i in 0..size {
+        let (operation, argument) = if i == faulty {
+            ("jmp", rng.choose(&executed) - i)
+        } else {
+            let end = if i < faulty { faulty } else { size };
+
+            match rng.below(4) {
+                0 | 1 => ("acc", rng.between(-50, 50)),
+                2 => ("nop", rng.between(-i, 0)),
+                _ => ("jmp", rng.between(1, (end - i).min(20))),
+            }
+        };
+
+        if i == next && i < faulty {
+            executed.push(i);
+            next = if operation == "jmp" {
+                i + argument
+            } else {
+                i + 1
+            };
+        }
+
+        instructions.push(format!("{} {:+}", operation, argument));
+    }
+
+    instructions.join("\n")
+}
+
+/// `size` numbers (at least 27) with a preamble of 25, the first invalid one adding up to a
+/// contiguous range of the ones before it.
+fn xmas_data(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(27);
+    let mut numbers = (1..=50).collect::<Vec<i64>>();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(25);
+
+    let invalid = rng.between(26, size as i64 - 1) as usize;
+
+    while numbers.len() < size {
+        let window = &numbers[numbers.len() - 25..];
+
+        let number = if numbers.len() == invalid {
+            let start = rng.below(numbers.len() as u64 - 1) as usize;
+            let end = rng.between(start as i64 + 2, (start + 5).min(numbers.len()) as i64);
+            let sum = numbers[start..end as usize].iter().sum::<i64>();
+
+            Some(sum).filter(|&sum| {
+                !window
+                    .iter()
+                    .any(|&first| 2 * first != sum && window.contains(&(sum - first)))
+            })
+        } else {
+            // Adding up the smallest numbers keeps them growing slowly enough.
+            let mut smallest = window.to_vec();
+            smallest.sort_unstable();
+            let (first, second) = (rng.choose(&smallest[..8]), rng.choose(&smallest[..8]));
+            Some(first + second).filter(|_| first != second)
+        };
+
+        numbers.extend(number);
+    }
+
+    numbers.iter().join("\n")
+}
+
+/// `size` adapters, in runs of one to five 1 jolt apart, the runs being 3 jolts apart.
+fn adapters(rng: &mut Rng, size: usize) -> String {
+    let mut joltages = Vec::new();
+    let mut joltage = 0;
+
+    while joltages.len() < size.max(1) {
+        for _ in 0..rng.between(1, 5) {
+            joltage += 1;
+            joltages.push(joltage);
+        }
+
+        joltage += 2;
+    }
+
+    joltages.truncate(size.max(1));
+    rng.shuffle(&mut joltages);
+
+    joltages.iter().join("\n")
+}
+
+/// `size` navigation instructions.
+fn navigation(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            if rng.chance(1, 4) {
+                format!("{}{}", rng.choose(&['L', 'R']), rng.choose(&[90, 180, 270]))
+            } else {
+                format!(
+                    "{}{}",
+                    rng.choose(&['N', 'S', 'E', 'W', 'F']),
+                    rng.between(1, 100)
+                )
+            }
+        })
+        .join("\n")
+}
+
+/// The notes on up to `size` buses with distinct prime IDs, whose product stays below 10^14
+/// so that the earliest timestamp can be computed in 64 bits.
+fn bus_notes(rng: &mut Rng, size: usize) -> String {
+    let mut primes = (5..1000)
+        .filter(|&n| (2..).take_while(|d| d * d <= n).all(|d| n % d != 0))
+        .collect::<Vec<i64>>();
+    rng.shuffle(&mut primes);
+
+    let mut product = 1;
+    let mut schedule = Vec::new();
+
+    for id in primes {
+        if product * id > 100_000_000_000_000 {
+            continue;
+        }
+
+        if !schedule.is_empty() {
+            schedule.extend(iter::repeat_n("x".to_string(), rng.between(0, 10) as usize));
+        }
+
+        product *= id;
+        schedule.push(id.to_string());
+
+        if schedule.len() - schedule.iter().filter(|id| *id == "x").count() == size.max(1) {
+            break;
+        }
+    }
+
+    format!("{}\n{}", rng.between(1000, 1_000_000), schedule.join(","))
+}
+
+/// An initialization program of `size` instructions, with at most 9 floating bits per mask.
+fn initialization_program(rng: &mut Rng, size: usize) -> String {
+    let mut instructions = Vec::new();
+
+    while instructions.len() < size.max(1) {
+        let floating = rng.between(0, 9);
+        let mut mask = (0..36)
+            .map(|i| match i < floating {
+                true => 'X',
+                false => *rng.choose(&['0', '1']),
+            })
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut mask);
+        instructions.push(format!("mask = {}", mask.into_iter().collect::<String>()));
+
+        for _ in 0..rng.between(1, 6) {
+            instructions.push(format!(
+                "mem[{}] = {}",
+                rng.between(0, 65535),
+                rng.between(0, 1_000_000_000)
+            ));
+        }
+    }
+
+    instructions.truncate(size.max(1));
+    instructions.join("\n")
+}
+
+/// `size` distinct starting numbers (between 2 and 21).
+fn starting_numbers(rng: &mut Rng, size: usize) -> String {
+    let mut numbers = (0..=20).collect::<Vec<_>>();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(size.clamp(2, 21));
+
+    numbers.iter().join(",")
+}
+
+const TICKET_FIELDS: [&str; 20] = [
+    "departure location",
+    "departure station",
+    "departure platform",
+    "departure track",
+    "departure date",
+    "departure time",
+    "arrival location",
+    "arrival station",
+    "arrival platform",
+    "arrival track",
+    "class",
+    "duration",
+    "price",
+    "route",
+    "row",
+    "seat",
+    "train",
+    "type",
+    "wagon",
+    "zone",
+];
+
+/// The notes on 20 fields and `size` nearby tickets, a quarter of them with an invalid value.
+///
+/// The values of each column fall into a band of its own, and every field accepts the bands
+/// from its rank on, so that the fields can be told apart one by one, last rank first.
+fn ticket_notes(rng: &mut Rng, size: usize) -> String {
+    let fields = TICKET_FIELDS.len() as i64;
+    let band = |rank| 100 + 40 * rank;
+
+    let mut ranks = (0..fields).collect::<Vec<_>>();
+    rng.shuffle(&mut ranks);
+
+    let rules = TICKET_FIELDS
+        .iter()
+        .zip(&ranks)
+        .map(|(field, &rank)| {
+            format!(
+                "{}: {}-{} or {}-{}",
+                field,
+                band(rank),
+                band(fields) - 1,
+                950 + 2 * rank,
+                951 + 2 * rank
+            )
+        })
+        .join("\n");
+
+    rng.shuffle(&mut ranks);
+
+    let ticket = |rng: &mut Rng, valid: bool| {
+        let mut values = ranks
+            .iter()
+            .map(|&rank| rng.between(band(rank), band(rank + 1) - 1))
+            .collect::<Vec<_>>();
+
+        if !valid {
+            values[rng.below(fields as u64) as usize] = rng.between(0, band(0) - 1);
+        }
+
+        values.iter().join(",")
+    };
+
+    let your_ticket = ticket(rng, true);
+    let nearby_tickets = (0..size.max(1))
+        .map(|_| {
+            let valid = rng.chance(3, 4);
+            ticket(rng, valid)
+        })
+        .join("\n");
+
+    format!(
+        "{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}",
+        rules, your_ticket, nearby_tickets
+    )
+}
+
+/// `size` expressions of at most 12 digits, so that they can't overflow whatever the
+/// precedence.
+fn homework(rng: &mut Rng, size: usize) -> String {
+    fn expression(rng: &mut Rng, depth: usize) -> (String, usize) {
+        let mut text = String::new();
+        let mut digits = 0;
+
+        for i in 0..rng.between(2, 5) {
+            if i > 0 {
+                let operator = rng.choose(&[" + ", " * "]);
+                text.push_str(operator);
+            }
+
+            if depth < 2 && rng.chance(1, 4) {
+                let (inner, inner_digits) = expression(rng, depth + 1);
+                text.push_str(&format!("({})", inner));
+                digits += inner_digits;
+            } else {
+                text.push_str(&rng.between(1, 9).to_string());
+                digits += 1;
+            }
+        }
+
+        (text, digits)
+    }
+
+    (0..size.max(1))
+        .map(|_| loop {
+            match expression(rng, 0) {
+                (expression, digits) if digits <= 12 => break expression,
+                _ => continue,
+            }
+        })
+        .join("\n")
+}
+
+/// Adds rules matching exactly `words`, all of the same length, to `rules` as rule `id`: one
+/// per prefix, choosing between the letters that can follow it.
+fn word_rules(
+    id: usize,
+    words: &[&str],
+    letters: [usize; 2],
+    ids: &mut impl Iterator<Item = usize>,
+    rules: &mut Vec<String>,
+) {
+    let alternatives = ['a', 'b']
+        .iter()
+        .zip(letters)
+        .filter_map(|(&letter, letter_id)| {
+            let suffixes = words
+                .iter()
+                .filter_map(|word| word.strip_prefix(letter))
+                .collect::<Vec<_>>();
+
+            match suffixes.first() {
+                None => None,
+                Some(&"") => Some(letter_id.to_string()),
+                Some(_) => {
+                    let suffixes_id = ids.next().unwrap();
+                    word_rules(suffixes_id, &suffixes, letters, ids, rules);
+                    Some(format!("{} {}", letter_id, suffixes_id))
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    rules.push(format!("{}: {}", id, alternatives.join(" | ")));
+}
+
+/// Rules where 42 and 31 match disjoint sets of 5 letter words, and `size` messages, some
+/// matching rule 0, some only with the looping rules 8 and 11, and some neither.
+fn messages(rng: &mut Rng, size: usize) -> String {
+    let mut words = (0..32)
+        .map(|n| {
+            (0..5)
+                .map(|bit| if n >> bit & 1 == 1 { 'b' } else { 'a' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut words);
+
+    let words = words.iter().map(String::as_str).collect::<Vec<_>>();
+    let (words_42, words_31) = words.split_at(rng.between(1, 31) as usize);
+
+    let mut ids = (1..).filter(|id| ![8, 11, 31, 42].contains(id));
+    let letters = [ids.next().unwrap(), ids.next().unwrap()];
+    let mut rules = vec![
+        "0: 8 11".to_string(),
+        "8: 42".to_string(),
+        "11: 42 31".to_string(),
+        format!("{}: \"a\"", letters[0]),
+        format!("{}: \"b\"", letters[1]),
+    ];
+    word_rules(42, words_42, letters, &mut ids, &mut rules);
+    word_rules(31, words_31, letters, &mut ids, &mut rules);
+    rng.shuffle(&mut rules);
+
+    let messages = (0..size.max(1))
+        .map(|_| match rng.below(3) {
+            0 => [words_42, words_42, words_31]
+                .iter()
+                .map(|words| *rng.choose(words))
+                .collect::<String>(),
+            1 => {
+                let count_31 = rng.between(1, 3);
+                let count_42 = count_31 + rng.between(1, 3);
+
+                let mut message = (0..count_42)
+                    .map(|_| *rng.choose(words_42))
+                    .collect::<String>();
+                message.extend((0..count_31).map(|_| *rng.choose(words_31)));
+                message
+            }
+            _ => (0..rng.between(1, 6))
+                .map(|_| *rng.choose(&words))
+                .collect::<String>(),
+        })
+        .join("\n");
+
+    format!("{}\n\n{}", rules.join("\n"), messages)
+}
+
+const SEA_MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+/// The pixels of the sea monster in each of its 8 orientations, the first one unturned.
+fn sea_monster_orientations() -> Vec<Vec<(usize, usize)>> {
+    let monster = SEA_MONSTER
+        .iter()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.match_indices('#')
+                .map(move |(x, _)| (x as i64, y as i64))
+        })
+        .collect::<Vec<_>>();
+
+    // Flipped or not, then turned a quarter from none to three times.
+    (0..8)
+        .map(|orientation| {
+            let turned = monster
+                .iter()
+                .map(|&(x, y)| {
+                    let flipped = if orientation < 4 { (x, y) } else { (-x, y) };
+                    (0..orientation % 4).fold(flipped, |(x, y), _| (-y, x))
+                })
+                .collect::<Vec<_>>();
+            let min_x = turned.iter().map(|&(x, _)| x).min().unwrap();
+            let min_y = turned.iter().map(|&(_, y)| y).min().unwrap();
+
+            turned
+                .iter()
+                .map(|&(x, y)| ((x - min_x) as usize, (y - min_y) as usize))
+                .collect()
+        })
+        .collect()
+}
+
+/// The pixels of the sea monsters showing in the image, whichever way it is turned, but those
+/// planted unturned at the `planted` top-left corners.
+fn stray_monsters(
+    image: &[Vec<bool>],
+    orientations: &[Vec<(usize, usize)>],
+    planted: &HashSet<(usize, usize)>,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut strays = Vec::new();
+
+    for (orientation, monster) in orientations.iter().enumerate() {
+        let width = monster.iter().map(|&(x, _)| x).max().unwrap() + 1;
+        let height = monster.iter().map(|&(_, y)| y).max().unwrap() + 1;
+
+        for (y, x) in (0..=image.len() - height).cartesian_product(0..=image.len() - width) {
+            let pixels = monster
+                .iter()
+                .map(|&(dx, dy)| (x + dx, y + dy))
+                .collect::<Vec<_>>();
+
+            if pixels.iter().all(|&(x, y)| image[y][x])
+                && !(orientation == 0 && planted.contains(&(x, y)))
+            {
+                strays.push(pixels);
+            }
+        }
+    }
+
+    strays
+}
+
+/// `size` by `size` tiles (between 3 and 12, as 10 pixel edges can't tell more apart) cut out
+/// of an image hiding sea monsters, each rotated and flipped at random, and the number of `#`
+/// pixels that are not part of a sea monster.
+fn tiles(rng: &mut Rng, size: usize) -> (String, usize) {
+    let side = size.clamp(3, 12);
+    let image_width = 8 * side;
+    let orientations = sea_monster_orientations();
+
+    let (image, monsters) = 'image: loop {
+        let mut image = (0..image_width)
+            .map(|_| {
+                (0..image_width)
+                    .map(|_| rng.chance(1, 3))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut planted = HashSet::new();
+        let mut monsters = HashSet::new();
+
+        for _ in 0..side * side / 2 {
+            let x = rng.below((image_width - 19) as u64) as usize;
+            let y = rng.below((image_width - 2) as u64) as usize;
+            let pixels = orientations[0]
+                .iter()
+                .map(|&(dx, dy)| (x + dx, y + dy))
+                .collect::<Vec<_>>();
+
+            if pixels.iter().all(|pixel| !monsters.contains(pixel)) {
+                for &(x, y) in &pixels {
+                    image[y][x] = true;
+                }
+
+                planted.insert((x, y));
+                monsters.extend(pixels);
+            }
+        }
+
+        // Sea monsters showing by chance would change which way up the image is or how rough
+        // the water is, so their background pixels are drawn again until none show, or the
+        // whole image if one is made of planted pixels only.
+        loop {
+            let strays = stray_monsters(&image, &orientations, &planted);
+
+            if strays.is_empty() {
+                break 'image (image, monsters);
+            }
+
+            for stray in strays {
+                let background = stray
+                    .into_iter()
+                    .filter(|pixel| !monsters.contains(pixel))
+                    .collect::<Vec<_>>();
+
+                if background.is_empty() {
+                    continue 'image;
+                }
+
+                for (x, y) in background {
+                    image[y][x] = rng.chance(1, 3);
+                }
+            }
+        }
+    };
+    let roughness = image.iter().flatten().filter(|&&pixel| pixel).count() - monsters.len();
+
+    // Tiles share their edges with their neighbours, so that the tile in row `i` and column
+    // `j` spans the pixels from `9 * i` to `9 * i + 9` down and `9 * j` to `9 * j + 9` across.
+    let width = 9 * side + 1;
+    let mut pixels = vec![vec![false; width]; width];
+
+    for (y, row) in image.iter().enumerate() {
+        for (x, &pixel) in row.iter().enumerate() {
+            pixels[9 * (y / 8) + 1 + y % 8][9 * (x / 8) + 1 + x % 8] = pixel;
+        }
+    }
+
+    for y in (0..width).step_by(9) {
+        for x in (0..width).step_by(9) {
+            pixels[y][x] = rng.chance(1, 2);
+        }
+    }
+
+    let edges = (0..width).step_by(9).flat_map(|line| {
+        (0..width - 1).step_by(9).flat_map(move |start| {
+            let across = (0..10).map(move |i| (line, start + i)).collect::<Vec<_>>();
+            let down = (0..10).map(move |i| (start + i, line)).collect::<Vec<_>>();
+            [across, down]
+        })
+    });
+    let mut used_edges = HashSet::new();
+
+    for edge in edges {
+        loop {
+            for &(y, x) in &edge[1..9] {
+                pixels[y][x] = rng.chance(1, 2);
+            }
+
+            let bits = edge.iter().map(|&(y, x)| pixels[y][x]).collect::<Vec<_>>();
+            let reversed = bits.iter().rev().copied().collect::<Vec<_>>();
+
+            if bits != reversed && !used_edges.contains(&reversed) && used_edges.insert(bits) {
+                break;
+            }
+        }
+    }
+
+    let mut ids = (1000..10_000).collect::<Vec<_>>();
+    rng.shuffle(&mut ids);
+
+    let mut tiles = (0..side)
+        .cartesian_product(0..side)
+        .zip(ids)
+        .map(|((i, j), id)| {
+            let mut tile = pixels[9 * i..=9 * i + 9]
+                .iter()
+                .map(|row| row[9 * j..=9 * j + 9].to_vec())
+                .collect::<Vec<_>>();
+
+            for _ in 0..rng.below(4) {
+                tile = (0..10)
+                    .map(|y| (0..10).map(|x| tile[9 - x][y]).collect())
+                    .collect();
+            }
+
+            if rng.chance(1, 2) {
+                tile.iter_mut().for_each(|row| row.reverse());
+            }
+
+            let rows = tile
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&pixel| if pixel { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .join("\n");
+
+            format!("Tile {}:\n{}", id, rows)
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut tiles);
+
+    (tiles.join("\n\n"), roughness)
+}
+
+/// The day 20 input `input(20, seed, size)` generates, with its water roughness.
+pub fn tiles_with_roughness(seed: u64, size: usize) -> (String, usize) {
+    tiles(&mut Rng::new(seed), size)
+}
+
+const ALLERGENS: [&str; 9] = [
+    "dairy",
+    "eggs",
+    "fish",
+    "nuts",
+    "peanuts",
+    "sesame",
+    "shellfish",
+    "soy",
+    "wheat",
+];
+
+/// Whether the ingredient of each allergen can be found by elimination.
+fn resolvable(foods: &[(Vec<&str>, Vec<&str>)]) -> bool {
+    let mut candidates = HashMap::<&str, HashSet<&str>>::new();
+
+    for (ingredients, allergens) in foods {
+        for allergen in allergens {
+            let ingredients = ingredients.iter().copied().collect::<HashSet<_>>();
+
+            candidates
+                .entry(allergen)
+                .and_modify(|candidates| candidates.retain(|c| ingredients.contains(c)))
+                .or_insert(ingredients);
+        }
+    }
+
+    while !candidates.is_empty() {
+        let resolved = match candidates.iter().find(|(_, c)| c.len() == 1) {
+            Some((&allergen, ingredients)) => (allergen, *ingredients.iter().next().unwrap()),
+            None => return false,
+        };
+
+        candidates.remove(resolved.0);
+
+        for ingredients in candidates.values_mut() {
+            ingredients.remove(resolved.1);
+        }
+    }
+
+    true
+}
+
+/// `size` foods, with up to 8 allergens whose ingredients can be found by elimination.
+fn foods(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+
+    loop {
+        let mut pool = HashSet::new();
+
+        while pool.len() < (size * 5).clamp(20, 200) {
+            pool.insert(word(rng, 3, 8));
+        }
+
+        let mut pool = pool.into_iter().collect::<Vec<_>>();
+        pool.sort_unstable();
+        rng.shuffle(&mut pool);
+
+        let mut allergens = ALLERGENS.to_vec();
+        rng.shuffle(&mut allergens);
+        allergens.truncate((size / 5).clamp(1, 8));
+
+        let sources = allergens
+            .iter()
+            .zip(&pool)
+            .map(|(&allergen, ingredient)| (allergen, ingredient.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        let foods = (0..size)
+            .map(|_| {
+                let mut listed = allergens
+                    .iter()
+                    .copied()
+                    .filter(|_| rng.chance(1, 3))
+                    .collect::<Vec<_>>();
+
+                if listed.is_empty() {
+                    listed.push(*rng.choose(&allergens));
+                }
+
+                let mut ingredients = pool
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|ingredient| {
+                        listed
+                            .iter()
+                            .any(|allergen| sources[allergen] == *ingredient)
+                            || rng.chance(1, 5)
+                    })
+                    .collect::<Vec<_>>();
+                rng.shuffle(&mut ingredients);
+
+                (ingredients, listed)
+            })
+            .collect::<Vec<_>>();
+
+        if resolvable(&foods) {
+            return foods
+                .iter()
+                .map(|(ingredients, allergens)| {
+                    format!(
+                        "{} (contains {})",
+                        ingredients.join(" "),
+                        allergens.join(", ")
+                    )
+                })
+                .join("\n");
+        }
+    }
+}
+
+/// Two decks of `size` cards each.
+fn decks(rng: &mut Rng, size: usize) -> String {
+    let mut cards = (1..=2 * size.max(1)).collect::<Vec<_>>();
+    rng.shuffle(&mut cards);
+    let (player1, player2) = cards.split_at(cards.len() / 2);
+
+    format!(
+        "Player 1:\n{}\n\nPlayer 2:\n{}",
+        player1.iter().join("\n"),
+        player2.iter().join("\n")
+    )
+}
+
+/// The nine cups, in any order.
+fn cups(rng: &mut Rng) -> String {
+    let mut cups = (1..=9).collect::<Vec<_>>();
+    rng.shuffle(&mut cups);
+
+    cups.iter().join("")
+}
+
+/// `size` paths of up to 20 steps.
+fn tile_paths(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            (0..rng.between(1, 20))
+                .map(|_| *rng.choose(&["e", "se", "sw", "w", "nw", "ne"]))
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+/// The public keys of a card and a door with loop sizes of up to `size`.
+fn public_keys(rng: &mut Rng, size: usize) -> String {
+    let mut public_key = || {
+        let mut loop_size = rng.between(1, size.clamp(1, 20_201_226) as i64);
+        let (mut key, mut power) = (1_u64, 7);
+
+        while loop_size > 0 {
+            if loop_size % 2 == 1 {
+                key = key * power % 20_201_227;
+            }
+
+            power = power * power % 20_201_227;
+            loop_size /= 2;
+        }
+
+        key
+    };
+
+    format!("{}\n{}", public_key(), public_key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn inputs_are_valid() {
+        for day in 1..=25 {
+            let size = (puzzle_size(day) / 10).max(3);
+
+            for seed in 0..3 {
+                let input = input(day, seed, size);
+
+                for part in 1..=2 {
+                    // Too slow without optimisations.
+                    if (day, part) == (15, 2) || (day, part) == (23, 2) {
+                        continue;
+                    }
+
+                    for solver in registry().variants(day, part) {
+                        let answer = solver.run(&input);
+
+                        assert!(
+                            answer.is_ok(),
+                            "{} failed on\n{}",
+                            solver.metadata().name,
+                            input
+                        );
+
+                        if (day, part) == (20, 2) {
+                            let roughness = tiles_with_roughness(seed, size).1;
+                            assert_eq!(answer.unwrap(), roughness.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        for day in 1..=25 {
+            assert_eq!(input(day, 7, 10), input(day, 7, 10));
+            assert_ne!(input(day, 7, 10), input(day, 8, 10));
+        }
+    }
+
+    #[test]
+    fn puzzle_sized_tiles() {
+        let (input, roughness) = tiles_with_roughness(0, puzzle_size(20));
+
+        assert_eq!(input.matches("Tile").count(), 144);
+
+        for solver in registry().variants(20, 2) {
+            assert_eq!(solver.run(&input).unwrap(), roughness.to_string());
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Integer vectors of any dimension, shared by the grid-based days.
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Vector<const N: usize>(pub [i32; N]);
+
+pub type Vector2 = Vector<2>;
+pub type Vector3 = Vector<3>;
+pub type Vector4 = Vector<4>;
+
+impl<const N: usize> Vector<N> {
+    pub const ZERO: Self = Vector([0; N]);
+
+    pub fn manhattan_length(self) -> i32 {
+        self.0.iter().map(|coordinate| coordinate.abs()).sum()
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> i32 {
+        (self - other).manhattan_length()
+    }
+
+    /// Rotates by `quarter_turns` in the plane of the `from` and `to` axes; a positive quarter
+    /// turn takes the `from` axis to the `to` axis.
+    pub fn rotate(mut self, from: usize, to: usize, quarter_turns: i32) -> Self {
+        for _ in 0..quarter_turns.rem_euclid(4) {
+            let (a, b) = (self[from], self[to]);
+            self[from] = -b;
+            self[to] = a;
+        }
+
+        self
+    }
+
+    /// Mirrors the vector across the hyperplane orthogonal to `axis`.
+    pub fn reflect(mut self, axis: usize) -> Self {
+        self[axis] = -self[axis];
+        self
+    }
+
+    /// The `3^N - 1` unit steps to the touching cells, diagonals included.
+    pub fn moore_directions() -> impl Iterator<Item = Self> {
+        (0..3_usize.pow(N as u32)).filter_map(|mut index| {
+            let mut direction = Self::ZERO;
+
+            for coordinate in direction.0.iter_mut() {
+                *coordinate = (index % 3) as i32 - 1;
+                index /= 3;
+            }
+
+            Some(direction).filter(|&direction| direction != Self::ZERO)
+        })
+    }
+
+    /// The `2N` unit steps along the axes.
+    pub fn axis_directions() -> impl Iterator<Item = Self> {
+        (0..2 * N).map(|index| {
+            let mut direction = Self::ZERO;
+            direction[index / 2] = if index % 2 == 0 { 1 } else { -1 };
+            direction
+        })
+    }
+
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        Self::moore_directions().map(move |direction| self + direction)
+    }
+
+    pub fn axis_neighbours(self) -> impl Iterator<Item = Self> {
+        Self::axis_directions().map(move |direction| self + direction)
+    }
+}
+
+impl Vector2 {
+    pub fn x(self) -> i32 {
+        self[0]
+    }
+
+    pub fn y(self) -> i32 {
+        self[1]
+    }
+}
+
+impl Vector3 {
+    /// The six steps between hexagons in cube coordinates, whose sum is always zero.
+    pub const HEX_DIRECTIONS: [Self; 6] = [
+        Vector([1, -1, 0]),
+        Vector([0, -1, 1]),
+        Vector([-1, 0, 1]),
+        Vector([-1, 1, 0]),
+        Vector([0, 1, -1]),
+        Vector([1, 0, -1]),
+    ];
+
+    pub fn x(self) -> i32 {
+        self[0]
+    }
+
+    pub fn y(self) -> i32 {
+        self[1]
+    }
+
+    pub fn z(self) -> i32 {
+        self[2]
+    }
+
+    pub fn hex_neighbours(self) -> impl Iterator<Item = Self> {
+        Self::HEX_DIRECTIONS
+            .iter()
+            .map(move |&direction| self + direction)
+    }
+}
+
+impl Vector4 {
+    pub fn x(self) -> i32 {
+        self[0]
+    }
+
+    pub fn y(self) -> i32 {
+        self[1]
+    }
+
+    pub fn z(self) -> i32 {
+        self[2]
+    }
+
+    pub fn w(self) -> i32 {
+        self[3]
+    }
+}
+
+impl From<(i32, i32)> for Vector2 {
+    fn from((x, y): (i32, i32)) -> Self {
+        Vector([x, y])
+    }
+}
+
+impl From<(i32, i32, i32)> for Vector3 {
+    fn from((x, y, z): (i32, i32, i32)) -> Self {
+        Vector([x, y, z])
+    }
+}
+
+impl From<(i32, i32, i32, i32)> for Vector4 {
+    fn from((x, y, z, w): (i32, i32, i32, i32)) -> Self {
+        Vector([x, y, z, w])
+    }
+}
+
+impl<const N: usize> Index<usize> for Vector<N> {
+    type Output = i32;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Vector<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Vector<N> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<const N: usize> AddAssign for Vector<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (coordinate, delta) in self.0.iter_mut().zip(rhs.0.iter()) {
+            *coordinate += delta;
+        }
+    }
+}
+
+impl<const N: usize> Sub for Vector<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<const N: usize> SubAssign for Vector<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self += -rhs;
+    }
+}
+
+impl<const N: usize> Neg for Vector<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self * -1
+    }
+}
+
+impl<const N: usize> Mul<i32> for Vector<N> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: i32) -> Self::Output {
+        for coordinate in self.0.iter_mut() {
+            *coordinate *= rhs;
+        }
+
+        self
+    }
+}
+
+/// A vector is serialised as the tuple of its coordinates.
+impl<const N: usize> Serialize for Vector<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(N)?;
+
+        for coordinate in &self.0 {
+            tuple.serialize_element(coordinate)?;
+        }
+
+        tuple.end()
+    }
+}
+
+struct VectorVisitor<const N: usize>(PhantomData<Vector<N>>);
+
+impl<'de, const N: usize> Visitor<'de> for VectorVisitor<N> {
+    type Value = Vector<N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} coordinates", N)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut vector = Vector::ZERO;
+
+        for i in 0..N {
+            vector[i] = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+
+        Ok(vector)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Vector<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(N, VectorVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a: Vector3 = (1, -2, 3).into();
+        let b: Vector3 = (4, 5, -6).into();
+
+        assert_eq!(a + b, (5, 3, -3).into());
+        assert_eq!(a - b, (-3, -7, 9).into());
+        assert_eq!(a * 2, (2, -4, 6).into());
+        assert_eq!(a.manhattan_length(), 6);
+        assert_eq!(a.manhattan_distance(b), 19);
+    }
+
+    #[test]
+    fn rotation_and_reflection() {
+        let v: Vector2 = (3, 1).into();
+
+        assert_eq!(v.rotate(0, 1, 1), (-1, 3).into());
+        assert_eq!(v.rotate(0, 1, -1), (1, -3).into());
+        assert_eq!(v.rotate(0, 1, 6), v * -1);
+        assert_eq!(v.rotate(1, 0, 1), v.rotate(0, 1, 3));
+        assert_eq!(v.reflect(0), (-3, 1).into());
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        assert_eq!(Vector2::ZERO.neighbours().count(), 8);
+        assert_eq!(Vector3::ZERO.neighbours().count(), 26);
+        assert_eq!(Vector4::ZERO.neighbours().count(), 80);
+        assert!(Vector4::moore_directions().all(|direction| direction != Vector4::ZERO));
+        assert_eq!(
+            Vector2::ZERO.axis_neighbours().collect::<Vec<_>>(),
+            vec![(1, 0).into(), (-1, 0).into(), (0, 1).into(), (0, -1).into()]
+        );
+        assert!(Vector3::ZERO
+            .hex_neighbours()
+            .all(|neighbour| neighbour.0.iter().sum::<i32>() == 0
+                && neighbour.manhattan_length() == 2));
+    }
+
+    #[test]
+    fn serialisation() {
+        let v: Vector3 = (1, -2, 3).into();
+
+        assert_eq!(serde_json::to_string(&v).unwrap(), "[1,-2,3]");
+        assert_eq!(serde_json::from_str::<Vector3>("[1,-2,3]").unwrap(), v);
+        assert!(serde_json::from_str::<Vector3>("[1,-2]").is_err());
+        assert!(serde_json::from_str::<Vector3>("[1,-2,3,4]").is_err());
+        assert_eq!(
+            bincode::deserialize::<Vector3>(&bincode::serialize(&v).unwrap()).unwrap(),
+            v
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! The puzzle inputs: those of several profiles side by side in a local directory, and the
+//! worked examples of the puzzles as named fixtures.
+//!
+//! The default profile is the `dayN.txt` files of the directory itself, where cargo-aoc puts
+//! them, and every other profile is a subdirectory of `dayN.txt` files, such as `alice/day1.txt`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A worked example of a puzzle, with its answers when the puzzle gives them.
+#[derive(Debug)]
+pub struct Fixture {
+    pub day: u8,
+    pub name: &'static str,
+    pub input: &'static str,
+    pub answers: [Option<&'static str>; 2],
+}
+
+macro_rules! fixture {
+    ($day:literal, $name:literal, $file:literal, $part1:expr, $part2:expr) => {
+        Fixture {
+            day: $day,
+            name: $name,
+            input: include_str!(concat!("../fixtures/", $file)),
+            answers: [$part1, $part2],
+        }
+    };
+}
+
+/// The worked examples, at least one named `example` per day.
+pub const FIXTURES: [Fixture; 30] = [
+    fixture!(1, "example", "day1.txt", Some("514579"), Some("241861950")),
+    fixture!(2, "example", "day2.txt", Some("2"), Some("1")),
+    fixture!(3, "example", "day3.txt", Some("7"), Some("336")),
+    fixture!(4, "example", "day4.txt", Some("2"), None),
+    fixture!(5, "example", "day5.txt", Some("820"), None),
+    fixture!(6, "example", "day6.txt", Some("11"), Some("6")),
+    fixture!(7, "example", "day7.txt", Some("4"), Some("32")),
+    fixture!(7, "part2", "day7-part2.txt", None, Some("126")),
+    fixture!(8, "example", "day8.txt", Some("5"), Some("8")),
+    // The example has a preamble of 5 numbers rather than 25.
+    fixture!(9, "example", "day9.txt", None, None),
+    fixture!(10, "example", "day10.txt", Some("35"), Some("8")),
+    fixture!(10, "larger", "day10-larger.txt", Some("220"), Some("19208")),
+    fixture!(11, "example", "day11.txt", Some("37"), Some("26")),
+    fixture!(12, "example", "day12.txt", Some("25"), Some("286")),
+    fixture!(13, "example", "day13.txt", Some("295"), Some("1068781")),
+    fixture!(14, "example", "day14.txt", Some("165"), None),
+    fixture!(14, "part2", "day14-part2.txt", None, Some("208")),
+    fixture!(15, "example", "day15.txt", Some("436"), Some("175594")),
+    fixture!(16, "example", "day16.txt", Some("71"), None),
+    fixture!(16, "part2", "day16-part2.txt", None, None),
+    fixture!(17, "example", "day17.txt", Some("112"), Some("848")),
+    fixture!(18, "example", "day18.txt", Some("26457"), Some("694173")),
+    fixture!(19, "example", "day19.txt", Some("2"), None),
+    fixture!(19, "part2", "day19-part2.txt", Some("3"), Some("12")),
+    fixture!(
+        20,
+        "example",
+        "day20.txt",
+        Some("20899048083289"),
+        Some("273")
+    ),
+    fixture!(
+        21,
+        "example",
+        "day21.txt",
+        Some("5"),
+        Some("mxmxvkd,sqjhc,fvjkl")
+    ),
+    fixture!(22, "example", "day22.txt", Some("306"), Some("291")),
+    fixture!(
+        23,
+        "example",
+        "day23.txt",
+        Some("67384529"),
+        Some("149245887792")
+    ),
+    fixture!(24, "example", "day24.txt", Some("10"), Some("2208")),
+    fixture!(25, "example", "day25.txt", Some("14897079"), None),
+];
+
+/// The example of the day named `name`, if any.
+pub fn fixture(day: u8, name: &str) -> Option<&'static Fixture> {
+    FIXTURES
+        .iter()
+        .find(|fixture| fixture.day == day && fixture.name == name)
+}
+
+/// The example of the day giving the answer to the part, the one named `example` if it does.
+pub fn part_fixture(day: u8, part: u8) -> Option<&'static Fixture> {
+    FIXTURES
+        .iter()
+        .filter(|fixture| fixture.day == day && fixture.answers[usize::from(part) - 1].is_some())
+        .min_by_key(|fixture| fixture.name != "example")
+}
+
+/// The input of the example of the day named `name`.
+///
+/// # Panics
+///
+/// Panics if there is no such example, which is only meant to happen in tests.
+pub fn example(day: u8, name: &str) -> &'static str {
+    fixture(day, name)
+        .unwrap_or_else(|| panic!("no example {:?} for day {}", name, day))
+        .input
+}
+
+/// The profile of the inputs of the directory itself.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The profile of the `example` fixtures, whatever the directory.
+pub const EXAMPLE_PROFILE: &str = "example";
+
+/// The inputs of a directory, by day and profile.
+#[derive(Clone, Debug)]
+pub struct Inputs {
+    dir: PathBuf,
+}
+
+impl Inputs {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Inputs { dir: dir.into() }
+    }
+
+    fn profile_dir(&self, profile: &str) -> PathBuf {
+        match profile {
+            DEFAULT_PROFILE => self.dir.clone(),
+            _ => self.dir.join(profile),
+        }
+    }
+
+    fn has_inputs(dir: &Path) -> bool {
+        (1..=25).any(|day| dir.join(format!("day{}.txt", day)).is_file())
+    }
+
+    /// The profiles with at least one input, the default one first and the examples last.
+    pub fn profiles(&self) -> Vec<String> {
+        let mut profiles = fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| Inputs::has_inputs(&entry.path()))
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name != DEFAULT_PROFILE && name != EXAMPLE_PROFILE)
+            .collect::<Vec<_>>();
+        profiles.sort();
+
+        if Inputs::has_inputs(&self.dir) {
+            profiles.insert(0, DEFAULT_PROFILE.to_string());
+        }
+
+        profiles.push(EXAMPLE_PROFILE.to_string());
+        profiles
+    }
+
+    /// The file of the day's input in the profile, or `None` for the examples.
+    pub fn path(&self, day: u8, profile: &str) -> Option<PathBuf> {
+        (profile != EXAMPLE_PROFILE)
+            .then(|| self.profile_dir(profile).join(format!("day{}.txt", day)))
+    }
+
+    /// Reads the day's input in the profile.
+    pub fn read(&self, day: u8, profile: &str) -> io::Result<String> {
+        match self.path(day, profile) {
+            Some(path) => fs::read_to_string(&path).map_err(|error| {
+                io::Error::new(error.kind(), format!("{}: {}", path.display(), error))
+            }),
+            None => fixture(day, "example")
+                .map(|fixture| fixture.input.to_string())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("no example for day {}", day),
+                    )
+                }),
+        }
+    }
+
+    /// Reads the input of the day's part in the profile, which for the examples is the one
+    /// giving the answer to the part.
+    pub fn read_part(&self, day: u8, part: u8, profile: &str) -> io::Result<String> {
+        if profile != EXAMPLE_PROFILE {
+            return self.read(day, profile);
+        }
+
+        part_fixture(day, part)
+            .map(|fixture| fixture.input.to_string())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no example for day {} part {}", day, part),
+                )
+            })
+    }
+
+    /// The days with an input in the profile, which for the examples is an example giving the
+    /// answer to one of the parts.
+    pub fn days(&self, profile: &str) -> Vec<u8> {
+        (1..=25)
+            .filter(|&day| match self.path(day, profile) {
+                Some(path) => path.is_file(),
+                None => (1..=2).any(|part| part_fixture(day, part).is_some()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn fixture_answers() {
+        // The long simulations of their examples are checked by their own tests.
+        let slow = [(15, 2), (23, 2)];
+
+        for fixture in &FIXTURES {
+            for (part, answer) in (1..=2).zip(fixture.answers) {
+                if let Some(answer) = answer.filter(|_| !slow.contains(&(fixture.day, part))) {
+                    let solver = registry().find(fixture.day, part, None).unwrap();
+
+                    assert_eq!(
+                        solver.run(fixture.input).unwrap(),
+                        answer,
+                        "day {} part {} {}",
+                        fixture.day,
+                        part,
+                        fixture.name
+                    );
+                }
+            }
+        }
+
+        assert_eq!(example(7, "part2").lines().count(), 7);
+        assert!(fixture(7, "part3").is_none());
+    }
+
+    #[test]
+    fn profiles() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(dir.join("bob")).unwrap();
+        fs::create_dir_all(dir.join("empty")).unwrap();
+        fs::write(dir.join("day1.txt"), "1\n").unwrap();
+        fs::write(dir.join("bob").join("day2.txt"), "1-3 a: abcde\n").unwrap();
+
+        let inputs = Inputs::new(&dir);
+        let profiles = inputs.profiles();
+        let bob = inputs.read(2, "bob");
+        let bob_part = inputs.read_part(2, 1, "bob");
+        let missing = inputs.read(1, "bob");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(profiles, vec!["default", "bob", "example"]);
+        assert_eq!(bob.unwrap(), "1-3 a: abcde\n");
+        assert_eq!(bob_part.unwrap(), "1-3 a: abcde\n");
+        assert_eq!(missing.unwrap_err().kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            inputs.read(3, EXAMPLE_PROFILE).unwrap(),
+            example(3, "example")
+        );
+        assert_eq!(
+            inputs.read_part(14, 2, EXAMPLE_PROFILE).unwrap(),
+            example(14, "part2")
+        );
+        assert_eq!(
+            inputs.read_part(19, 1, EXAMPLE_PROFILE).unwrap(),
+            example(19, "example")
+        );
+        assert_eq!(
+            inputs.read_part(9, 1, EXAMPLE_PROFILE).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(inputs.days(EXAMPLE_PROFILE).len(), 24);
+        assert_eq!(inputs.path(4, "default"), Some(dir.join("day4.txt")));
+    }
+}
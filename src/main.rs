@@ -1,3 +1,45 @@
-use aoc_runner_derive::aoc_main;
+//! Command line runner for the solutions, independent of cargo-aoc's input layout.
 
-aoc_main! { lib = advent_of_code_2020 }
+#[macro_use]
+mod cli;
+
+use cli::options::{parse_args, Command, USAGE};
+use cli::{all, animate, audit, bench, diff, export, generate, list, parse, run, verify};
+use std::{env, process};
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        outln!("{}", USAGE);
+        return;
+    }
+
+    let options = parse_args(args).unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let result = match options.command {
+        Command::Run => run::run(options.day.unwrap(), &options),
+        Command::Bench => bench::bench(&options),
+        Command::All => all::all(&options),
+        Command::Diff => diff::diff(options.day.unwrap(), &options),
+        Command::Verify => verify::verify(&options),
+        Command::Animate => animate::animate(options.day.unwrap(), &options),
+        Command::Export => export::export(options.day.unwrap(), &options),
+        Command::Parse => parse::parse(options.day.unwrap(), &options),
+        Command::Audit => audit::audit(&options),
+        Command::Generate => generate::generate(options.day.unwrap(), &options),
+        Command::List => list::list(&options),
+    };
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
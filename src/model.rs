@@ -0,0 +1,311 @@
+//! The parsed models of the puzzle inputs, as JSON for other tools to consume or in a compact
+//! binary form, and an on-disk cache of them keyed by input hash to skip parsing.
+
+use crate::answers::input_hash;
+use crate::parsing::ParseError;
+use crate::solver::{self, Metadata, Parsed, Solver};
+use crate::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::{type_name, Any};
+use std::error;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Serialises the maps with keys JSON cannot have, such as vectors, as lists of pairs.
+pub mod pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    Json,
+    Binary,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    Binary(bincode::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Json(error) => write!(f, "invalid JSON model: {}", error),
+            Error::Binary(error) => write!(f, "invalid binary model: {}", error),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// The parsing and serialisation of the model of a day.
+pub trait Codec: Send + Sync {
+    fn day(&self) -> u8;
+
+    fn parse(&self, input: &str) -> Result<Parsed, ParseError>;
+
+    /// Serialises a model parsed by this codec, or by a solver of the same day.
+    fn encode(&self, parsed: &Parsed, encoding: Encoding) -> Result<Vec<u8>, Error>;
+
+    fn decode(&self, bytes: &[u8], encoding: Encoding) -> Result<Parsed, Error>;
+}
+
+struct Model<I> {
+    day: u8,
+    parse: fn(&str) -> Result<I, ParseError>,
+}
+
+impl<I> Codec for Model<I>
+where
+    I: Serialize + DeserializeOwned + Any + Send + Sync,
+{
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+        Ok(Box::new((self.parse)(input)?))
+    }
+
+    fn encode(&self, parsed: &Parsed, encoding: Encoding) -> Result<Vec<u8>, Error> {
+        let model = parsed
+            .downcast_ref::<I>()
+            .unwrap_or_else(|| panic!("day {} expects {}", self.day, type_name::<I>()));
+
+        match encoding {
+            Encoding::Json => serde_json::to_vec(model).map_err(Error::Json),
+            Encoding::Binary => bincode::serialize(model).map_err(Error::Binary),
+        }
+    }
+
+    fn decode(&self, bytes: &[u8], encoding: Encoding) -> Result<Parsed, Error> {
+        Ok(match encoding {
+            Encoding::Json => Box::new(serde_json::from_slice::<I>(bytes).map_err(Error::Json)?),
+            Encoding::Binary => Box::new(bincode::deserialize::<I>(bytes).map_err(Error::Binary)?),
+        })
+    }
+}
+
+/// The codec of the day's model, the one its solvers parse the input into.
+pub fn codec(day: u8) -> Option<Box<dyn Codec>> {
+    fn model<I>(day: u8, parse: fn(&str) -> Result<I, ParseError>) -> Option<Box<dyn Codec>>
+    where
+        I: Serialize + DeserializeOwned + Any + Send + Sync,
+    {
+        Some(Box::new(Model { day, parse }))
+    }
+
+    match day {
+        1 => model(day, day1::parse),
+        2 => model(day, day2::parse),
+        3 => model(day, day3::parse),
+        4 => model(day, day4::parse),
+        5 => model(day, day5::parse),
+        6 => model(day, day6::parse),
+        7 => model(day, day7::parse),
+        8 => model(day, day8::parse),
+        9 => model(day, day9::parse),
+        10 => model(day, day10::parse),
+        11 => model(day, day11::parse),
+        12 => model(day, day12::parse),
+        13 => model(day, day13::parse),
+        14 => model(day, day14::parse),
+        15 => model(day, day15::parse),
+        16 => model(day, day16::parse),
+        17 => model(day, day17::parse),
+        18 => model(day, day18::parse),
+        19 => model(day, day19::parse),
+        20 => model(day, day20::parse),
+        21 => model(day, day21::parse),
+        22 => model(day, day22::parse),
+        23 => model(day, day23::parse),
+        24 => model(day, day24::parse),
+        25 => model(day, day25::parse),
+        _ => None,
+    }
+}
+
+/// The binary models of the inputs already parsed, saved in a directory as
+/// `day<N>-<version>-<input hash>.bin`.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Cache { dir: dir.into() }
+    }
+
+    /// The file of the model of the input, which changes with the version of the crate as the
+    /// models may.
+    pub fn path(&self, day: u8, input: &str) -> PathBuf {
+        self.dir.join(format!(
+            "day{}-{}-{:016x}.bin",
+            day,
+            env!("CARGO_PKG_VERSION"),
+            input_hash(input)
+        ))
+    }
+
+    /// The model of the input, read from the cache if it is there, else parsed and saved. The
+    /// cache only saves time: a model that cannot be read is parsed again, and one that cannot
+    /// be saved is not.
+    pub fn parse(&self, codec: &dyn Codec, input: &str) -> Result<Parsed, ParseError> {
+        let path = self.path(codec.day(), input);
+
+        if let Some(parsed) = fs::read(&path)
+            .ok()
+            .and_then(|bytes| codec.decode(&bytes, Encoding::Binary).ok())
+        {
+            return Ok(parsed);
+        }
+
+        let parsed = codec.parse(input)?;
+
+        if let Ok(bytes) = codec.encode(&parsed, Encoding::Binary) {
+            let _ = fs::create_dir_all(&self.dir).and_then(|()| fs::write(&path, bytes));
+        }
+
+        Ok(parsed)
+    }
+}
+
+struct Cached {
+    solver: &'static dyn Solver,
+    codec: Box<dyn Codec>,
+    cache: Arc<Cache>,
+}
+
+impl Solver for Cached {
+    fn metadata(&self) -> &Metadata {
+        self.solver.metadata()
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+        self.cache.parse(self.codec.as_ref(), input)
+    }
+
+    fn solve(&self, parsed: &Parsed) -> Result<String, solver::Error> {
+        self.solver.solve(parsed)
+    }
+}
+
+/// The solver, parsing its input through the cache.
+pub fn cached(solver: &'static dyn Solver, cache: &Arc<Cache>) -> Box<dyn Solver> {
+    Box::new(Cached {
+        solver,
+        codec: codec(solver.metadata().day).unwrap(),
+        cache: cache.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::FIXTURES;
+
+    #[test]
+    fn round_trips() {
+        // The long simulations of their examples are checked by their own tests.
+        let slow = [(15, 2), (23, 2)];
+
+        for fixture in &FIXTURES {
+            let codec = codec(fixture.day).unwrap();
+            let parsed = codec.parse(fixture.input).unwrap();
+
+            for encoding in [Encoding::Json, Encoding::Binary] {
+                let bytes = codec.encode(&parsed, encoding).unwrap();
+                let decoded = codec.decode(&bytes, encoding).unwrap();
+
+                for (part, answer) in (1..=2).zip(fixture.answers) {
+                    if let Some(answer) = answer.filter(|_| !slow.contains(&(fixture.day, part))) {
+                        for solver in registry().variants(fixture.day, part) {
+                            assert_eq!(
+                                solver.solve(&decoded).unwrap(),
+                                answer,
+                                "{} {} {:?}",
+                                solver.metadata().name,
+                                fixture.name,
+                                encoding
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        assert!(codec(26).is_none());
+        assert!(codec(7).unwrap().decode(b"{}", Encoding::Json).is_ok());
+        assert!(codec(7).unwrap().decode(b"[]", Encoding::Json).is_err());
+        assert!(codec(3).unwrap().decode(&[1], Encoding::Binary).is_err());
+    }
+
+    #[test]
+    fn json() {
+        let codec = codec(7).unwrap();
+        let parsed = codec
+            .parse("bright white bags contain 1 shiny gold bag.")
+            .unwrap();
+
+        assert_eq!(
+            codec.encode(&parsed, Encoding::Json).unwrap(),
+            br#"{"bright white":{"shiny gold":1}}"#
+        );
+        assert!(codec
+            .decode(br#"{"bright white":{"gold":1}}"#, Encoding::Json)
+            .is_err());
+    }
+
+    #[test]
+    fn cache() {
+        let dir = std::env::temp_dir().join(format!("aoc-model-{}", std::process::id()));
+        let cache = Arc::new(Cache::new(&dir));
+        let solver = cached(registry().find(20, 1, None).unwrap(), &cache);
+        let input = inputs::example(20, "example");
+        let path = cache.path(20, input);
+
+        let parsed = solver.run(input);
+        let saved = path.is_file();
+        // The model read from the cache is used as is, whatever the input.
+        fs::copy(&path, cache.path(20, "unparsable")).unwrap();
+        let reused = solver.run("unparsable");
+        fs::write(&path, b"corrupt").unwrap();
+        let reparsed = solver.run(input);
+        let invalid = solver.run("Tile 1:\n#");
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(parsed.unwrap(), "20899048083289");
+        assert!(saved);
+        assert_eq!(reused.unwrap(), "20899048083289");
+        assert_eq!(reparsed.unwrap(), "20899048083289");
+        assert!(invalid.is_err());
+        assert_eq!(solver.metadata().name, "day20::part1");
+    }
+}
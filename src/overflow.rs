@@ -0,0 +1,21 @@
+//! The error of the computations whose answers can outgrow even 128-bit integers on large
+//! generated inputs, rather than wrap around silently.
+
+use std::error;
+use std::fmt;
+
+/// An answer too large for 128 bits, naming the value that overflowed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Overflow(pub &'static str);
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arithmetic overflow: {} does not fit in 128 bits",
+            self.0
+        )
+    }
+}
+
+impl error::Error for Overflow {}
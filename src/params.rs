@@ -0,0 +1,297 @@
+//! The parameters of the puzzles, the actual puzzle values by default, so that the solvers can
+//! answer what-if variants of them.
+
+use crate::parsing::ParseError;
+use crate::solver::{solution, Answer, Solver};
+use crate::*;
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Params {
+    pub day1: day1::Params,
+    pub day3: day3::Params,
+    pub day7: day7::Params,
+    pub day9: day9::Params,
+    pub day14: day14::Params,
+    pub day15: day15::Params,
+    pub day17: day17::Params,
+    pub day23: day23::Params,
+    pub day24: day24::Params,
+}
+
+/// The name of every parameter, as `day<N>.<name>`.
+pub const KEYS: [&str; 12] = [
+    "day1.target",
+    "day3.slope",
+    "day7.target",
+    "day9.preamble",
+    "day14.word_size",
+    "day15.part1_turns",
+    "day15.part2_turns",
+    "day17.cycles",
+    "day23.part1_moves",
+    "day23.part2_moves",
+    "day23.part2_cups",
+    "day24.days",
+];
+
+fn number<T>(key: &str, value: &str, min: T, max: Option<T>) -> Result<T, String>
+where
+    T: Copy + FromStr + PartialOrd + fmt::Display,
+{
+    value
+        .parse()
+        .ok()
+        .filter(|number| *number >= min && max.is_none_or(|max| *number <= max))
+        .ok_or_else(|| match max {
+            Some(max) => format!("{} must be a number from {} to {}", key, min, max),
+            None => format!("{} must be a number from {}", key, min),
+        })
+}
+
+/// A slope of `<right>,<down>` steps, going down at least one row and to the left for a
+/// negative `<right>`.
+fn slope(key: &str, value: &str) -> Result<day3::Slope, String> {
+    let error = || format!("{} must be <right>,<down> with <down> from 1", key);
+    let (right, down) = value.split_once(',').ok_or_else(error)?;
+
+    Ok((
+        number(key, right.trim(), i32::MIN, None).map_err(|_| error())?,
+        number(key, down.trim(), 1, None).map_err(|_| error())?,
+    )
+        .into())
+}
+
+impl Params {
+    /// Sets the parameter named `key`, one of [`KEYS`], from its text.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+
+        match key {
+            "day1.target" => self.day1.target = number(key, value, 0, None)?,
+            "day3.slope" => self.day3.slope = slope(key, value)?,
+            "day7.target" if value.split_whitespace().count() == 2 => {
+                self.day7.target = value.to_string()
+            }
+            "day7.target" => return Err(format!("{} must be a shade and a color", key)),
+            "day9.preamble" => self.day9.preamble = number(key, value, 2, None)?,
+            "day14.word_size" => self.day14.word_size = number(key, value, 1, Some(63))?,
+            "day15.part1_turns" => self.day15.part1_turns = number(key, value, 1, Some(1 << 30))?,
+            "day15.part2_turns" => self.day15.part2_turns = number(key, value, 1, Some(1 << 30))?,
+            "day17.cycles" => self.day17.cycles = number(key, value, 0, None)?,
+            "day23.part1_moves" => self.day23.part1_moves = number(key, value, 0, None)?,
+            "day23.part2_moves" => self.day23.part2_moves = number(key, value, 0, None)?,
+            "day23.part2_cups" => self.day23.part2_cups = number(key, value, 10, Some(1 << 31))?,
+            "day24.days" => self.day24.days = number(key, value, 0, None)?,
+            _ => return Err(format!("unknown parameter {:?}", key)),
+        }
+
+        Ok(())
+    }
+
+    /// Parses a config file of `<key> = <value>` lines, the other parameters keeping their
+    /// default value. Blank lines and lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut params = Params::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected <key> = <value>", i + 1))?;
+
+            params
+                .set(key.trim(), value)
+                .map_err(|error| format!("line {}: {}", i + 1, error))?;
+        }
+
+        Ok(params)
+    }
+}
+
+fn parameterised<I, O>(
+    (day, part): (u8, u8),
+    params: &Arc<Params>,
+    parse: fn(&str, &Params) -> Result<I, ParseError>,
+    solve: fn(&I, &Params) -> O,
+) -> Option<Box<dyn Solver>>
+where
+    I: Any + Send + Sync,
+    O: Answer + 'static,
+{
+    let metadata = registry().find(day, part, None)?.metadata();
+    let (parse_params, solve_params) = (params.clone(), params.clone());
+
+    Some(solution(
+        (day, part, None),
+        metadata.name,
+        metadata.complexity,
+        move |input| parse(input, &parse_params),
+        move |parsed| solve(parsed, &solve_params),
+    ))
+}
+
+/// The main solution of the part with `params` instead of the puzzle's, or `None` if the part
+/// has no parameters.
+pub fn solver(day: u8, part: u8, params: &Params) -> Option<Box<dyn Solver>> {
+    let params = &Arc::new(params.clone());
+
+    match (day, part) {
+        (1, 1) => parameterised(
+            (1, 1),
+            params,
+            |input, _| day1::parse(input),
+            |parsed, params| day1::part1_with(parsed, &params.day1),
+        ),
+        (1, 2) => parameterised(
+            (1, 2),
+            params,
+            |input, _| day1::parse(input),
+            |parsed, params| day1::part2_with(parsed, &params.day1),
+        ),
+        (3, 1) => parameterised(
+            (3, 1),
+            params,
+            |input, _| day3::parse(input),
+            |parsed, params| day3::part1_with(parsed, &params.day3),
+        ),
+        (7, 1) => parameterised(
+            (7, 1),
+            params,
+            |input, _| day7::parse(input),
+            |parsed, params| day7::part1_with(parsed, &params.day7),
+        ),
+        (7, 2) => parameterised(
+            (7, 2),
+            params,
+            |input, _| day7::parse(input),
+            |parsed, params| day7::part2_with(parsed, &params.day7),
+        ),
+        (9, 1) => parameterised(
+            (9, 1),
+            params,
+            |input, _| day9::parse(input),
+            |parsed, params| day9::part1_with(parsed, &params.day9),
+        ),
+        (9, 2) => parameterised(
+            (9, 2),
+            params,
+            |input, _| day9::parse(input),
+            |parsed, params| day9::part2_with(parsed, &params.day9),
+        ),
+        (14, 1) => parameterised(
+            (14, 1),
+            params,
+            |input, params| day14::parse_with(input, &params.day14),
+            |parsed, params| day14::part1_with(parsed, &params.day14),
+        ),
+        (14, 2) => parameterised(
+            (14, 2),
+            params,
+            |input, params| day14::parse_with(input, &params.day14),
+            |parsed, params| day14::part2_with(parsed, &params.day14),
+        ),
+        (15, 1) => parameterised(
+            (15, 1),
+            params,
+            |input, _| day15::parse(input),
+            |parsed, params| day15::part1_with(parsed, &params.day15),
+        ),
+        (15, 2) => parameterised(
+            (15, 2),
+            params,
+            |input, _| day15::parse(input),
+            |parsed, params| day15::part2_with(parsed, &params.day15),
+        ),
+        (17, 1) => parameterised(
+            (17, 1),
+            params,
+            |input, _| day17::parse(input),
+            |parsed, params| day17::part1_with(parsed, &params.day17),
+        ),
+        (17, 2) => parameterised(
+            (17, 2),
+            params,
+            |input, _| day17::parse(input),
+            |parsed, params| day17::part2_with(parsed, &params.day17),
+        ),
+        (23, 1) => parameterised(
+            (23, 1),
+            params,
+            |input, _| day23::parse(input),
+            |parsed, params| day23::part1_with(parsed, &params.day23),
+        ),
+        (23, 2) => parameterised(
+            (23, 2),
+            params,
+            |input, _| day23::parse(input),
+            |parsed, params| day23::part2_with(parsed, &params.day23),
+        ),
+        (24, 2) => parameterised(
+            (24, 2),
+            params,
+            |input, _| day24::parse(input),
+            |parsed, params| day24::part2_with(parsed, &params.day24),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config() {
+        let params = Params::parse(
+            "# What if the preamble was shorter?\nday9.preamble = 5\n\nday7.target=dark olive\n",
+        )
+        .unwrap();
+
+        assert_eq!(params.day9.preamble, 5);
+        assert_eq!(params.day7.target, "dark olive");
+        assert_eq!(params.day15, day15::Params::default());
+        assert!(Params::parse("day9.preamble 5").is_err());
+        assert!(Params::parse("day9.preamble = 1").is_err());
+        assert!(Params::parse("day8.steps = 1").is_err());
+        assert!(Params::default().set("day7.target", "gold").is_err());
+        assert!(Params::default().set("day3.slope", "1,0").is_err());
+        assert!(Params::default().set("day3.slope", "-1,1").is_ok());
+        assert!(Params::default().set("day3.slope", "1,-1").is_err());
+
+        for key in KEYS {
+            let value = match key {
+                "day3.slope" => "1, 2",
+                "day7.target" => "light red",
+                _ => "20",
+            };
+            assert!(Params::default().set(key, value).is_ok(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn parameterised_solvers() {
+        let mut params = Params::default();
+        params.set("day9.preamble", "5").unwrap();
+        params.set("day1.target", "1020").unwrap();
+
+        let input = "35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127\n219";
+        let day9 = solver(9, 1, &params).unwrap();
+
+        assert_eq!(day9.run(input).unwrap(), "127");
+        assert_eq!(day9.metadata().name, "day9::part1");
+        assert_eq!(
+            solver(1, 1, &params).unwrap().run("979\n41").unwrap(),
+            "40139"
+        );
+        assert!(solver(2, 1, &params).is_none());
+    }
+}
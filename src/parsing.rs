@@ -0,0 +1,199 @@
+//! Errors reported by the puzzle input parsers.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+/// A malformed puzzle input, located by its 1-based line and column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.reason
+        )?;
+
+        if self.token.is_empty() {
+            write!(f, " (found nothing)")
+        } else {
+            write!(f, " (found {:?})", self.token)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// An input that could not be read, or parsed, while streaming it.
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "cannot read input: {}", error),
+            StreamError::Parse(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> Self {
+        StreamError::Io(error)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(error: ParseError) -> Self {
+        StreamError::Parse(error)
+    }
+}
+
+/// Parses the input read from `reader` one line at a time, locating the errors in the whole
+/// input.
+pub(crate) fn parse_lines<T>(
+    day: u8,
+    reader: impl BufRead,
+    mut parse: impl FnMut(&Source, &str) -> Result<T, ParseError>,
+) -> impl Iterator<Item = Result<T, StreamError>> {
+    reader.lines().enumerate().map(move |(i, line)| {
+        let line = line?;
+
+        parse(&Source::new(day, &line), &line).map_err(|error| {
+            ParseError {
+                line: i + 1,
+                ..error
+            }
+            .into()
+        })
+    })
+}
+
+/// The error for a streamed input ending before line `line`.
+pub(crate) fn missing(day: u8, line: usize, expected: &str) -> StreamError {
+    StreamError::Parse(ParseError {
+        day,
+        line,
+        column: 1,
+        token: String::new(),
+        reason: format!("expected {}", expected),
+    })
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct Source<'a> {
+    day: u8,
+    text: &'a str,
+}
+
+impl<'a> Source<'a> {
+    pub(crate) fn new(day: u8, text: &'a str) -> Self {
+        Source { day, text }
+    }
+
+    fn locate(&self, token: &str) -> (usize, usize) {
+        let start = self.text.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize)
+            .saturating_sub(start)
+            .min(self.text.len());
+        let before = self.text.get(..offset).unwrap_or("");
+
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+
+        (line, column)
+    }
+
+    pub(crate) fn error(&self, token: &str, reason: impl Into<String>) -> ParseError {
+        let (line, column) = self.locate(token);
+
+        ParseError {
+            day: self.day,
+            line,
+            column,
+            token: token.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn parse<T>(&self, token: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        token
+            .parse()
+            .map_err(|error| self.error(token, format!("{}", error)))
+    }
+
+    pub(crate) fn next<'b>(
+        &self,
+        tokens: &mut impl Iterator<Item = &'b str>,
+        within: &'b str,
+        expected: &str,
+    ) -> Result<&'b str, ParseError> {
+        tokens
+            .next()
+            .ok_or_else(|| self.error(&within[within.len()..], format!("expected {}", expected)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_position() {
+        let text = "12\n34 5x\n";
+        let source = Source::new(1, text);
+
+        let error = source.parse::<u8>(&text[6..8]).unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.token, "5x");
+        assert_eq!(
+            error.to_string(),
+            "day 1, line 2, column 4: invalid digit found in string (found \"5x\")"
+        );
+
+        let line = &text[3..8];
+        let mut tokens = line.split_whitespace().skip(2);
+        let error = source.next(&mut tokens, line, "a number").unwrap_err();
+
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!(
+            error.to_string(),
+            "day 1, line 2, column 6: expected a number (found nothing)"
+        );
+    }
+
+    #[test]
+    fn streamed_error_position() {
+        let mut lines = parse_lines(1, "12\n34 5x\n".as_bytes(), |source, line| {
+            source.parse::<u8>(line.rsplit(' ').next().unwrap())
+        });
+
+        assert_eq!(lines.next().unwrap().unwrap(), 12);
+
+        match lines.next().unwrap() {
+            Err(StreamError::Parse(error)) => assert_eq!((error.line, error.column), (2, 4)),
+            _ => panic!("expected a parse error"),
+        }
+
+        assert!(lines.next().is_none());
+    }
+}
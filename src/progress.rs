@@ -0,0 +1,252 @@
+//! Progress reports, cancellation and budgets for the long simulations of days 15, 23 and 25,
+//! which otherwise run for seconds with no feedback.
+
+use crate::params::Params;
+use crate::solver::{Answer, Error};
+use crate::{day15, day23, day25};
+use std::error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The number of steps between two checks of the progress, the cancellation and the time.
+const CHECK_INTERVAL: u64 = 1 << 16;
+
+/// A token cancelling the runs monitoring it, from any thread.
+#[derive(Clone, Debug, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn new() -> Self {
+        Cancel::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The most steps and time a run may take, unlimited by default.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Budget {
+    pub steps: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+/// Why a monitored run stopped before its end.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Interrupted {
+    Cancelled,
+    StepBudgetExceeded(u64),
+    TimeBudgetExceeded(Duration),
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interrupted::Cancelled => write!(f, "cancelled"),
+            Interrupted::StepBudgetExceeded(steps) => {
+                write!(f, "budget exceeded: more than {} steps", steps)
+            }
+            Interrupted::TimeBudgetExceeded(time) => {
+                write!(f, "budget exceeded: more than {:?}", time)
+            }
+        }
+    }
+}
+
+impl error::Error for Interrupted {}
+
+/// Watches over a run: reports the fraction of it completed, and interrupts it once cancelled
+/// or over budget. The default monitor does neither.
+#[derive(Default)]
+pub struct Monitor<'a> {
+    on_progress: Option<Box<dyn FnMut(f64) + 'a>>,
+    cancel: Option<Cancel>,
+    budget: Budget,
+    started: Option<Instant>,
+    next_check: u64,
+}
+
+impl<'a> Monitor<'a> {
+    pub fn new() -> Self {
+        Monitor::default()
+    }
+
+    /// Calls `on_progress` with the fraction of the run completed, from 0 to 1, every few
+    /// thousand steps.
+    pub fn with_progress(mut self, on_progress: impl FnMut(f64) + 'a) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: Cancel) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Called before every step of a run of `total` steps, `done` of which are done. Only
+    /// compares two numbers but every few thousand steps.
+    #[inline]
+    pub fn tick(&mut self, done: u64, total: u64) -> Result<(), Interrupted> {
+        if done < self.next_check {
+            Ok(())
+        } else {
+            self.check(done, total)
+        }
+    }
+
+    fn check(&mut self, done: u64, total: u64) -> Result<(), Interrupted> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+
+        if self.cancel.as_ref().is_some_and(Cancel::is_cancelled) {
+            return Err(Interrupted::Cancelled);
+        }
+
+        if let Some(steps) = self.budget.steps.filter(|&steps| done >= steps) {
+            return Err(Interrupted::StepBudgetExceeded(steps));
+        }
+
+        if let Some(time) = self.budget.time.filter(|&time| started.elapsed() > time) {
+            return Err(Interrupted::TimeBudgetExceeded(time));
+        }
+
+        if let Some(on_progress) = &mut self.on_progress {
+            on_progress(done as f64 / total.max(1) as f64);
+        }
+
+        self.next_check = (done + CHECK_INTERVAL).min(self.budget.steps.unwrap_or(u64::MAX));
+        Ok(())
+    }
+
+    /// Reports the end of the run.
+    pub fn finish(&mut self) {
+        if let Some(on_progress) = &mut self.on_progress {
+            on_progress(1.0);
+        }
+    }
+}
+
+/// The parts with a monitored solver.
+pub const PARTS: [(u8, u8); 4] = [(15, 1), (15, 2), (23, 2), (25, 1)];
+
+/// Solves the part with its main solution and `params`, watched over by `monitor`, or returns
+/// `None` if it has no monitored solver.
+pub fn solve(
+    day: u8,
+    part: u8,
+    input: &str,
+    params: &Params,
+    monitor: &mut Monitor,
+) -> Option<Result<String, Error>> {
+    fn answer<I, O: Answer>(
+        parsed: Result<I, crate::ParseError>,
+        solve: impl FnOnce(&I) -> Result<O, Interrupted>,
+    ) -> Result<String, Error> {
+        solve(&parsed?)?.into_answer()
+    }
+
+    Some(match (day, part) {
+        (15, 1) => answer(day15::parse(input), |numbers| {
+            day15::part1_monitored(numbers, &params.day15, monitor)
+        }),
+        (15, 2) => answer(day15::parse(input), |numbers| {
+            day15::part2_monitored(numbers, &params.day15, monitor)
+        }),
+        (23, 2) => answer(day23::parse(input), |cups| {
+            day23::part2_monitored(cups, &params.day23, monitor)
+        }),
+        (25, 1) => answer(day25::parse(input), |public_keys| {
+            day25::part1_monitored(public_keys, monitor)
+        }),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budgets() {
+        let mut monitor = Monitor::new().with_budget(Budget {
+            steps: Some(100_000),
+            time: None,
+        });
+
+        assert!((0..100_000).all(|done| monitor.tick(done, 200_000).is_ok()));
+        assert_eq!(
+            monitor.tick(100_000, 200_000),
+            Err(Interrupted::StepBudgetExceeded(100_000))
+        );
+
+        let mut monitor = Monitor::new().with_budget(Budget {
+            steps: None,
+            time: Some(Duration::from_millis(10)),
+        });
+        monitor.tick(0, 1).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(
+            monitor.tick(CHECK_INTERVAL, 1),
+            Err(Interrupted::TimeBudgetExceeded(Duration::from_millis(10)))
+        );
+    }
+
+    #[test]
+    fn progress_and_cancellation() {
+        let mut fractions = Vec::new();
+        let cancel = Cancel::new();
+        let mut monitor = Monitor::new()
+            .with_progress(|fraction| fractions.push(fraction))
+            .with_cancel(cancel.clone());
+
+        for done in 0..4 * CHECK_INTERVAL {
+            monitor.tick(done, 4 * CHECK_INTERVAL).unwrap();
+        }
+
+        monitor.finish();
+        cancel.cancel();
+
+        assert_eq!(monitor.tick(0, 1), Ok(()));
+        assert_eq!(
+            monitor.tick(4 * CHECK_INTERVAL, 1),
+            Err(Interrupted::Cancelled)
+        );
+        drop(monitor);
+        assert_eq!(fractions, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn monitored_answers() {
+        let params = Params::default();
+
+        assert_eq!(
+            solve(15, 1, "0,3,6", &params, &mut Monitor::new())
+                .unwrap()
+                .unwrap(),
+            "436"
+        );
+
+        let mut monitor = Monitor::new().with_budget(Budget {
+            steps: Some(1_000),
+            time: None,
+        });
+
+        assert!(matches!(
+            solve(23, 2, "389125467", &params, &mut monitor).unwrap(),
+            Err(Error::Interrupted(Interrupted::StepBudgetExceeded(1_000)))
+        ));
+        assert!(solve(8, 1, "nop +0", &params, &mut Monitor::new()).is_none());
+    }
+}
@@ -0,0 +1,554 @@
+//! A runtime registry of every solver, independent of cargo-aoc's code generation.
+
+use crate::day25::UnreachableKey;
+use crate::overflow::Overflow;
+use crate::parsing::{ParseError, StreamError};
+use crate::progress::Interrupted;
+use crate::*;
+use lazy_static::lazy_static;
+use std::any::{type_name, Any};
+use std::error;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+
+/// A parsed puzzle input, as produced by [`Solver::parse`].
+pub type Parsed = Box<dyn Any + Send + Sync>;
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(ParseError),
+    Io(io::Error),
+    NoSolution,
+    Interrupted(Interrupted),
+    Overflow(Overflow),
+    UnreachableKey(UnreachableKey),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(error) => error.fmt(f),
+            Error::Io(error) => write!(f, "cannot read input: {}", error),
+            Error::NoSolution => write!(f, "the input has no solution"),
+            Error::Interrupted(interrupted) => interrupted.fmt(f),
+            Error::Overflow(overflow) => overflow.fmt(f),
+            Error::UnreachableKey(unreachable) => unreachable.fmt(f),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl From<Interrupted> for Error {
+    fn from(interrupted: Interrupted) -> Self {
+        Error::Interrupted(interrupted)
+    }
+}
+
+impl From<Overflow> for Error {
+    fn from(overflow: Overflow) -> Self {
+        Error::Overflow(overflow)
+    }
+}
+
+impl From<UnreachableKey> for Error {
+    fn from(unreachable: UnreachableKey) -> Self {
+        Error::UnreachableKey(unreachable)
+    }
+}
+
+impl From<StreamError> for Error {
+    fn from(error: StreamError) -> Self {
+        match error {
+            StreamError::Io(error) => Error::Io(error),
+            StreamError::Parse(error) => Error::Parse(error),
+        }
+    }
+}
+
+/// A value a solver can return as its answer: `None` when the input has no solution, and an
+/// error when it could not be solved.
+pub trait Answer {
+    fn into_answer(self) -> Result<String, Error>;
+}
+
+macro_rules! impl_answer {
+    ($($t:ty),*) => {
+        $(
+            impl Answer for $t {
+                fn into_answer(self) -> Result<String, Error> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_answer!(i32, i64, i128, isize, u64, u128, usize, String);
+
+impl<T: Answer> Answer for Option<T> {
+    fn into_answer(self) -> Result<String, Error> {
+        self.ok_or(Error::NoSolution)?.into_answer()
+    }
+}
+
+impl<T: Answer, E: Into<Error>> Answer for Result<T, E> {
+    fn into_answer(self) -> Result<String, Error> {
+        self.map_err(Into::into)?.into_answer()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    pub day: u8,
+    pub part: u8,
+    /// The cargo-aoc variant name, `None` for the main solution.
+    pub variant: Option<&'static str>,
+    pub name: &'static str,
+    pub complexity: &'static str,
+    pub input_type: &'static str,
+}
+
+impl Metadata {
+    pub fn variant_name(&self) -> &'static str {
+        self.variant.unwrap_or("default")
+    }
+}
+
+pub trait Solver: Send + Sync {
+    fn metadata(&self) -> &Metadata;
+
+    fn parse(&self, input: &str) -> Result<Parsed, ParseError>;
+
+    /// Solves an input parsed by this solver, or by another one of the same day.
+    fn solve(&self, parsed: &Parsed) -> Result<String, Error>;
+
+    fn run(&self, input: &str) -> Result<String, Error> {
+        self.solve(&self.parse(input)?)
+    }
+}
+
+struct Solution<I, P, S> {
+    metadata: Metadata,
+    parse: P,
+    solve: S,
+    input: PhantomData<fn() -> I>,
+}
+
+impl<I, O, P, S> Solver for Solution<I, P, S>
+where
+    I: Any + Send + Sync,
+    O: Answer,
+    P: Fn(&str) -> Result<I, ParseError> + Send + Sync,
+    S: Fn(&I) -> O + Send + Sync,
+{
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+        Ok(Box::new((self.parse)(input)?))
+    }
+
+    fn solve(&self, parsed: &Parsed) -> Result<String, Error> {
+        let input = parsed
+            .downcast_ref::<I>()
+            .unwrap_or_else(|| panic!("{} expects {}", self.metadata.name, type_name::<I>()));
+
+        (self.solve)(input).into_answer()
+    }
+}
+
+pub(crate) fn solution<I, O, P, S>(
+    (day, part, variant): (u8, u8, Option<&'static str>),
+    name: &'static str,
+    complexity: &'static str,
+    parse: P,
+    solve: S,
+) -> Box<dyn Solver>
+where
+    I: Any + Send + Sync,
+    O: Answer + 'static,
+    P: Fn(&str) -> Result<I, ParseError> + Send + Sync + 'static,
+    S: Fn(&I) -> O + Send + Sync + 'static,
+{
+    Box::new(Solution {
+        metadata: Metadata {
+            day,
+            part,
+            variant,
+            name,
+            complexity,
+            input_type: type_name::<I>(),
+        },
+        parse,
+        solve,
+        input: PhantomData,
+    })
+}
+
+pub struct Registry(Vec<Box<dyn Solver>>);
+
+impl Registry {
+    pub fn all(&self) -> impl Iterator<Item = &dyn Solver> {
+        self.0.iter().map(|solver| solver.as_ref())
+    }
+
+    pub fn variants(&self, day: u8, part: u8) -> impl Iterator<Item = &dyn Solver> {
+        self.all().filter(move |solver| {
+            let metadata = solver.metadata();
+            metadata.day == day && metadata.part == part
+        })
+    }
+
+    pub fn find(&self, day: u8, part: u8, variant: Option<&str>) -> Option<&dyn Solver> {
+        self.variants(day, part)
+            .find(|solver| solver.metadata().variant == variant)
+    }
+}
+
+lazy_static! {
+    static ref REGISTRY: Registry = Registry(vec![
+        solution((1, 1, None), "day1::part1", "O(n)", day1::parse, |e| {
+            day1::part1(e)
+        }),
+        solution(
+            (1, 1, Some("BoolArray")),
+            "day1::part1_bool_array",
+            "O(n)",
+            day1::parse,
+            |e| day1::part1_bool_array(e)
+        ),
+        solution(
+            (1, 1, Some("Itertools")),
+            "day1::part1_itertools",
+            "O(n^2)",
+            day1::parse,
+            |e| day1::part1_itertools(e)
+        ),
+        solution(
+            (1, 1, Some("KSum")),
+            "day1::part1_k_sum",
+            "O(n)",
+            day1::parse,
+            |e| day1::part1_k_sum(e)
+        ),
+        solution((1, 2, None), "day1::part2", "O(n^2)", day1::parse, |e| {
+            day1::part2(e)
+        }),
+        solution(
+            (1, 2, Some("BoolArray")),
+            "day1::part2_bool_array",
+            "O(n^2)",
+            day1::parse,
+            |e| day1::part2_bool_array(e)
+        ),
+        solution(
+            (1, 2, Some("Itertools")),
+            "day1::part2_itertools",
+            "O(n^3)",
+            day1::parse,
+            |e| day1::part2_itertools(e)
+        ),
+        solution(
+            (1, 2, Some("KSum")),
+            "day1::part2_k_sum",
+            "O(n^2)",
+            day1::parse,
+            |e| day1::part2_k_sum(e)
+        ),
+        solution((2, 1, None), "day2::part1", "O(n)", day2::parse, |p| {
+            day2::part1(p)
+        }),
+        solution((2, 2, None), "day2::part2", "O(n)", day2::parse, |p| {
+            day2::part2(p)
+        }),
+        solution(
+            (3, 1, None),
+            "day3::part1",
+            "O(t)",
+            day3::parse,
+            day3::part1
+        ),
+        solution(
+            (3, 2, None),
+            "day3::part2",
+            "O(t)",
+            day3::parse,
+            day3::part2
+        ),
+        solution((4, 1, None), "day4::part1", "O(n)", day4::parse, |p| {
+            day4::part1(p)
+        }),
+        solution((4, 2, None), "day4::part2", "O(n)", day4::parse, |p| {
+            day4::part2(p)
+        }),
+        solution((5, 1, None), "day5::part1", "O(n)", day5::parse, |b| {
+            day5::part1(b)
+        }),
+        solution(
+            (5, 2, None),
+            "day5::part2",
+            "O(n log n)",
+            day5::parse,
+            |b| day5::part2(b)
+        ),
+        solution((6, 1, None), "day6::part1", "O(n)", day6::parse, |g| {
+            day6::part1(g)
+        }),
+        solution((6, 2, None), "day6::part2", "O(n)", day6::parse, |g| {
+            day6::part2(g)
+        }),
+        solution(
+            (7, 1, None),
+            "day7::part1",
+            "O(n^2)",
+            day7::parse,
+            day7::part1
+        ),
+        solution(
+            (7, 2, None),
+            "day7::part2",
+            "O(2^d)",
+            day7::parse,
+            day7::part2
+        ),
+        solution(
+            (8, 1, None),
+            "day8::part1",
+            "O(n)",
+            day8::parse,
+            day8::part1
+        ),
+        solution(
+            (8, 2, None),
+            "day8::part2",
+            "O(n^2)",
+            day8::parse,
+            day8::part2
+        ),
+        solution((9, 1, None), "day9::part1", "O(n*p^2)", day9::parse, |e| {
+            day9::part1(e)
+        }),
+        solution((9, 2, None), "day9::part2", "O(n^2)", day9::parse, |e| {
+            day9::part2(e)
+        }),
+        solution((10, 1, None), "day10::part1", "O(n)", day10::parse, |j| {
+            day10::part1(j)
+        }),
+        solution((10, 2, None), "day10::part2", "O(n)", day10::parse, |j| {
+            day10::part2(j)
+        }),
+        solution(
+            (11, 1, None),
+            "day11::part1",
+            "O(r*n)",
+            day11::parse,
+            day11::part1
+        ),
+        solution(
+            (11, 2, None),
+            "day11::part2",
+            "O(r*n*w)",
+            day11::parse,
+            day11::part2
+        ),
+        solution((12, 1, None), "day12::part1", "O(n)", day12::parse, |i| {
+            day12::part1(i)
+        }),
+        solution((12, 2, None), "day12::part2", "O(n)", day12::parse, |i| {
+            day12::part2(i)
+        }),
+        solution(
+            (13, 1, None),
+            "day13::part1",
+            "O(b)",
+            day13::parse,
+            day13::part1
+        ),
+        solution(
+            (13, 2, None),
+            "day13::part2",
+            "O(b log m)",
+            day13::parse,
+            day13::part2
+        ),
+        solution((14, 1, None), "day14::part1", "O(n)", day14::parse, |i| {
+            day14::part1(i)
+        }),
+        solution(
+            (14, 2, None),
+            "day14::part2",
+            "O(n*2^x)",
+            day14::parse,
+            |i| day14::part2(i)
+        ),
+        solution((15, 1, None), "day15::part1", "O(t)", day15::parse, |s| {
+            day15::part1(s)
+        }),
+        solution((15, 2, None), "day15::part2", "O(t)", day15::parse, |s| {
+            day15::part2(s)
+        }),
+        solution(
+            (16, 1, None),
+            "day16::part1",
+            "O(n*f)",
+            day16::parse,
+            day16::part1
+        ),
+        solution(
+            (16, 2, None),
+            "day16::part2",
+            "O(n*f^2)",
+            day16::parse,
+            day16::part2
+        ),
+        solution(
+            (17, 1, None),
+            "day17::part1",
+            "O(c*s*3^d)",
+            day17::parse,
+            |c| day17::part1(c)
+        ),
+        solution(
+            (17, 2, None),
+            "day17::part2",
+            "O(c*s*3^d)",
+            day17::parse,
+            |c| day17::part2(c)
+        ),
+        solution((18, 1, None), "day18::part1", "O(n)", day18::parse, |e| {
+            day18::part1(e)
+        }),
+        solution((18, 2, None), "day18::part2", "O(n)", day18::parse, |e| {
+            day18::part2(e)
+        }),
+        solution(
+            (19, 1, None),
+            "day19::part1",
+            "O(n*m)",
+            day19::parse,
+            day19::part1
+        ),
+        solution(
+            (19, 2, None),
+            "day19::part2",
+            "O(n*m)",
+            day19::parse,
+            day19::part2
+        ),
+        solution(
+            (20, 1, None),
+            "day20::part1",
+            "O(t)",
+            day20::parse,
+            day20::part1
+        ),
+        solution(
+            (20, 2, None),
+            "day20::part2",
+            "O(t*p)",
+            day20::parse,
+            day20::part2
+        ),
+        solution((21, 1, None), "day21::part1", "O(n*a)", day21::parse, |f| {
+            day21::part1(f)
+        }),
+        solution((21, 2, None), "day21::part2", "O(n*a)", day21::parse, |f| {
+            day21::part2(f)
+        }),
+        solution(
+            (22, 1, None),
+            "day22::part1",
+            "O(r)",
+            day22::parse,
+            day22::part1
+        ),
+        solution(
+            (22, 2, None),
+            "day22::part2",
+            "O(2^c)",
+            day22::parse,
+            day22::part2
+        ),
+        solution(
+            (22, 2, Some("Full")),
+            "day22::part2_full",
+            "O(2^c)",
+            day22::parse,
+            day22::part2_full
+        ),
+        solution((23, 1, None), "day23::part1", "O(m)", day23::parse, |c| {
+            day23::part1(c)
+        }),
+        solution((23, 2, None), "day23::part2", "O(m)", day23::parse, |c| {
+            day23::part2(c)
+        }),
+        solution((24, 1, None), "day24::part1", "O(n)", day24::parse, |d| {
+            day24::part1(d)
+        }),
+        solution((24, 2, None), "day24::part2", "O(d*t)", day24::parse, |d| {
+            day24::part2(d)
+        }),
+        solution((25, 1, None), "day25::part1", "O(l)", day25::parse, |k| {
+            day25::part1(k)
+        }),
+    ]);
+}
+
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::example;
+    use std::collections::HashSet;
+
+    #[test]
+    fn registry_covers_every_part() {
+        let parts: HashSet<_> = registry()
+            .all()
+            .map(|solver| (solver.metadata().day, solver.metadata().part))
+            .collect();
+
+        assert_eq!(parts.len(), 49);
+        assert_eq!(registry().variants(1, 1).count(), 4);
+        assert_eq!(registry().variants(1, 2).count(), 4);
+        assert_eq!(registry().variants(25, 2).count(), 0);
+    }
+
+    #[test]
+    fn variants_agree() {
+        let input = example(1, "example");
+
+        for part in 1..=2 {
+            let parsed = registry()
+                .find(1, part, None)
+                .unwrap()
+                .parse(input)
+                .unwrap();
+
+            let answers: HashSet<_> = registry()
+                .variants(1, part)
+                .map(|solver| solver.solve(&parsed).unwrap())
+                .collect();
+
+            assert_eq!(answers.len(), 1);
+        }
+
+        let solver = registry().find(1, 1, Some("Itertools")).unwrap();
+
+        assert_eq!(solver.run(input).unwrap(), "514579");
+        assert!(matches!(solver.run("1\n2"), Err(Error::NoSolution)));
+        assert!(matches!(solver.run("1\nx"), Err(Error::Parse(_))));
+    }
+}
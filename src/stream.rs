@@ -0,0 +1,60 @@
+//! Streaming solvers for the line-oriented days, which read their input from any `BufRead`
+//! one line at a time instead of parsing it all up front, and stop reading as soon as they
+//! know the answer.
+
+use crate::solver::{Answer, Error};
+use crate::{day1, day2, day25, day5, day9};
+use std::io::BufRead;
+
+/// The parts with a streaming solver.
+pub const PARTS: [(u8, u8); 8] = [
+    (1, 1),
+    (1, 2),
+    (2, 1),
+    (2, 2),
+    (5, 1),
+    (5, 2),
+    (9, 1),
+    (25, 1),
+];
+
+/// Solves the part with the input read from `reader`, or returns `None` if it has no
+/// streaming solver.
+pub fn solve(day: u8, part: u8, reader: impl BufRead) -> Option<Result<String, Error>> {
+    Some(match (day, part) {
+        (1, 1) => day1::part1_stream(reader).into_answer(),
+        (1, 2) => day1::part2_stream(reader).into_answer(),
+        (2, 1) => day2::part1_stream(reader).into_answer(),
+        (2, 2) => day2::part2_stream(reader).into_answer(),
+        (5, 1) => day5::part1_stream(reader).into_answer(),
+        (5, 2) => day5::part2_stream(reader).into_answer(),
+        (9, 1) => day9::part1_stream(reader).into_answer(),
+        (25, 1) => day25::part1_stream(reader).into_answer(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, registry};
+
+    #[test]
+    fn same_answers() {
+        for (day, part) in PARTS {
+            let size = (generate::puzzle_size(day) / 10).max(3);
+            let input = generate::input(day, 0, size);
+            let solver = registry().find(day, part, None).unwrap();
+
+            assert_eq!(
+                solve(day, part, input.as_bytes()).unwrap().ok(),
+                solver.run(&input).ok(),
+                "day {} part {}",
+                day,
+                part
+            );
+        }
+
+        assert!(solve(9, 2, "".as_bytes()).is_none());
+    }
+}
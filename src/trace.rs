@@ -0,0 +1,337 @@
+//! Structured events the solvers can report while solving, to inspect how an answer was
+//! reached without adding `println!`s.
+
+use crate::solver::{Answer, Error};
+use crate::{day11, day16, day21, day22, day8};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+/// A value of an event field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    List(Vec<Value>),
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Value::Int(value as i64)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i32, i64, isize, u8, usize);
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::List(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Value {
+    fn write_json(&self, json: &mut String) {
+        match self {
+            Value::Int(value) => write!(json, "{}", value).unwrap(),
+            Value::Bool(value) => write!(json, "{}", value).unwrap(),
+            Value::Str(value) => json.push_str(&json_string(value)),
+            Value::List(values) => {
+                json.push('[');
+
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        json.push(',');
+                    }
+
+                    value.write_json(json);
+                }
+
+                json.push(']');
+            }
+        }
+    }
+}
+
+/// A step of a solver, named and described by its fields in the order they were added.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub day: u8,
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, Value)>,
+}
+
+impl Event {
+    pub fn new(day: u8, name: &'static str) -> Self {
+        Event {
+            day,
+            name,
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, key: &'static str, value: impl Into<Value>) -> Self {
+        self.fields.push((key, value.into()));
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.fields
+            .iter()
+            .find(|(other, _)| *other == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The event as a single line JSON object, its day and name first.
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            "{{\"day\":{},\"event\":{}",
+            self.day,
+            json_string(self.name)
+        );
+
+        for (key, value) in &self.fields {
+            write!(json, ",{}:", json_string(key)).unwrap();
+            value.write_json(&mut json);
+        }
+
+        json.push('}');
+        json
+    }
+}
+
+/// The string as a JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// Where the traced solvers report their events.
+pub trait Tracer {
+    /// Whether the events are recorded at all, so that they need not be built otherwise.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn record(&mut self, event: Event);
+
+    /// Records the event built by `event`, only building it if the tracer is enabled.
+    fn trace(&mut self, event: impl FnOnce() -> Event)
+    where
+        Self: Sized,
+    {
+        if self.enabled() {
+            self.record(event());
+        }
+    }
+}
+
+impl<T: Tracer + ?Sized> Tracer for &mut T {
+    fn enabled(&self) -> bool {
+        (**self).enabled()
+    }
+
+    fn record(&mut self, event: Event) {
+        (**self).record(event);
+    }
+}
+
+/// The tracer of the untraced solvers, which compiles down to nothing.
+pub struct NoTrace;
+
+impl Tracer for NoTrace {
+    #[inline(always)]
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    #[inline(always)]
+    fn record(&mut self, _: Event) {}
+}
+
+impl Tracer for Vec<Event> {
+    fn record(&mut self, event: Event) {
+        self.push(event);
+    }
+}
+
+/// Writes each event as a line of JSON, keeping the first write error for
+/// [`JsonLines::finish`].
+pub struct JsonLines<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonLines<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLines {
+            writer,
+            error: None,
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.error {
+            Some(error) => Err(error),
+            None => self.writer.flush().map(|_| self.writer),
+        }
+    }
+}
+
+impl<W: Write> Tracer for JsonLines<W> {
+    fn enabled(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn record(&mut self, event: Event) {
+        if let Err(error) = writeln!(self.writer, "{}", event.to_json()) {
+            self.error = Some(error);
+        }
+    }
+}
+
+/// The parts with a traced solver.
+pub const PARTS: [(u8, u8); 9] = [
+    (8, 1),
+    (8, 2),
+    (11, 1),
+    (11, 2),
+    (16, 2),
+    (21, 1),
+    (21, 2),
+    (22, 1),
+    (22, 2),
+];
+
+/// Solves the part with its main solution, reporting its events to `tracer`, or returns `None`
+/// if it has no traced solver.
+pub fn solve(
+    day: u8,
+    part: u8,
+    input: &str,
+    mut tracer: &mut dyn Tracer,
+) -> Option<Result<String, Error>> {
+    fn answer<I, O: Answer>(
+        parsed: Result<I, crate::ParseError>,
+        solve: impl FnOnce(&I) -> O,
+    ) -> Result<String, Error> {
+        solve(&parsed?).into_answer()
+    }
+
+    let tracer = &mut tracer;
+
+    Some(match (day, part) {
+        (8, 1) => answer(day8::parse(input), |computer| {
+            day8::part1_traced(computer, tracer)
+        }),
+        (8, 2) => answer(day8::parse(input), |computer| {
+            day8::part2_traced(computer, tracer)
+        }),
+        (11, 1) => answer(day11::parse(input), |layout| {
+            day11::part1_traced(layout, tracer)
+        }),
+        (11, 2) => answer(day11::parse(input), |layout| {
+            day11::part2_traced(layout, tracer)
+        }),
+        (16, 2) => answer(day16::parse(input), |notes| {
+            day16::part2_traced(notes, tracer)
+        }),
+        (21, 1) => answer(day21::parse(input), |foods| {
+            day21::part1_traced(foods, tracer)
+        }),
+        (21, 2) => answer(day21::parse(input), |foods| {
+            day21::part2_traced(foods, tracer)
+        }),
+        (22, 1) => answer(day22::parse(input), |decks| {
+            day22::part1_traced(decks, tracer)
+        }),
+        (22, 2) => answer(day22::parse(input), |decks| {
+            day22::part2_traced(decks, tracer)
+        }),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lines() {
+        let mut tracer = JsonLines::new(Vec::new());
+
+        tracer.trace(|| {
+            Event::new(21, "candidates")
+                .with("allergen", "fish \"raw\"")
+                .with("ingredients", vec!["a", "b"])
+                .with("done", false)
+        });
+        tracer.trace(|| Event::new(8, "loop").with("accumulator", -3));
+
+        assert_eq!(
+            String::from_utf8(tracer.finish().unwrap()).unwrap(),
+            "{\"day\":21,\"event\":\"candidates\",\"allergen\":\"fish \\\"raw\\\"\",\
+             \"ingredients\":[\"a\",\"b\"],\"done\":false}\n\
+             {\"day\":8,\"event\":\"loop\",\"accumulator\":-3}\n"
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        let mut built = false;
+
+        NoTrace.trace(|| {
+            built = true;
+            Event::new(8, "step")
+        });
+
+        assert!(!built);
+    }
+
+    #[test]
+    fn traced_answers() {
+        let mut events = Vec::new();
+        let answer = solve(8, 1, "nop +0\nacc +1\njmp -2", &mut events);
+
+        assert_eq!(answer.unwrap().unwrap(), "1");
+        assert_eq!(events.last().unwrap().name, "loop");
+        assert!(solve(8, 1, "jump +1", &mut events).unwrap().is_err());
+        assert!(solve(1, 1, "1721", &mut events).is_none());
+    }
+}